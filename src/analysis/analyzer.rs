@@ -0,0 +1,124 @@
+use super::{CharFilter, FilteredText, Token, TokenFilter, TokenStream, Tokenizer};
+
+// Runs the char filters, the tokenizer and the token filters in order.
+pub struct Analyzer {
+    char_filters: Vec<Box<dyn CharFilter>>,
+    tokenizer: Box<dyn Tokenizer>,
+    token_filters: Vec<Box<dyn TokenFilter>>,
+}
+
+impl Analyzer {
+    pub fn new<T: Tokenizer + 'static>(tokenizer: T) -> Self {
+        Self {
+            char_filters: Vec::new(),
+            tokenizer: Box::new(tokenizer),
+            token_filters: Vec::new(),
+        }
+    }
+
+    pub fn add_char_filter<F: CharFilter + 'static>(mut self, char_filter: F) -> Self {
+        self.char_filters.push(Box::new(char_filter));
+        self
+    }
+
+    pub fn add_token_filter<F: TokenFilter + 'static>(mut self, token_filter: F) -> Self {
+        self.token_filters.push(Box::new(token_filter));
+        self
+    }
+
+    // The offsets of the tokens refer to the original text, before the char
+    // filters.
+    pub fn analyze(&self, text: &str) -> Vec<Token> {
+        let mut filtered_texts: Vec<FilteredText> = Vec::new();
+        for char_filter in &self.char_filters {
+            let input = filtered_texts.last().map_or(text, FilteredText::text);
+            filtered_texts.push(char_filter.filter(input));
+        }
+        let text = filtered_texts.last().map_or(text, FilteredText::text);
+        let mut tokens: TokenStream = self.tokenizer.tokenize(text);
+        for token_filter in &self.token_filters {
+            tokens = token_filter.filter(tokens);
+        }
+        let mut tokens: Vec<_> = tokens.collect();
+        for filtered_text in filtered_texts.iter().rev() {
+            for token in &mut tokens {
+                token.start_offset = filtered_text.correct_start_offset(token.start_offset);
+                token.end_offset = filtered_text.correct_end_offset(token.end_offset);
+            }
+        }
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{
+        CharFilter, FilteredText, LowerCaseFilter, PatternReplaceCharFilter, StandardTokenizer,
+        StopWordFilter, Token, WhitespaceTokenizer,
+    };
+
+    use super::Analyzer;
+
+    struct DashToSpaceFilter;
+
+    impl CharFilter for DashToSpaceFilter {
+        fn filter(&self, text: &str) -> FilteredText {
+            text.replace('-', " ").into()
+        }
+    }
+
+    #[test]
+    fn test_simple() {
+        let analyzer = Analyzer::new(StandardTokenizer)
+            .add_token_filter(LowerCaseFilter)
+            .add_token_filter(StopWordFilter::english());
+        let tokens = analyzer.analyze("The Quick fox");
+        assert_eq!(
+            tokens,
+            vec![
+                Token {
+                    text: "quick".to_string(),
+                    position_increment: 2,
+                    position_length: 1,
+                    start_offset: 4,
+                    end_offset: 9,
+                    keyword: false,
+                },
+                Token::new("fox".to_string(), 10, 13),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_char_filter() {
+        let analyzer = Analyzer::new(WhitespaceTokenizer).add_char_filter(DashToSpaceFilter);
+        let texts: Vec<_> = analyzer
+            .analyze("state-of-the-art")
+            .into_iter()
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(texts, vec!["state", "of", "the", "art"]);
+    }
+
+    #[test]
+    fn test_char_filter_offsets() {
+        let text = "x-ray of Zürich";
+        let analyzer = Analyzer::new(WhitespaceTokenizer)
+            .add_char_filter(PatternReplaceCharFilter::new([("ü", "ue")]))
+            .add_char_filter(DashToSpaceFilter)
+            .add_char_filter(PatternReplaceCharFilter::new([("of ", "")]));
+        let tokens: Vec<_> = analyzer
+            .analyze(text)
+            .into_iter()
+            .map(|token| (token.text, &text[token.start_offset..token.end_offset]))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                ("x".to_string(), "x"),
+                ("ray".to_string(), "ray"),
+                ("Zuerich".to_string(), "Zürich"),
+            ]
+        );
+    }
+}
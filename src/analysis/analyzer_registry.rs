@@ -0,0 +1,108 @@
+use std::{collections::HashMap, sync::Arc};
+
+use super::{
+    Analyzer, AsciiFoldingFilter, CjkBigramTokenizer, EnglishStemFilter, KeywordTokenizer,
+    LowerCaseFilter, StandardTokenizer, StopWordFilter, WhitespaceTokenizer, WidthFoldingFilter,
+};
+
+pub const KEYWORD_ANALYZER: &str = "keyword";
+pub const WHITESPACE_ANALYZER: &str = "whitespace";
+pub const STANDARD_ANALYZER: &str = "standard";
+pub const ENGLISH_ANALYZER: &str = "english";
+pub const CJK_ANALYZER: &str = "cjk";
+
+// Analyzers registered by name, so that fields can refer to them from the
+// schema.
+pub struct AnalyzerRegistry {
+    analyzers: HashMap<String, Arc<Analyzer>>,
+}
+
+impl Default for AnalyzerRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(KEYWORD_ANALYZER, Analyzer::new(KeywordTokenizer));
+        registry.register(WHITESPACE_ANALYZER, Analyzer::new(WhitespaceTokenizer));
+        registry.register(
+            STANDARD_ANALYZER,
+            Analyzer::new(StandardTokenizer).add_token_filter(LowerCaseFilter),
+        );
+        registry.register(
+            ENGLISH_ANALYZER,
+            Analyzer::new(StandardTokenizer)
+                .add_token_filter(LowerCaseFilter)
+                .add_token_filter(AsciiFoldingFilter)
+                .add_token_filter(StopWordFilter::english())
+                .add_token_filter(EnglishStemFilter),
+        );
+        registry.register(
+            CJK_ANALYZER,
+            Analyzer::new(CjkBigramTokenizer)
+                .add_token_filter(WidthFoldingFilter)
+                .add_token_filter(LowerCaseFilter),
+        );
+        registry
+    }
+}
+
+impl AnalyzerRegistry {
+    pub fn empty() -> Self {
+        Self {
+            analyzers: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, name: &str, analyzer: Analyzer) {
+        self.analyzers.insert(name.to_string(), Arc::new(analyzer));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<Analyzer>> {
+        self.analyzers.get(name).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Analyzer, WhitespaceTokenizer};
+
+    use super::{
+        AnalyzerRegistry, CJK_ANALYZER, ENGLISH_ANALYZER, KEYWORD_ANALYZER, STANDARD_ANALYZER,
+    };
+
+    fn texts(registry: &AnalyzerRegistry, name: &str, text: &str) -> Vec<String> {
+        registry
+            .get(name)
+            .unwrap()
+            .analyze(text)
+            .into_iter()
+            .map(|token| token.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        let mut registry = AnalyzerRegistry::default();
+        assert_eq!(
+            texts(&registry, KEYWORD_ANALYZER, "Hello World"),
+            vec!["Hello World"]
+        );
+        assert_eq!(
+            texts(&registry, STANDARD_ANALYZER, "Hello, World!"),
+            vec!["hello", "world"]
+        );
+        assert_eq!(
+            texts(&registry, ENGLISH_ANALYZER, "The Café is opening"),
+            vec!["cafe", "open"]
+        );
+        assert_eq!(
+            texts(&registry, CJK_ANALYZER, "ﾃﾞｰﾀ ＲＵＳＴ"),
+            texts(&registry, CJK_ANALYZER, "データ rust")
+        );
+        assert!(registry.get("custom").is_none());
+
+        registry.register("custom", Analyzer::new(WhitespaceTokenizer));
+        assert_eq!(
+            texts(&registry, "custom", "Hello, World!"),
+            vec!["Hello,", "World!"]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::ops::Range;
+
+// Transforms the text before it is tokenized.
+pub trait CharFilter: Send + Sync {
+    fn filter(&self, text: &str) -> FilteredText;
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Replacement {
+    filtered_start: usize,
+    filtered_end: usize,
+    original_start: usize,
+    original_end: usize,
+}
+
+// The output of a char filter, recording the replacements made so that the
+// offsets of the tokens can be corrected to refer to the original text. The
+// text between replacements is expected to be copied unchanged.
+#[derive(Clone, Debug, Default)]
+pub struct FilteredText {
+    text: String,
+    replacements: Vec<Replacement>,
+}
+
+impl From<String> for FilteredText {
+    // For filters that keep the byte length of everything they change.
+    fn from(text: String) -> Self {
+        Self {
+            text,
+            replacements: Vec::new(),
+        }
+    }
+}
+
+impl FilteredText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    pub fn push_replacement(&mut self, original: Range<usize>, replacement: &str) {
+        let filtered_start = self.text.len();
+        self.text.push_str(replacement);
+        self.replacements.push(Replacement {
+            filtered_start,
+            filtered_end: self.text.len(),
+            original_start: original.start,
+            original_end: original.end,
+        });
+    }
+
+    // A start offset within a replacement moves to the start of the replaced
+    // text, and one after an empty replacement to after the removed text.
+    pub fn correct_start_offset(&self, offset: usize) -> usize {
+        let index = self
+            .replacements
+            .partition_point(|replacement| replacement.filtered_start <= offset);
+        self.correct(index, offset, true)
+    }
+
+    // An end offset within or at the end of a replacement moves to the end of
+    // the replaced text, but one right before an empty replacement stays
+    // before the removed text.
+    pub fn correct_end_offset(&self, offset: usize) -> usize {
+        let index = self
+            .replacements
+            .partition_point(|replacement| replacement.filtered_start < offset);
+        self.correct(index, offset, false)
+    }
+
+    // Corrects with the replacement before `index`. Offsets within a
+    // replacement can't be mapped into the replaced text, which may not even
+    // have a char boundary there, so they snap to its start or its end.
+    fn correct(&self, index: usize, offset: usize, is_start: bool) -> usize {
+        let replacement = match index.checked_sub(1) {
+            Some(index) => self.replacements[index],
+            None => return offset,
+        };
+        if offset >= replacement.filtered_end {
+            replacement.original_end + offset - replacement.filtered_end
+        } else if is_start {
+            replacement.original_start
+        } else {
+            replacement.original_end
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FilteredText;
+
+    #[test]
+    fn test_simple() {
+        // "a<b>&amp;</b> c" to "a&amp; c".
+        let mut filtered = FilteredText::new();
+        filtered.push_str("a");
+        filtered.push_replacement(1..4, "");
+        filtered.push_replacement(4..9, "&");
+        filtered.push_replacement(9..13, "");
+        filtered.push_str(" c");
+        assert_eq!(filtered.text(), "a& c");
+
+        assert_eq!(filtered.correct_start_offset(0), 0);
+        assert_eq!(filtered.correct_start_offset(1), 4);
+        assert_eq!(filtered.correct_end_offset(1), 1);
+        assert_eq!(filtered.correct_end_offset(2), 9);
+        assert_eq!(filtered.correct_start_offset(2), 13);
+        assert_eq!(filtered.correct_start_offset(3), 14);
+        assert_eq!(filtered.correct_end_offset(4), 15);
+
+        // "½ x" to "1/2 x".
+        let mut filtered = FilteredText::new();
+        filtered.push_replacement(0..2, "1/2");
+        filtered.push_str(" x");
+        assert_eq!(filtered.correct_start_offset(0), 0);
+        assert_eq!(filtered.correct_end_offset(1), 2);
+        assert_eq!(filtered.correct_start_offset(2), 0);
+        assert_eq!(filtered.correct_end_offset(3), 2);
+        assert_eq!(filtered.correct_start_offset(4), 3);
+
+        let filtered = FilteredText::from("unchanged".to_string());
+        assert_eq!(filtered.correct_start_offset(3), 3);
+        assert_eq!(filtered.correct_end_offset(9), 9);
+    }
+}
@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use super::{Token, TokenStream, Tokenizer, WordDictionary};
+
+// Segments Chinese text with a word dictionary.
+//
+// Each run of Han chars is turned into a DAG of all the dictionary words it
+// contains, and cut along the path that maximizes the product of the word
+// probabilities. Chars that start no dictionary word are emitted on their
+// own. Runs of other letters and digits are emitted as single words.
+//
+// In search mode, the 2-char and 3-char dictionary words inside longer words
+// are emitted as well, at the same position as the word containing them.
+#[derive(Clone)]
+pub struct ChineseTokenizer {
+    dictionary: Arc<WordDictionary>,
+    search_mode: bool,
+}
+
+impl ChineseTokenizer {
+    pub fn new(dictionary: Arc<WordDictionary>) -> Self {
+        Self {
+            dictionary,
+            search_mode: false,
+        }
+    }
+
+    pub fn for_search(dictionary: Arc<WordDictionary>) -> Self {
+        Self {
+            dictionary,
+            search_mode: true,
+        }
+    }
+
+    fn cut(&self, chars: &[char]) -> Vec<usize> {
+        let len = chars.len();
+        let log_total = (self.dictionary.total_freq().max(1) as f64).ln();
+        let mut route = vec![(0.0, len); len + 1];
+        for start in (0..len).rev() {
+            let prefixes = self.dictionary.prefixes(&chars[start..]);
+            let candidates = if prefixes.is_empty() {
+                vec![(1, 1)]
+            } else {
+                prefixes
+            };
+            route[start] = candidates
+                .into_iter()
+                .map(|(word_len, freq)| {
+                    let end = start + word_len;
+                    ((freq.max(1) as f64).ln() - log_total + route[end].0, end)
+                })
+                .fold((f64::NEG_INFINITY, start + 1), |best, candidate| {
+                    if candidate.0 >= best.0 {
+                        candidate
+                    } else {
+                        best
+                    }
+                });
+        }
+
+        let mut ends = Vec::new();
+        let mut start = 0;
+        while start < len {
+            start = route[start].1;
+            ends.push(start);
+        }
+        ends
+    }
+
+    fn segment_han(&self, text: &str, chars: &[(usize, char)], tokens: &mut Vec<Token>) {
+        let han: Vec<_> = chars.iter().map(|&(_, c)| c).collect();
+        let (last_offset, last_char) = chars[chars.len() - 1];
+        let run_end = last_offset + last_char.len_utf8();
+        let byte_offset = |index: usize| chars.get(index).map_or(run_end, |&(offset, _)| offset);
+        let mut start = 0;
+        for end in self.cut(&han) {
+            let (start_offset, end_offset) = (byte_offset(start), byte_offset(end));
+            tokens.push(Token::new(
+                text[start_offset..end_offset].to_string(),
+                start_offset,
+                end_offset,
+            ));
+            if self.search_mode {
+                for gram in [2, 3] {
+                    if end - start <= gram {
+                        continue;
+                    }
+                    for sub_start in start..=end - gram {
+                        let sub_end = sub_start + gram;
+                        let (sub_start_offset, sub_end_offset) =
+                            (byte_offset(sub_start), byte_offset(sub_end));
+                        let word = &text[sub_start_offset..sub_end_offset];
+                        if self.dictionary.contains(word) {
+                            let mut token =
+                                Token::new(word.to_string(), sub_start_offset, sub_end_offset);
+                            token.position_increment = 0;
+                            tokens.push(token);
+                        }
+                    }
+                }
+            }
+            start = end;
+        }
+    }
+}
+
+pub fn is_han(c: char) -> bool {
+    matches!(c,
+        '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2A6DF}'
+        | '\u{2A700}'..='\u{2EBEF}'
+        | '\u{30000}'..='\u{3134F}')
+}
+
+#[derive(PartialEq)]
+enum CharClass {
+    Han,
+    Word,
+    Other,
+}
+
+fn char_class(c: char) -> CharClass {
+    if is_han(c) {
+        CharClass::Han
+    } else if c.is_alphanumeric() {
+        CharClass::Word
+    } else {
+        CharClass::Other
+    }
+}
+
+impl Tokenizer for ChineseTokenizer {
+    fn tokenize<'a>(&'a self, text: &'a str) -> TokenStream<'a> {
+        let mut tokens = Vec::new();
+        let chars: Vec<_> = text.char_indices().collect();
+        let mut start = 0;
+        while start < chars.len() {
+            let class = char_class(chars[start].1);
+            let mut end = start + 1;
+            while end < chars.len() && char_class(chars[end].1) == class {
+                end += 1;
+            }
+            match class {
+                CharClass::Han => self.segment_han(text, &chars[start..end], &mut tokens),
+                CharClass::Word => {
+                    let start_offset = chars[start].0;
+                    let end_offset = chars.get(end).map_or(text.len(), |&(offset, _)| offset);
+                    tokens.push(Token::new(
+                        text[start_offset..end_offset].to_string(),
+                        start_offset,
+                        end_offset,
+                    ));
+                }
+                CharClass::Other => {}
+            }
+            start = end;
+        }
+        Box::new(tokens.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::analysis::{Analyzer, LowerCaseFilter, Tokenizer, WordDictionary};
+
+    use super::ChineseTokenizer;
+
+    fn dictionary() -> Arc<WordDictionary> {
+        let text = "我 100\n来到 50\n来 30\n到 30\n北京 80\n北 10\n京 10\n清华 40\n\
+                    清华大学 60\n大学 70\n华大 5\n学 10\n大 30\n华 5\n";
+        Arc::new(WordDictionary::load(text.as_bytes()).unwrap())
+    }
+
+    fn texts(tokenizer: &ChineseTokenizer, text: &str) -> Vec<String> {
+        tokenizer.tokenize(text).map(|token| token.text).collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        let tokenizer = ChineseTokenizer::new(dictionary());
+        assert_eq!(
+            texts(&tokenizer, "我来到北京清华大学"),
+            vec!["我", "来到", "北京", "清华大学"]
+        );
+        assert_eq!(
+            texts(&tokenizer, "我在Rust大会，2024年"),
+            vec!["我", "在", "Rust", "大", "会", "2024", "年"]
+        );
+        assert!(texts(&tokenizer, "，。 ！").is_empty());
+
+        let tokens: Vec<_> = tokenizer.tokenize("去北京 ok").collect();
+        let offsets: Vec<_> = tokens
+            .iter()
+            .map(|token| (token.start_offset, token.end_offset))
+            .collect();
+        assert_eq!(offsets, vec![(0, 3), (3, 9), (10, 12)]);
+    }
+
+    #[test]
+    fn test_search_mode() {
+        let tokenizer = ChineseTokenizer::for_search(dictionary());
+        let tokens: Vec<_> = tokenizer
+            .tokenize("我来到清华大学")
+            .map(|token| (token.text, token.position_increment, token.start_offset))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                ("我".to_string(), 1, 0),
+                ("来到".to_string(), 1, 3),
+                ("清华大学".to_string(), 1, 9),
+                ("清华".to_string(), 0, 9),
+                ("华大".to_string(), 0, 12),
+                ("大学".to_string(), 0, 15),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analyzer() {
+        let analyzer =
+            Analyzer::new(ChineseTokenizer::new(dictionary())).add_token_filter(LowerCaseFilter);
+        let texts: Vec<_> = analyzer
+            .analyze("北京RUST")
+            .into_iter()
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(texts, vec!["北京", "rust"]);
+    }
+}
@@ -0,0 +1,128 @@
+use super::{is_han, Token, TokenStream, Tokenizer};
+
+// Emits the overlapping pairs of chars of each run of Chinese, Japanese and
+// Korean chars, or the char itself when the run has a single char. Runs of
+// other letters and digits are emitted as single words.
+//
+// Combining and half-width sound marks stay with the char they follow, so
+// that width folding the bigrams later makes them match their full-width
+// forms.
+#[derive(Clone, Default)]
+pub struct CjkBigramTokenizer;
+
+pub fn is_cjk(c: char) -> bool {
+    is_han(c)
+        || matches!(c,
+            '\u{1100}'..='\u{11FF}'
+            | '\u{3040}'..='\u{309F}'
+            | '\u{30A0}'..='\u{30FF}'
+            | '\u{3130}'..='\u{318F}'
+            | '\u{31F0}'..='\u{31FF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{FF66}'..='\u{FF9F}')
+}
+
+fn is_sound_mark(c: char) -> bool {
+    matches!(c, '\u{3099}' | '\u{309A}' | '\u{FF9E}' | '\u{FF9F}')
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CharClass {
+    Cjk,
+    Word,
+    Other,
+}
+
+fn char_class(c: char) -> CharClass {
+    if is_cjk(c) || is_sound_mark(c) {
+        CharClass::Cjk
+    } else if c.is_alphanumeric() {
+        CharClass::Word
+    } else {
+        CharClass::Other
+    }
+}
+
+fn push_bigrams(text: &str, start: usize, end: usize, tokens: &mut Vec<Token>) {
+    // The byte offsets where each char, with its sound marks, starts.
+    let mut units: Vec<_> = text[start..end]
+        .char_indices()
+        .filter(|&(_, c)| !is_sound_mark(c))
+        .map(|(offset, _)| start + offset)
+        .collect();
+    if units.first() != Some(&start) {
+        units.insert(0, start);
+    }
+    units.push(end);
+    if units.len() == 2 {
+        tokens.push(Token::new(text[start..end].to_string(), start, end));
+        return;
+    }
+    for window in units.windows(3) {
+        let (start, end) = (window[0], window[2]);
+        tokens.push(Token::new(text[start..end].to_string(), start, end));
+    }
+}
+
+impl Tokenizer for CjkBigramTokenizer {
+    fn tokenize<'a>(&'a self, text: &'a str) -> TokenStream<'a> {
+        let mut tokens = Vec::new();
+        let mut chars = text.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let class = char_class(c);
+            let mut end = start + c.len_utf8();
+            while let Some(&(offset, c)) = chars.peek() {
+                if char_class(c) != class {
+                    break;
+                }
+                end = offset + c.len_utf8();
+                chars.next();
+            }
+            match class {
+                CharClass::Cjk => push_bigrams(text, start, end, &mut tokens),
+                CharClass::Word => {
+                    tokens.push(Token::new(text[start..end].to_string(), start, end))
+                }
+                CharClass::Other => {}
+            }
+        }
+        Box::new(tokens.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Token, Tokenizer};
+
+    use super::CjkBigramTokenizer;
+
+    fn texts(text: &str) -> Vec<String> {
+        CjkBigramTokenizer
+            .tokenize(text)
+            .map(|token| token.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        let tokens: Vec<_> = CjkBigramTokenizer.tokenize("北京大学 rust").collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::new("北京".to_string(), 0, 6),
+                Token::new("京大".to_string(), 3, 9),
+                Token::new("大学".to_string(), 6, 12),
+                Token::new("rust".to_string(), 13, 17),
+            ]
+        );
+
+        assert_eq!(
+            texts("我爱Rust，東京タワー"),
+            vec!["我爱", "Rust", "東京", "京タ", "タワ", "ワー"]
+        );
+        assert_eq!(texts("한국어 입니다"), vec!["한국", "국어", "입니", "니다"]);
+        assert_eq!(texts("中 文"), vec!["中", "文"]);
+        assert_eq!(texts("ｶﾞｷﾞ"), vec!["ｶﾞｷﾞ"]);
+        assert!(texts("，。 ！").is_empty());
+    }
+}
@@ -0,0 +1,673 @@
+const MAX_CODE_LEN: usize = 4;
+
+const SILENT_STARTS: &[&str] = &["GN", "KN", "PN", "WR", "PS"];
+const L_R_N_M_B_H_F_V_W_SPACE: &[&str] = &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "];
+const ES_EP_EB_EL_EY_IB_IL_IN_IE_EI_ER: &[&str] = &[
+    "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+];
+const L_T_K_S_N_M_B_Z: &[&str] = &["L", "T", "K", "S", "N", "M", "B", "Z"];
+
+// Encodes a word with the Double Metaphone algorithm of Lawrence Philips,
+// returning its primary and alternate codes of at most four chars, e.g.
+// "Smith" to "SM0" and "XMT". The alternate code is the same as the primary
+// one for most words, and both are empty without any letter to encode.
+pub fn double_metaphone(word: &str) -> (String, String) {
+    let word: Vec<_> = word.trim().chars().flat_map(char::to_uppercase).collect();
+    let mut encoder = Encoder {
+        slavo_germanic: Word(&word).is_slavo_germanic(),
+        word: Word(&word),
+        primary: String::new(),
+        alternate: String::new(),
+    };
+    encoder.encode();
+    (encoder.primary, encoder.alternate)
+}
+
+#[derive(Clone, Copy)]
+struct Word<'a>(&'a [char]);
+
+impl<'a> Word<'a> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    // Returns NUL out of the word.
+    fn at(&self, index: isize) -> char {
+        if index < 0 {
+            return '\0';
+        }
+        self.0.get(index as usize).copied().unwrap_or('\0')
+    }
+
+    // Whether the `len` chars from `start` are one of the `candidates`.
+    fn contains(&self, start: isize, len: usize, candidates: &[&str]) -> bool {
+        if start < 0 || start as usize + len > self.0.len() {
+            return false;
+        }
+        let target = &self.0[start as usize..start as usize + len];
+        candidates
+            .iter()
+            .any(|candidate| candidate.chars().eq(target.iter().copied()))
+    }
+
+    fn is_vowel(&self, index: isize) -> bool {
+        matches!(self.at(index), 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+    }
+
+    fn is_slavo_germanic(&self) -> bool {
+        let text: String = self.0.iter().collect();
+        text.contains('W') || text.contains('K') || text.contains("CZ") || text.contains("WITZ")
+    }
+
+    fn last(&self) -> isize {
+        self.0.len() as isize - 1
+    }
+}
+
+struct Encoder<'a> {
+    word: Word<'a>,
+    slavo_germanic: bool,
+    primary: String,
+    alternate: String,
+}
+
+impl<'a> Encoder<'a> {
+    fn is_complete(&self) -> bool {
+        self.primary.len() >= MAX_CODE_LEN && self.alternate.len() >= MAX_CODE_LEN
+    }
+
+    fn append_primary(&mut self, code: &str) {
+        let remaining = MAX_CODE_LEN.saturating_sub(self.primary.len());
+        self.primary.extend(code.chars().take(remaining));
+    }
+
+    fn append_alternate(&mut self, code: &str) {
+        let remaining = MAX_CODE_LEN.saturating_sub(self.alternate.len());
+        self.alternate.extend(code.chars().take(remaining));
+    }
+
+    fn append(&mut self, code: &str) {
+        self.append_primary(code);
+        self.append_alternate(code);
+    }
+
+    fn append_both(&mut self, primary: &str, alternate: &str) {
+        self.append_primary(primary);
+        self.append_alternate(alternate);
+    }
+
+    fn encode(&mut self) {
+        let word = self.word;
+        let mut index: isize = if word.contains(0, 2, SILENT_STARTS) {
+            1
+        } else {
+            0
+        };
+        while !self.is_complete() && index < word.len() as isize {
+            index = match word.at(index) {
+                'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                    if index == 0 {
+                        self.append("A");
+                    }
+                    index + 1
+                }
+                'B' => {
+                    self.append("P");
+                    self.skip_double(index, 'B')
+                }
+                'Ç' => {
+                    self.append("S");
+                    index + 1
+                }
+                'C' => self.handle_c(index),
+                'D' => self.handle_d(index),
+                'F' => {
+                    self.append("F");
+                    self.skip_double(index, 'F')
+                }
+                'G' => self.handle_g(index),
+                'H' => self.handle_h(index),
+                'J' => self.handle_j(index),
+                'K' => {
+                    self.append("K");
+                    self.skip_double(index, 'K')
+                }
+                'L' => self.handle_l(index),
+                'M' => {
+                    self.append("M");
+                    if self.condition_m0(index) {
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+                'N' => {
+                    self.append("N");
+                    self.skip_double(index, 'N')
+                }
+                'Ñ' => {
+                    self.append("N");
+                    index + 1
+                }
+                'P' => self.handle_p(index),
+                'Q' => {
+                    self.append("K");
+                    self.skip_double(index, 'Q')
+                }
+                'R' => self.handle_r(index),
+                'S' => self.handle_s(index),
+                'T' => self.handle_t(index),
+                'V' => {
+                    self.append("F");
+                    self.skip_double(index, 'V')
+                }
+                'W' => self.handle_w(index),
+                'X' => self.handle_x(index),
+                'Z' => self.handle_z(index),
+                _ => index + 1,
+            };
+        }
+    }
+
+    fn skip_double(&self, index: isize, c: char) -> isize {
+        if self.word.at(index + 1) == c {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_c(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if self.condition_c0(index) {
+            self.append("K");
+            index + 2
+        } else if index == 0 && word.contains(index, 6, &["CAESAR"]) {
+            self.append("S");
+            index + 2
+        } else if word.contains(index, 2, &["CH"]) {
+            self.handle_ch(index)
+        } else if word.contains(index, 2, &["CZ"]) && !word.contains(index - 2, 4, &["WICZ"]) {
+            self.append_both("S", "X");
+            index + 2
+        } else if word.contains(index + 1, 3, &["CIA"]) {
+            self.append("X");
+            index + 3
+        } else if word.contains(index, 2, &["CC"]) && !(index == 1 && word.at(0) == 'M') {
+            self.handle_cc(index)
+        } else if word.contains(index, 2, &["CK", "CG", "CQ"]) {
+            self.append("K");
+            index + 2
+        } else if word.contains(index, 2, &["CI", "CE", "CY"]) {
+            if word.contains(index, 3, &["CIO", "CIE", "CIA"]) {
+                self.append_both("S", "X");
+            } else {
+                self.append("S");
+            }
+            index + 2
+        } else {
+            self.append("K");
+            if word.contains(index + 1, 2, &[" C", " Q", " G"]) {
+                index + 3
+            } else if word.contains(index + 1, 1, &["C", "K", "Q"])
+                && !word.contains(index + 1, 2, &["CE", "CI"])
+            {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn condition_c0(&self, index: isize) -> bool {
+        let word = self.word;
+        if word.contains(index, 4, &["CHIA"]) {
+            true
+        } else if index <= 1 || word.is_vowel(index - 2) || !word.contains(index - 1, 3, &["ACH"]) {
+            false
+        } else {
+            let c = word.at(index + 2);
+            (c != 'I' && c != 'E') || word.contains(index - 2, 6, &["BACHER", "MACHER"])
+        }
+    }
+
+    fn handle_cc(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if word.contains(index + 2, 1, &["I", "E", "H"]) && !word.contains(index + 2, 2, &["HU"]) {
+            if (index == 1 && word.at(index - 1) == 'A')
+                || word.contains(index - 1, 5, &["UCCEE", "UCCES"])
+            {
+                self.append("KS");
+            } else {
+                self.append("X");
+            }
+            index + 3
+        } else {
+            self.append("K");
+            index + 2
+        }
+    }
+
+    fn handle_ch(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if index > 0 && word.contains(index, 4, &["CHAE"]) {
+            self.append_both("K", "X");
+        } else if self.condition_ch0(index) || self.condition_ch1(index) {
+            self.append("K");
+        } else if index > 0 {
+            if word.contains(0, 2, &["MC"]) {
+                self.append("K");
+            } else {
+                self.append_both("X", "K");
+            }
+        } else {
+            self.append("X");
+        }
+        index + 2
+    }
+
+    fn condition_ch0(&self, index: isize) -> bool {
+        let word = self.word;
+        index == 0
+            && (word.contains(index + 1, 5, &["HARAC", "HARIS"])
+                || word.contains(index + 1, 3, &["HOR", "HYM", "HIA", "HEM"]))
+            && !word.contains(0, 5, &["CHORE"])
+    }
+
+    fn condition_ch1(&self, index: isize) -> bool {
+        let word = self.word;
+        word.contains(0, 4, &["VAN ", "VON "])
+            || word.contains(0, 3, &["SCH"])
+            || word.contains(index - 2, 6, &["ORCHES", "ARCHIT", "ORCHID"])
+            || word.contains(index + 2, 1, &["T", "S"])
+            || ((word.contains(index - 1, 1, &["A", "O", "U", "E"]) || index == 0)
+                && (word.contains(index + 2, 1, L_R_N_M_B_H_F_V_W_SPACE)
+                    || index + 1 == word.last()))
+    }
+
+    fn handle_d(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if word.contains(index, 2, &["DG"]) {
+            if word.contains(index + 2, 1, &["I", "E", "Y"]) {
+                self.append("J");
+                index + 3
+            } else {
+                self.append("TK");
+                index + 2
+            }
+        } else if word.contains(index, 2, &["DT", "DD"]) {
+            self.append("T");
+            index + 2
+        } else {
+            self.append("T");
+            index + 1
+        }
+    }
+
+    fn handle_g(&mut self, index: isize) -> isize {
+        let word = self.word;
+        let slavo_germanic = self.slavo_germanic;
+        if word.at(index + 1) == 'H' {
+            self.handle_gh(index)
+        } else if word.at(index + 1) == 'N' {
+            if index == 1 && word.is_vowel(0) && !slavo_germanic {
+                self.append_both("KN", "N");
+            } else if !word.contains(index + 2, 2, &["EY"])
+                && word.at(index + 1) != 'Y'
+                && !slavo_germanic
+            {
+                self.append_both("N", "KN");
+            } else {
+                self.append("KN");
+            }
+            index + 2
+        } else if word.contains(index + 1, 2, &["LI"]) && !slavo_germanic {
+            self.append_both("KL", "L");
+            index + 2
+        } else if (index == 0
+            && (word.at(index + 1) == 'Y'
+                || word.contains(index + 1, 2, ES_EP_EB_EL_EY_IB_IL_IN_IE_EI_ER)))
+            || ((word.contains(index + 1, 2, &["ER"]) || word.at(index + 1) == 'Y')
+                && !word.contains(0, 6, &["DANGER", "RANGER", "MANGER"])
+                && !word.contains(index - 1, 1, &["E", "I"])
+                && !word.contains(index - 1, 3, &["RGY", "OGY"]))
+        {
+            self.append_both("K", "J");
+            index + 2
+        } else if word.contains(index + 1, 1, &["E", "I", "Y"])
+            || word.contains(index - 1, 4, &["AGGI", "OGGI"])
+        {
+            if word.contains(0, 4, &["VAN ", "VON "])
+                || word.contains(0, 3, &["SCH"])
+                || word.contains(index + 1, 2, &["ET"])
+            {
+                self.append("K");
+            } else if word.contains(index + 1, 3, &["IER"]) {
+                self.append("J");
+            } else {
+                self.append_both("J", "K");
+            }
+            index + 2
+        } else {
+            self.append("K");
+            self.skip_double(index, 'G')
+        }
+    }
+
+    fn handle_gh(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if index > 0 && !word.is_vowel(index - 1) {
+            self.append("K");
+        } else if index == 0 {
+            if word.at(index + 2) == 'I' {
+                self.append("J");
+            } else {
+                self.append("K");
+            }
+        } else if (index > 1 && word.contains(index - 2, 1, &["B", "H", "D"]))
+            || (index > 2 && word.contains(index - 3, 1, &["B", "H", "D"]))
+            || (index > 3 && word.contains(index - 4, 1, &["B", "H"]))
+        {
+            // Silent, as in "hugh" or "bough".
+        } else if index > 2
+            && word.at(index - 1) == 'U'
+            && word.contains(index - 3, 1, &["C", "G", "L", "R", "T"])
+        {
+            self.append("F");
+        } else if index > 0 && word.at(index - 1) != 'I' {
+            self.append("K");
+        }
+        index + 2
+    }
+
+    fn handle_h(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if (index == 0 || word.is_vowel(index - 1)) && word.is_vowel(index + 1) {
+            self.append("H");
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_j(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if word.contains(index, 4, &["JOSE"]) || word.contains(0, 4, &["SAN "]) {
+            if (index == 0 && word.at(index + 4) == ' ')
+                || word.len() == 4
+                || word.contains(0, 4, &["SAN "])
+            {
+                self.append("H");
+            } else {
+                self.append_both("J", "H");
+            }
+            return index + 1;
+        }
+        if index == 0 {
+            self.append_both("J", "A");
+        } else if word.is_vowel(index - 1)
+            && !self.slavo_germanic
+            && matches!(word.at(index + 1), 'A' | 'O')
+        {
+            self.append_both("J", "H");
+        } else if index == word.last() {
+            self.append_primary("J");
+        } else if !word.contains(index + 1, 1, L_T_K_S_N_M_B_Z)
+            && !word.contains(index - 1, 1, &["S", "K", "L"])
+        {
+            self.append("J");
+        }
+        self.skip_double(index, 'J')
+    }
+
+    fn handle_l(&mut self, index: isize) -> isize {
+        if self.word.at(index + 1) == 'L' {
+            if self.condition_l0(index) {
+                self.append_primary("L");
+            } else {
+                self.append("L");
+            }
+            index + 2
+        } else {
+            self.append("L");
+            index + 1
+        }
+    }
+
+    fn condition_l0(&self, index: isize) -> bool {
+        let word = self.word;
+        let len = word.len() as isize;
+        (index == len - 3 && word.contains(index - 1, 4, &["ILLO", "ILLA", "ALLE"]))
+            || ((word.contains(len - 2, 2, &["AS", "OS"])
+                || word.contains(len - 1, 1, &["A", "O"]))
+                && word.contains(index - 1, 4, &["ALLE"]))
+    }
+
+    fn condition_m0(&self, index: isize) -> bool {
+        let word = self.word;
+        word.at(index + 1) == 'M'
+            || (word.contains(index - 1, 3, &["UMB"])
+                && (index + 1 == word.last() || word.contains(index + 2, 2, &["ER"])))
+    }
+
+    fn handle_p(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if word.at(index + 1) == 'H' {
+            self.append("F");
+            index + 2
+        } else {
+            self.append("P");
+            if word.contains(index + 1, 1, &["P", "B"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn handle_r(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if index == word.last()
+            && !self.slavo_germanic
+            && word.contains(index - 2, 2, &["IE"])
+            && !word.contains(index - 4, 2, &["ME", "MA"])
+        {
+            self.append_alternate("R");
+        } else {
+            self.append("R");
+        }
+        self.skip_double(index, 'R')
+    }
+
+    fn handle_s(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if word.contains(index - 1, 3, &["ISL", "YSL"]) {
+            index + 1
+        } else if index == 0 && word.contains(index, 5, &["SUGAR"]) {
+            self.append_both("X", "S");
+            index + 1
+        } else if word.contains(index, 2, &["SH"]) {
+            if word.contains(index + 1, 4, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                self.append("S");
+            } else {
+                self.append("X");
+            }
+            index + 2
+        } else if word.contains(index, 3, &["SIO", "SIA"]) || word.contains(index, 4, &["SIAN"]) {
+            if self.slavo_germanic {
+                self.append("S");
+            } else {
+                self.append_both("S", "X");
+            }
+            index + 3
+        } else if (index == 0 && word.contains(index + 1, 1, &["M", "N", "L", "W"]))
+            || word.contains(index + 1, 1, &["Z"])
+        {
+            self.append_both("S", "X");
+            if word.contains(index + 1, 1, &["Z"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        } else if word.contains(index, 2, &["SC"]) {
+            self.handle_sc(index)
+        } else {
+            if index == word.last() && word.contains(index - 2, 2, &["AI", "OI"]) {
+                self.append_alternate("S");
+            } else {
+                self.append("S");
+            }
+            if word.contains(index + 1, 1, &["S", "Z"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn handle_sc(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if word.at(index + 2) == 'H' {
+            if word.contains(index + 3, 2, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                if word.contains(index + 3, 2, &["ER", "EN"]) {
+                    self.append_both("X", "SK");
+                } else {
+                    self.append("SK");
+                }
+            } else if index == 0 && !word.is_vowel(3) && word.at(3) != 'W' {
+                self.append_both("X", "S");
+            } else {
+                self.append("X");
+            }
+        } else if word.contains(index + 2, 1, &["I", "E", "Y"]) {
+            self.append("S");
+        } else {
+            self.append("SK");
+        }
+        index + 3
+    }
+
+    fn handle_t(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if word.contains(index, 4, &["TION"]) || word.contains(index, 3, &["TIA", "TCH"]) {
+            self.append("X");
+            index + 3
+        } else if word.contains(index, 2, &["TH"]) || word.contains(index, 3, &["TTH"]) {
+            if word.contains(index + 2, 2, &["OM", "AM"])
+                || word.contains(0, 4, &["VAN ", "VON "])
+                || word.contains(0, 3, &["SCH"])
+            {
+                self.append("T");
+            } else {
+                self.append_both("0", "T");
+            }
+            index + 2
+        } else {
+            self.append("T");
+            if word.contains(index + 1, 1, &["T", "D"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn handle_w(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if word.contains(index, 2, &["WR"]) {
+            self.append("R");
+            index + 2
+        } else if index == 0 && (word.is_vowel(index + 1) || word.contains(index, 2, &["WH"])) {
+            if word.is_vowel(index + 1) {
+                self.append_both("A", "F");
+            } else {
+                self.append("A");
+            }
+            index + 1
+        } else if (index == word.last() && word.is_vowel(index - 1))
+            || word.contains(index - 1, 5, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+            || word.contains(0, 3, &["SCH"])
+        {
+            self.append_alternate("F");
+            index + 1
+        } else if word.contains(index, 4, &["WICZ", "WITZ"]) {
+            self.append_both("TS", "FX");
+            index + 4
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_x(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if index == 0 {
+            self.append("S");
+            return index + 1;
+        }
+        // Silent at the end of French words, as in "breaux".
+        if !(index == word.last()
+            && (word.contains(index - 3, 3, &["IAU", "EAU"])
+                || word.contains(index - 2, 2, &["AU", "OU"])))
+        {
+            self.append("KS");
+        }
+        if word.contains(index + 1, 1, &["C", "X"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_z(&mut self, index: isize) -> isize {
+        let word = self.word;
+        if word.at(index + 1) == 'H' {
+            self.append("J");
+            return index + 2;
+        }
+        if word.contains(index + 1, 2, &["ZO", "ZI", "ZA"])
+            || (self.slavo_germanic && index > 0 && word.at(index - 1) != 'T')
+        {
+            self.append_both("S", "TS");
+        } else {
+            self.append("S");
+        }
+        self.skip_double(index, 'Z')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::double_metaphone;
+
+    #[test]
+    fn test_simple() {
+        let words = [
+            ("Smith", "SM0", "XMT"),
+            ("Schmidt", "XMT", "SMT"),
+            ("Thompson", "TMPS", "TMPS"),
+            ("Jose", "HS", "HS"),
+            ("Knight", "NT", "NT"),
+            ("Gough", "KF", "KF"),
+            ("Philips", "FLPS", "FLPS"),
+            ("Caesar", "SSR", "SSR"),
+            ("Xavier", "SF", "SFR"),
+            ("Jackson", "JKSN", "AKSN"),
+            ("Katherine", "K0RN", "KTRN"),
+            ("Catherine", "K0RN", "KTRN"),
+            ("Kathryn", "K0RN", "KTRN"),
+            ("Stephen", "STFN", "STFN"),
+            ("Steven", "STFN", "STFN"),
+            ("Michael", "MKL", "MXL"),
+            ("Bartholomew", "PR0L", "PRTL"),
+            ("", "", ""),
+        ];
+        for (word, primary, alternate) in words {
+            assert_eq!(
+                double_metaphone(word),
+                (primary.to_string(), alternate.to_string()),
+                "encoding `{}`",
+                word
+            );
+        }
+    }
+}
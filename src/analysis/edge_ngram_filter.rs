@@ -0,0 +1,92 @@
+use super::{ngram_filter::gram_tokens, TokenFilter, TokenStream};
+
+// Replaces each token with its prefixes of `min_gram` to `max_gram` chars, for
+// search-as-you-type where a prefix is matched by a plain term lookup. The
+// prefixes are at the position of the token and keep its offsets. Tokens
+// shorter than `min_gram` are removed, unless the original tokens are
+// preserved, in which case the tokens that are not a prefix of themselves are
+// emitted after their prefixes.
+#[derive(Clone)]
+pub struct EdgeNGramFilter {
+    min_gram: usize,
+    max_gram: usize,
+    preserve_original: bool,
+}
+
+impl EdgeNGramFilter {
+    pub fn new(min_gram: usize, max_gram: usize) -> Self {
+        assert!(
+            min_gram > 0 && min_gram <= max_gram,
+            "Invalid gram sizes [{}, {}]",
+            min_gram,
+            max_gram
+        );
+        Self {
+            min_gram,
+            max_gram,
+            preserve_original: false,
+        }
+    }
+
+    pub fn set_preserve_original(mut self) -> Self {
+        self.preserve_original = true;
+        self
+    }
+}
+
+impl TokenFilter for EdgeNGramFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        gram_tokens(tokens, self.preserve_original, move |len| {
+            (self.min_gram..=self.max_gram.min(len)).map(|gram| (0, gram))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{TokenFilter, Tokenizer, WhitespaceTokenizer};
+
+    use super::EdgeNGramFilter;
+
+    fn grams(filter: &EdgeNGramFilter, text: &str) -> Vec<(String, u32)> {
+        filter
+            .filter(WhitespaceTokenizer.tokenize(text))
+            .map(|token| (token.text, token.position_increment))
+            .collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        let filter = EdgeNGramFilter::new(1, 3);
+        assert_eq!(
+            grams(&filter, "rust go"),
+            vec![
+                ("r".to_string(), 1),
+                ("ru".to_string(), 0),
+                ("rus".to_string(), 0),
+                ("g".to_string(), 1),
+                ("go".to_string(), 0),
+            ]
+        );
+
+        let filter = EdgeNGramFilter::new(3, 4);
+        assert_eq!(
+            grams(&filter, "a élan"),
+            vec![("éla".to_string(), 2), ("élan".to_string(), 0)]
+        );
+    }
+
+    #[test]
+    fn test_preserve_original() {
+        let filter = EdgeNGramFilter::new(2, 3).set_preserve_original();
+        assert_eq!(
+            grams(&filter, "a rust"),
+            vec![
+                ("a".to_string(), 1),
+                ("ru".to_string(), 1),
+                ("rus".to_string(), 0),
+                ("rust".to_string(), 0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,439 @@
+use super::{TokenFilter, TokenStream};
+
+// Stems English words with the Porter2 algorithm, e.g. "running" to "run" and
+// "generously" to "generous". Tokens marked as keywords and words with chars
+// other than lowercase ASCII letters and apostrophes are left as they are, so
+// this filter is expected after lowercasing.
+#[derive(Clone, Default)]
+pub struct EnglishStemFilter;
+
+impl TokenFilter for EnglishStemFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        Box::new(tokens.map(|mut token| {
+            if !token.keyword {
+                if let Some(stem) = stem_english(&token.text) {
+                    token.text = stem;
+                }
+            }
+            token
+        }))
+    }
+}
+
+// Returns None when the word is left as it is.
+pub fn stem_english(word: &str) -> Option<String> {
+    if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase() || b == b'\'') {
+        return None;
+    }
+    let stem = match exception(word) {
+        Some(stem) => stem.to_string(),
+        None => {
+            let mut stemmer = Stemmer::new(word);
+            stemmer.stem();
+            stemmer.into_string()
+        }
+    };
+    if stem == word {
+        None
+    } else {
+        Some(stem)
+    }
+}
+
+fn exception(word: &str) -> Option<&'static str> {
+    let stem = match word {
+        "skis" => "ski",
+        "skies" => "sky",
+        "dying" => "die",
+        "lying" => "lie",
+        "tying" => "tie",
+        "idly" => "idl",
+        "gently" => "gentl",
+        "ugly" => "ugli",
+        "early" => "earli",
+        "only" => "onli",
+        "singly" => "singl",
+        "sky" => "sky",
+        "news" => "news",
+        "howe" => "howe",
+        "atlas" => "atlas",
+        "cosmos" => "cosmos",
+        "bias" => "bias",
+        "andes" => "andes",
+        _ => return None,
+    };
+    Some(stem)
+}
+
+// Words left as they are once their plural is removed.
+const INVARIANT_AFTER_STEP_1A: &[&str] = &[
+    "inning", "outing", "canning", "herring", "earring", "proceed", "exceed", "succeed",
+];
+
+fn is_vowel(b: u8) -> bool {
+    matches!(b, b'a' | b'e' | b'i' | b'o' | b'u' | b'y')
+}
+
+fn is_double(b: u8) -> bool {
+    matches!(
+        b,
+        b'b' | b'd' | b'f' | b'g' | b'm' | b'n' | b'p' | b'r' | b't'
+    )
+}
+
+fn is_li_ending(b: u8) -> bool {
+    matches!(
+        b,
+        b'c' | b'd' | b'e' | b'g' | b'h' | b'k' | b'm' | b'n' | b'r' | b't'
+    )
+}
+
+// Either a vowel followed by a non-vowel other than w, x and Y and preceded
+// by a non-vowel, or a vowel followed by a non-vowel at the beginning.
+fn ends_with_short_syllable(word: &[u8]) -> bool {
+    match word.len() {
+        0 | 1 => false,
+        2 => is_vowel(word[0]) && !is_vowel(word[1]),
+        len => {
+            !is_vowel(word[len - 3])
+                && is_vowel(word[len - 2])
+                && !is_vowel(word[len - 1])
+                && !matches!(word[len - 1], b'w' | b'x' | b'Y')
+        }
+    }
+}
+
+// The word is stemmed in place as bytes. A `y` acting as a consonant is
+// stored as `Y` while stemming.
+struct Stemmer {
+    word: Vec<u8>,
+    r1: usize,
+    r2: usize,
+}
+
+impl Stemmer {
+    fn new(word: &str) -> Self {
+        let mut word = word.as_bytes().to_vec();
+        if word[0] == b'\'' {
+            word.remove(0);
+        }
+        for i in 0..word.len() {
+            if word[i] == b'y' && (i == 0 || is_vowel(word[i - 1])) {
+                word[i] = b'Y';
+            }
+        }
+        let r1 = ["gener", "commun", "arsen"]
+            .iter()
+            .find(|prefix| word.starts_with(prefix.as_bytes()))
+            .map_or_else(|| Self::region_start(&word, 0), |prefix| prefix.len());
+        let r2 = Self::region_start(&word, r1);
+        Self { word, r1, r2 }
+    }
+
+    // The region after the first non-vowel following a vowel, from `start`.
+    fn region_start(word: &[u8], start: usize) -> usize {
+        (start + 1..word.len())
+            .find(|&i| !is_vowel(word[i]) && is_vowel(word[i - 1]))
+            .map_or(word.len(), |i| i + 1)
+    }
+
+    fn into_string(self) -> String {
+        let word: Vec<u8> = self
+            .word
+            .into_iter()
+            .map(|b| if b == b'Y' { b'y' } else { b })
+            .collect();
+        String::from_utf8(word).unwrap()
+    }
+
+    fn ends_with(&self, suffix: &str) -> bool {
+        self.word.ends_with(suffix.as_bytes())
+    }
+
+    // Returns the longest of the suffixes the word ends with.
+    fn longest_suffix<'s>(&self, suffixes: &[&'s str]) -> Option<&'s str> {
+        suffixes
+            .iter()
+            .filter(|suffix| self.ends_with(suffix))
+            .max_by_key(|suffix| suffix.len())
+            .copied()
+    }
+
+    fn suffix_start(&self, suffix: &str) -> usize {
+        self.word.len() - suffix.len()
+    }
+
+    fn replace_suffix(&mut self, suffix: &str, replacement: &str) {
+        self.word.truncate(self.suffix_start(suffix));
+        self.word.extend_from_slice(replacement.as_bytes());
+    }
+
+    fn has_vowel(&self, end: usize) -> bool {
+        self.word[..end].iter().any(|&b| is_vowel(b))
+    }
+
+    fn is_short(&self) -> bool {
+        self.r1 >= self.word.len() && ends_with_short_syllable(&self.word)
+    }
+
+    fn stem(&mut self) {
+        self.step_0();
+        self.step_1a();
+        if INVARIANT_AFTER_STEP_1A
+            .iter()
+            .any(|word| self.word == word.as_bytes())
+        {
+            return;
+        }
+        self.step_1b();
+        self.step_1c();
+        self.step_2();
+        self.step_3();
+        self.step_4();
+        self.step_5();
+    }
+
+    fn step_0(&mut self) {
+        if let Some(suffix) = self.longest_suffix(&["'s'", "'s", "'"]) {
+            self.replace_suffix(suffix, "");
+        }
+    }
+
+    fn step_1a(&mut self) {
+        match self.longest_suffix(&["sses", "ied", "ies", "us", "ss", "s"]) {
+            Some("sses") => self.replace_suffix("sses", "ss"),
+            Some(suffix @ ("ied" | "ies")) => {
+                if self.suffix_start(suffix) > 1 {
+                    self.replace_suffix(suffix, "i");
+                } else {
+                    self.replace_suffix(suffix, "ie");
+                }
+            }
+            Some("s") => {
+                let start = self.suffix_start("s");
+                if start >= 2 && self.has_vowel(start - 1) {
+                    self.replace_suffix("s", "");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn step_1b(&mut self) {
+        match self.longest_suffix(&["eedly", "ingly", "edly", "eed", "ing", "ed"]) {
+            Some(suffix @ ("eed" | "eedly")) if self.suffix_start(suffix) >= self.r1 => {
+                self.replace_suffix(suffix, "ee");
+            }
+            Some("eed" | "eedly") => {}
+            Some(suffix) => {
+                if !self.has_vowel(self.suffix_start(suffix)) {
+                    return;
+                }
+                self.replace_suffix(suffix, "");
+                let len = self.word.len();
+                if self.ends_with("at") || self.ends_with("bl") || self.ends_with("iz") {
+                    self.word.push(b'e');
+                } else if len >= 2
+                    && self.word[len - 1] == self.word[len - 2]
+                    && is_double(self.word[len - 1])
+                {
+                    self.word.pop();
+                } else if self.is_short() {
+                    self.word.push(b'e');
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn step_1c(&mut self) {
+        let len = self.word.len();
+        if len > 2 && matches!(self.word[len - 1], b'y' | b'Y') && !is_vowel(self.word[len - 2]) {
+            self.word[len - 1] = b'i';
+        }
+    }
+
+    fn step_2(&mut self) {
+        const SUFFIXES: &[(&str, &str)] = &[
+            ("tional", "tion"),
+            ("enci", "ence"),
+            ("anci", "ance"),
+            ("abli", "able"),
+            ("entli", "ent"),
+            ("izer", "ize"),
+            ("ization", "ize"),
+            ("ational", "ate"),
+            ("ation", "ate"),
+            ("ator", "ate"),
+            ("alism", "al"),
+            ("aliti", "al"),
+            ("alli", "al"),
+            ("fulness", "ful"),
+            ("ousli", "ous"),
+            ("ousness", "ous"),
+            ("iveness", "ive"),
+            ("iviti", "ive"),
+            ("biliti", "ble"),
+            ("bli", "ble"),
+            ("fulli", "ful"),
+            ("lessli", "less"),
+            ("ogi", "og"),
+            ("li", ""),
+        ];
+        let suffixes: Vec<_> = SUFFIXES.iter().map(|&(suffix, _)| suffix).collect();
+        let suffix = match self.longest_suffix(&suffixes) {
+            Some(suffix) => suffix,
+            None => return,
+        };
+        let start = self.suffix_start(suffix);
+        if start < self.r1 {
+            return;
+        }
+        let preceding = start.checked_sub(1).map(|i| self.word[i]);
+        let replacement = SUFFIXES.iter().find(|&&(s, _)| s == suffix).unwrap().1;
+        match suffix {
+            "ogi" if preceding != Some(b'l') => {}
+            "li" if !preceding.is_some_and(is_li_ending) => {}
+            _ => self.replace_suffix(suffix, replacement),
+        }
+    }
+
+    fn step_3(&mut self) {
+        const SUFFIXES: &[(&str, &str)] = &[
+            ("tional", "tion"),
+            ("ational", "ate"),
+            ("alize", "al"),
+            ("icate", "ic"),
+            ("iciti", "ic"),
+            ("ical", "ic"),
+            ("ful", ""),
+            ("ness", ""),
+            ("ative", ""),
+        ];
+        let suffixes: Vec<_> = SUFFIXES.iter().map(|&(suffix, _)| suffix).collect();
+        let suffix = match self.longest_suffix(&suffixes) {
+            Some(suffix) => suffix,
+            None => return,
+        };
+        let start = self.suffix_start(suffix);
+        if start < self.r1 || (suffix == "ative" && start < self.r2) {
+            return;
+        }
+        let replacement = SUFFIXES.iter().find(|&&(s, _)| s == suffix).unwrap().1;
+        self.replace_suffix(suffix, replacement);
+    }
+
+    fn step_4(&mut self) {
+        const SUFFIXES: &[&str] = &[
+            "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ism",
+            "ate", "iti", "ous", "ive", "ize", "ion",
+        ];
+        let suffix = match self.longest_suffix(SUFFIXES) {
+            Some(suffix) => suffix,
+            None => return,
+        };
+        let start = self.suffix_start(suffix);
+        if start < self.r2 {
+            return;
+        }
+        if suffix == "ion" && !(start > 0 && matches!(self.word[start - 1], b's' | b't')) {
+            return;
+        }
+        self.replace_suffix(suffix, "");
+    }
+
+    fn step_5(&mut self) {
+        let len = self.word.len();
+        if self.ends_with("e") {
+            let start = len - 1;
+            if start >= self.r2
+                || (start >= self.r1 && !ends_with_short_syllable(&self.word[..start]))
+            {
+                self.word.pop();
+            }
+        } else if self.ends_with("ll") && len > self.r2 {
+            self.word.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Analyzer, KeywordMarkerFilter, LowerCaseFilter, StandardTokenizer};
+
+    use super::{stem_english, EnglishStemFilter};
+
+    fn stem(word: &str) -> String {
+        stem_english(word).unwrap_or_else(|| word.to_string())
+    }
+
+    #[test]
+    fn test_simple() {
+        let words = [
+            ("running", "run"),
+            ("runs", "run"),
+            ("generously", "generous"),
+            ("generate", "generat"),
+            ("happiness", "happi"),
+            ("caresses", "caress"),
+            ("cries", "cri"),
+            ("ties", "tie"),
+            ("gaps", "gap"),
+            ("gas", "gas"),
+            ("kiwis", "kiwi"),
+            ("hopping", "hop"),
+            ("hoped", "hope"),
+            ("agreed", "agre"),
+            ("feed", "feed"),
+            ("bled", "bled"),
+            ("cry", "cri"),
+            ("say", "say"),
+            ("boy's", "boy"),
+            ("consign", "consign"),
+            ("consignment", "consign"),
+            ("consistently", "consist"),
+            ("consolation", "consol"),
+            ("consolatory", "consolatori"),
+            ("consoled", "consol"),
+            ("consolingly", "consol"),
+            ("consolidating", "consolid"),
+            ("conspicuously", "conspicu"),
+            ("conspiracy", "conspiraci"),
+            ("conspirators", "conspir"),
+            ("constables", "constabl"),
+            ("constancy", "constanc"),
+            ("national", "nation"),
+            ("rational", "ration"),
+            ("hopefulness", "hope"),
+            ("controlling", "control"),
+            ("fully", "fulli"),
+            ("skies", "sky"),
+            ("dying", "die"),
+            ("news", "news"),
+            ("succeeding", "succeed"),
+            ("exceed", "exceed"),
+            ("communism", "communism"),
+            ("yellow", "yellow"),
+            ("at", "at"),
+            ("Running", "Running"),
+            ("café", "café"),
+        ];
+        for (word, expected) in words {
+            assert_eq!(stem(word), expected, "stemming `{}`", word);
+        }
+    }
+
+    #[test]
+    fn test_keyword() {
+        let analyzer = Analyzer::new(StandardTokenizer)
+            .add_token_filter(LowerCaseFilter)
+            .add_token_filter(KeywordMarkerFilter::new(["running"]))
+            .add_token_filter(EnglishStemFilter);
+        let texts: Vec<_> = analyzer
+            .analyze("Running runners, running flies")
+            .into_iter()
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(texts, vec!["running", "runner", "running", "fli"]);
+    }
+}
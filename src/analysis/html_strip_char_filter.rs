@@ -0,0 +1,304 @@
+use std::ops::Range;
+
+use super::{CharFilter, FilteredText};
+
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "ul",
+];
+
+// Tags whose content is not text.
+const SKIPPED_TAGS: &[&str] = &["script", "style"];
+
+const ENTITIES: &[(&str, &str)] = &[
+    ("amp", "&"),
+    ("lt", "<"),
+    ("gt", ">"),
+    ("quot", "\""),
+    ("apos", "'"),
+    ("nbsp", "\u{A0}"),
+    ("copy", "©"),
+    ("reg", "®"),
+    ("trade", "™"),
+    ("hellip", "…"),
+    ("mdash", "—"),
+    ("ndash", "–"),
+    ("lsquo", "‘"),
+    ("rsquo", "’"),
+    ("ldquo", "“"),
+    ("rdquo", "”"),
+    ("laquo", "«"),
+    ("raquo", "»"),
+    ("euro", "€"),
+    ("pound", "£"),
+    ("yen", "¥"),
+    ("deg", "°"),
+    ("middot", "·"),
+    ("times", "×"),
+    ("eacute", "é"),
+    ("egrave", "è"),
+    ("agrave", "à"),
+    ("ccedil", "ç"),
+    ("uuml", "ü"),
+    ("ouml", "ö"),
+    ("auml", "ä"),
+    ("szlig", "ß"),
+];
+
+const MAX_ENTITY_LEN: usize = 10;
+
+// Removes the HTML markup and decodes the character entities. Block-level
+// tags are replaced with a line break so that the words they separate are not
+// joined, and comments as well as the content of scripts and styles are
+// removed. A `<` that doesn't start a tag is kept as text.
+#[derive(Clone, Default)]
+pub struct HtmlStripCharFilter;
+
+impl CharFilter for HtmlStripCharFilter {
+    fn filter(&self, text: &str) -> FilteredText {
+        let mut filtered = FilteredText::new();
+        let bytes = text.as_bytes();
+        let mut copied = 0;
+        let mut offset = 0;
+        while offset < bytes.len() {
+            // Only the delimiters of CDATA sections are removed, so that the
+            // offsets of their content map one-to-one.
+            let cdata = match bytes[offset] {
+                b'<' => parse_cdata(text, offset),
+                _ => None,
+            };
+            if let Some(content) = cdata {
+                filtered.push_str(&text[copied..offset]);
+                filtered.push_replacement(offset..content.start, "");
+                filtered.push_str(&text[content.clone()]);
+                filtered.push_replacement(content.end..content.end + 3, "");
+                copied = content.end + 3;
+                offset = copied;
+                continue;
+            }
+            let markup = match bytes[offset] {
+                b'<' => parse_markup(text, offset),
+                b'&' => parse_entity(text, offset),
+                _ => None,
+            };
+            match markup {
+                Some((end, replacement)) => {
+                    filtered.push_str(&text[copied..offset]);
+                    filtered.push_replacement(offset..end, &replacement);
+                    copied = end;
+                    offset = end;
+                }
+                None => offset += 1,
+            }
+        }
+        filtered.push_str(&text[copied..]);
+        filtered
+    }
+}
+
+fn find_ignore_case(text: &str, from: usize, pattern: &str) -> Option<usize> {
+    text.as_bytes()[from..]
+        .windows(pattern.len())
+        .position(|window| window.eq_ignore_ascii_case(pattern.as_bytes()))
+        .map(|position| from + position)
+}
+
+// Returns the end of the markup starting at `start` and its replacement.
+fn parse_markup(text: &str, start: usize) -> Option<(usize, String)> {
+    let rest = &text[start..];
+    if rest.starts_with("<!--") {
+        let end = rest.find("-->").map_or(text.len(), |end| start + end + 3);
+        return Some((end, String::new()));
+    }
+    // An unterminated CDATA section is kept as text.
+    if rest.starts_with("<![CDATA[") {
+        return None;
+    }
+
+    let name_start = match rest.as_bytes().get(1)? {
+        b'/' => 2,
+        b'!' | b'?' => 1,
+        b if b.is_ascii_alphabetic() => 1,
+        _ => return None,
+    };
+    let name_len = rest[name_start..]
+        .bytes()
+        .take_while(u8::is_ascii_alphanumeric)
+        .count();
+    let name = rest[name_start..name_start + name_len].to_ascii_lowercase();
+    let mut end = start + rest.find('>')? + 1;
+
+    let is_closing = name_start == 2;
+    if !is_closing && SKIPPED_TAGS.contains(&name.as_str()) && !rest[..end - start].ends_with("/>")
+    {
+        let closing = format!("</{}", name);
+        end = match find_ignore_case(text, end, &closing) {
+            Some(closing_start) => text[closing_start..]
+                .find('>')
+                .map_or(text.len(), |len| closing_start + len + 1),
+            None => text.len(),
+        };
+    }
+    let replacement = if BLOCK_TAGS.contains(&name.as_str()) {
+        "\n"
+    } else {
+        ""
+    };
+    Some((end, replacement.to_string()))
+}
+
+// Returns the content of the CDATA section starting at `start`.
+fn parse_cdata(text: &str, start: usize) -> Option<Range<usize>> {
+    let cdata = text[start..].strip_prefix("<![CDATA[")?;
+    let len = cdata.find("]]>")?;
+    let content_start = start + 9;
+    Some(content_start..content_start + len)
+}
+
+fn parse_entity(text: &str, start: usize) -> Option<(usize, String)> {
+    let rest = &text[start + 1..];
+    let len = rest.bytes().take(MAX_ENTITY_LEN).position(|b| b == b';')?;
+    let name = &rest[..len];
+    let decoded = if let Some(code) = name.strip_prefix('#') {
+        let code = match code.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => code.parse().ok()?,
+        };
+        char::from_u32(code)?.to_string()
+    } else {
+        ENTITIES
+            .iter()
+            .find(|&&(entity, _)| entity == name)?
+            .1
+            .to_string()
+    };
+    Some((start + len + 2, decoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Analyzer, CharFilter, StandardTokenizer};
+
+    use super::HtmlStripCharFilter;
+
+    fn strip(html: &str) -> String {
+        HtmlStripCharFilter.filter(html).text().to_string()
+    }
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(
+            strip("<p>Hello <b>world</b></p><p>again</p>"),
+            "\nHello world\n\nagain\n"
+        );
+        assert_eq!(
+            strip("Tom &amp; Jerry &#233;&#xE9; &copy;"),
+            "Tom & Jerry éé ©"
+        );
+        assert_eq!(strip("a &unknown; &amp b < c"), "a &unknown; &amp b < c");
+        assert_eq!(
+            strip("<!-- note -->x<script type=\"js\">if (a < b) {}</script>y<STYLE>p {}</style>z"),
+            "xyz"
+        );
+        assert_eq!(strip("<br/>line<![CDATA[ <raw> ]]>"), "\nline <raw> ");
+        assert_eq!(strip("unclosed <b"), "unclosed <b");
+    }
+
+    #[test]
+    fn test_offsets() {
+        let html = "<h1>Caf&eacute;</h1><p>Tom&amp;Jerry <i>rock</i></p>";
+        let tokens = Analyzer::new(StandardTokenizer)
+            .add_char_filter(HtmlStripCharFilter)
+            .analyze(html);
+        let tokens: Vec<_> = tokens
+            .iter()
+            .map(|token| {
+                (
+                    token.text.as_str(),
+                    &html[token.start_offset..token.end_offset],
+                )
+            })
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                ("Café", "Caf&eacute;"),
+                ("Tom", "Tom"),
+                ("Jerry", "Jerry"),
+                ("rock", "rock"),
+            ]
+        );
+
+        let html = "<![CDATA[hello world]]> end";
+        let tokens = Analyzer::new(StandardTokenizer)
+            .add_char_filter(HtmlStripCharFilter)
+            .analyze(html);
+        let tokens: Vec<_> = tokens
+            .iter()
+            .map(|token| &html[token.start_offset..token.end_offset])
+            .collect();
+        assert_eq!(tokens, vec!["hello", "world", "end"]);
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        let html = "<p>Café</p><![CDATA[naïve ß]]>—<b>日本</b>";
+        let tokens = Analyzer::new(StandardTokenizer)
+            .add_char_filter(HtmlStripCharFilter)
+            .analyze(html);
+        let tokens: Vec<_> = tokens
+            .iter()
+            .map(|token| {
+                (
+                    token.text.as_str(),
+                    &html[token.start_offset..token.end_offset],
+                )
+            })
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                ("Café", "Café"),
+                ("naïve", "naïve"),
+                ("ß", "ß"),
+                ("日", "日"),
+                ("本", "本"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::HashSet;
+
+use super::{TokenFilter, TokenStream};
+
+// Marks the listed terms as keywords, so that the stemmers after this filter
+// leave them as they are.
+#[derive(Clone)]
+pub struct KeywordMarkerFilter {
+    keywords: HashSet<String>,
+}
+
+impl KeywordMarkerFilter {
+    pub fn new<I, S>(keywords: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            keywords: keywords.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TokenFilter for KeywordMarkerFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        Box::new(tokens.map(|mut token| {
+            if self.keywords.contains(&token.text) {
+                token.keyword = true;
+            }
+            token
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{TokenFilter, Tokenizer, WhitespaceTokenizer};
+
+    use super::KeywordMarkerFilter;
+
+    #[test]
+    fn test_simple() {
+        let filter = KeywordMarkerFilter::new(["news", "running"]);
+        let keywords: Vec<_> = filter
+            .filter(WhitespaceTokenizer.tokenize("running news feeds"))
+            .map(|token| token.keyword)
+            .collect();
+        assert_eq!(keywords, vec![true, true, false]);
+    }
+}
@@ -0,0 +1,26 @@
+use std::iter;
+
+use super::{Token, TokenStream, Tokenizer};
+
+// Emits the whole text as a single token.
+#[derive(Clone, Default)]
+pub struct KeywordTokenizer;
+
+impl Tokenizer for KeywordTokenizer {
+    fn tokenize<'a>(&'a self, text: &'a str) -> TokenStream<'a> {
+        Box::new(iter::once(Token::new(text.to_string(), 0, text.len())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Token, Tokenizer};
+
+    use super::KeywordTokenizer;
+
+    #[test]
+    fn test_simple() {
+        let tokens: Vec<_> = KeywordTokenizer.tokenize("Hello world").collect();
+        assert_eq!(tokens, vec![Token::new("Hello world".to_string(), 0, 11)]);
+    }
+}
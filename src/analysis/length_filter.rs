@@ -0,0 +1,58 @@
+use super::{TokenFilter, TokenStream};
+
+// Removes the tokens whose length in chars is out of `[min, max]`. The
+// positions of the removed tokens are kept.
+#[derive(Clone)]
+pub struct LengthFilter {
+    min: usize,
+    max: usize,
+}
+
+impl LengthFilter {
+    pub fn new(min: usize, max: usize) -> Self {
+        Self { min, max }
+    }
+}
+
+impl TokenFilter for LengthFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        let mut skipped_positions = 0;
+        Box::new(tokens.filter_map(move |mut token| {
+            let len = token.text.chars().count();
+            if len < self.min || len > self.max {
+                skipped_positions += token.position_increment;
+                None
+            } else {
+                token.position_increment += skipped_positions;
+                skipped_positions = 0;
+                Some(token)
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{TokenFilter, Tokenizer, WhitespaceTokenizer};
+
+    use super::LengthFilter;
+
+    #[test]
+    fn test_simple() {
+        let filter = LengthFilter::new(2, 5);
+        let tokenizer = WhitespaceTokenizer;
+        let tokens: Vec<_> = filter
+            .filter(tokenizer.tokenize("a bb ccc toolong dd é éé"))
+            .map(|token| (token.text, token.position_increment))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                ("bb".to_string(), 2),
+                ("ccc".to_string(), 1),
+                ("dd".to_string(), 2),
+                ("éé".to_string(), 2),
+            ]
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use super::{TokenFilter, TokenStream};
+
+#[derive(Clone, Default)]
+pub struct LowerCaseFilter;
+
+impl TokenFilter for LowerCaseFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        Box::new(tokens.map(|mut token| {
+            if token.text.chars().any(char::is_uppercase) {
+                token.text = token.text.to_lowercase();
+            }
+            token
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{TokenFilter, Tokenizer, WhitespaceTokenizer};
+
+    use super::LowerCaseFilter;
+
+    #[test]
+    fn test_simple() {
+        let tokenizer = WhitespaceTokenizer;
+        let texts: Vec<_> = LowerCaseFilter
+            .filter(tokenizer.tokenize("Hello WORLD ÉCOLE rust"))
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(texts, vec!["hello", "world", "école", "rust"]);
+    }
+}
@@ -0,0 +1,64 @@
+mod analyzer;
+mod analyzer_registry;
+mod ascii_folding_filter;
+mod char_filter;
+mod chinese_tokenizer;
+mod cjk_bigram_tokenizer;
+mod double_metaphone;
+mod edge_ngram_filter;
+mod english_stem_filter;
+mod html_strip_char_filter;
+mod keyword_marker_filter;
+mod keyword_tokenizer;
+mod length_filter;
+mod lower_case_filter;
+mod nfkc_filter;
+mod ngram_filter;
+mod pattern_replace_char_filter;
+mod phonetic_filter;
+mod shingle_filter;
+mod soundex;
+mod standard_tokenizer;
+mod stop_word_filter;
+mod synonym_graph_filter;
+mod synonym_map;
+mod token;
+mod token_filter;
+mod tokenizer;
+mod whitespace_tokenizer;
+mod width_folding_filter;
+mod word_dictionary;
+
+pub use analyzer::Analyzer;
+pub use analyzer_registry::{
+    AnalyzerRegistry, CJK_ANALYZER, ENGLISH_ANALYZER, KEYWORD_ANALYZER, STANDARD_ANALYZER,
+    WHITESPACE_ANALYZER,
+};
+pub use ascii_folding_filter::{fold_to_ascii, AsciiFoldingFilter};
+pub use char_filter::{CharFilter, FilteredText};
+pub use chinese_tokenizer::{is_han, ChineseTokenizer};
+pub use cjk_bigram_tokenizer::{is_cjk, CjkBigramTokenizer};
+pub use double_metaphone::double_metaphone;
+pub use edge_ngram_filter::EdgeNGramFilter;
+pub use english_stem_filter::{stem_english, EnglishStemFilter};
+pub use html_strip_char_filter::HtmlStripCharFilter;
+pub use keyword_marker_filter::KeywordMarkerFilter;
+pub use keyword_tokenizer::KeywordTokenizer;
+pub use length_filter::LengthFilter;
+pub use lower_case_filter::LowerCaseFilter;
+pub use nfkc_filter::NfkcFilter;
+pub use ngram_filter::NGramFilter;
+pub use pattern_replace_char_filter::PatternReplaceCharFilter;
+pub use phonetic_filter::{PhoneticEncoder, PhoneticFilter};
+pub use shingle_filter::ShingleFilter;
+pub use soundex::soundex;
+pub use standard_tokenizer::StandardTokenizer;
+pub use stop_word_filter::{StopWordFilter, ENGLISH_STOP_WORDS};
+pub use synonym_graph_filter::SynonymGraphFilter;
+pub use synonym_map::SynonymMap;
+pub use token::{Token, TokenStream};
+pub use token_filter::TokenFilter;
+pub use tokenizer::Tokenizer;
+pub use whitespace_tokenizer::WhitespaceTokenizer;
+pub use width_folding_filter::{fold_width, WidthFoldingFilter};
+pub use word_dictionary::WordDictionary;
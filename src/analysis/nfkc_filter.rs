@@ -0,0 +1,36 @@
+use unicode_normalization::{is_nfkc, UnicodeNormalization};
+
+use super::{TokenFilter, TokenStream};
+
+// Applies Unicode NFKC normalization, which composes characters and replaces
+// compatibility variants such as ligatures, full-width and half-width forms
+// and circled digits with their canonical equivalents.
+#[derive(Clone, Default)]
+pub struct NfkcFilter;
+
+impl TokenFilter for NfkcFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        Box::new(tokens.map(|mut token| {
+            if !is_nfkc(&token.text) {
+                token.text = token.text.nfkc().collect();
+            }
+            token
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{TokenFilter, Tokenizer, WhitespaceTokenizer};
+
+    use super::NfkcFilter;
+
+    #[test]
+    fn test_simple() {
+        let texts: Vec<_> = NfkcFilter
+            .filter(WhitespaceTokenizer.tokenize("ﬁle Ｒｕｓｔ ｶﾞｷ ① ㌔ cafe\u{301}"))
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(texts, vec!["file", "Rust", "ガキ", "1", "キロ", "café"]);
+    }
+}
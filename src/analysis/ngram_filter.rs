@@ -0,0 +1,155 @@
+use super::{Token, TokenFilter, TokenStream};
+
+// Replaces each token with its substrings of `min_gram` to `max_gram` chars,
+// ordered by start and then by length. All the grams of a token are at its
+// position and keep its offsets. Tokens shorter than `min_gram` are removed,
+// unless the original tokens are preserved, in which case the tokens that are
+// not a gram of themselves are emitted after their grams.
+#[derive(Clone)]
+pub struct NGramFilter {
+    min_gram: usize,
+    max_gram: usize,
+    preserve_original: bool,
+}
+
+impl NGramFilter {
+    pub fn new(min_gram: usize, max_gram: usize) -> Self {
+        assert!(
+            min_gram > 0 && min_gram <= max_gram,
+            "Invalid gram sizes [{}, {}]",
+            min_gram,
+            max_gram
+        );
+        Self {
+            min_gram,
+            max_gram,
+            preserve_original: false,
+        }
+    }
+
+    pub fn set_preserve_original(mut self) -> Self {
+        self.preserve_original = true;
+        self
+    }
+}
+
+impl TokenFilter for NGramFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        gram_tokens(tokens, self.preserve_original, move |len| {
+            (0..len).flat_map(move |start| {
+                (self.min_gram..=self.max_gram)
+                    .map(move |gram| (start, start + gram))
+                    .take_while(move |&(_, end)| end <= len)
+            })
+        })
+    }
+}
+
+// Emits the grams given as char ranges by `grams` for each token, carrying
+// forward the positions of the tokens without any.
+pub(super) fn gram_tokens<'a, F, I>(
+    tokens: TokenStream<'a>,
+    preserve_original: bool,
+    grams: F,
+) -> TokenStream<'a>
+where
+    F: Fn(usize) -> I + 'a,
+    I: Iterator<Item = (usize, usize)>,
+{
+    let mut skipped_positions = 0;
+    Box::new(tokens.flat_map(move |token| {
+        let mut boundaries: Vec<_> = token.text.char_indices().map(|(i, _)| i).collect();
+        let len = boundaries.len();
+        boundaries.push(token.text.len());
+
+        let mut gram_tokens = Vec::new();
+        let mut is_gram = false;
+        for (start, end) in grams(len) {
+            is_gram |= start == 0 && end == len;
+            gram_tokens.push(Token {
+                text: token.text[boundaries[start]..boundaries[end]].to_string(),
+                position_increment: 0,
+                ..token.clone()
+            });
+        }
+        if preserve_original && !is_gram {
+            gram_tokens.push(Token {
+                position_increment: 0,
+                ..token.clone()
+            });
+        }
+
+        match gram_tokens.first_mut() {
+            Some(first) => {
+                first.position_increment = token.position_increment + skipped_positions;
+                skipped_positions = 0;
+            }
+            None => skipped_positions += token.position_increment,
+        }
+        gram_tokens
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{TokenFilter, Tokenizer, WhitespaceTokenizer};
+
+    use super::NGramFilter;
+
+    fn grams(filter: &NGramFilter, text: &str) -> Vec<(String, u32)> {
+        filter
+            .filter(WhitespaceTokenizer.tokenize(text))
+            .map(|token| (token.text, token.position_increment))
+            .collect()
+    }
+
+    fn texts(filter: &NGramFilter, text: &str) -> Vec<String> {
+        grams(filter, text)
+            .into_iter()
+            .map(|(text, _)| text)
+            .collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        let filter = NGramFilter::new(2, 3);
+        assert_eq!(texts(&filter, "rust"), vec!["ru", "rus", "us", "ust", "st"]);
+        assert_eq!(texts(&filter, "été"), vec!["ét", "été", "té"]);
+        assert_eq!(
+            grams(&filter, "a rust"),
+            vec![
+                ("ru".to_string(), 2),
+                ("rus".to_string(), 0),
+                ("us".to_string(), 0),
+                ("ust".to_string(), 0),
+                ("st".to_string(), 0),
+            ]
+        );
+
+        let tokens: Vec<_> = filter
+            .filter(WhitespaceTokenizer.tokenize("go rust"))
+            .collect();
+        assert_eq!(tokens[1].text, "ru");
+        assert_eq!((tokens[1].start_offset, tokens[1].end_offset), (3, 7));
+    }
+
+    #[test]
+    fn test_preserve_original() {
+        let filter = NGramFilter::new(2, 3).set_preserve_original();
+        assert_eq!(texts(&filter, "a go"), vec!["a", "go"]);
+        assert_eq!(
+            texts(&filter, "rust"),
+            vec!["ru", "rus", "us", "ust", "st", "rust"]
+        );
+        assert_eq!(
+            grams(&filter, "a rust")[..2],
+            [("a".to_string(), 1), ("ru".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_gram_sizes() {
+        NGramFilter::new(3, 2);
+    }
+}
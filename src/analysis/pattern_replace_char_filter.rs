@@ -0,0 +1,114 @@
+use super::{CharFilter, FilteredText};
+
+// Replaces literal patterns, trying the longest pattern first at each
+// position, e.g. "ph" to "f" or "½" to "1/2".
+#[derive(Clone)]
+pub struct PatternReplaceCharFilter {
+    patterns: Vec<(String, String)>,
+}
+
+impl PatternReplaceCharFilter {
+    pub fn new<I, S, T>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = (S, T)>,
+        S: Into<String>,
+        T: Into<String>,
+    {
+        let mut patterns: Vec<(String, String)> = patterns
+            .into_iter()
+            .map(|(pattern, replacement)| (pattern.into(), replacement.into()))
+            .collect();
+        assert!(
+            patterns.iter().all(|(pattern, _)| !pattern.is_empty()),
+            "Patterns must not be empty"
+        );
+        patterns.sort_by_key(|(pattern, _)| std::cmp::Reverse(pattern.len()));
+        Self { patterns }
+    }
+}
+
+impl CharFilter for PatternReplaceCharFilter {
+    fn filter(&self, text: &str) -> FilteredText {
+        let mut filtered = FilteredText::new();
+        let mut copied = 0;
+        for (offset, _) in text.char_indices() {
+            if offset < copied {
+                continue;
+            }
+            let rest = &text[offset..];
+            if let Some((pattern, replacement)) = self
+                .patterns
+                .iter()
+                .find(|(pattern, _)| rest.starts_with(pattern.as_str()))
+            {
+                filtered.push_str(&text[copied..offset]);
+                filtered.push_replacement(offset..offset + pattern.len(), replacement);
+                copied = offset + pattern.len();
+            }
+        }
+        filtered.push_str(&text[copied..]);
+        filtered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Analyzer, CharFilter, StandardTokenizer, WhitespaceTokenizer};
+
+    use super::PatternReplaceCharFilter;
+
+    #[test]
+    fn test_simple() {
+        let filter =
+            PatternReplaceCharFilter::new([("ph", "f"), ("p", "P"), ("½", "1/2"), ("-", "")]);
+        assert_eq!(
+            filter.filter("phone pet ½ co-op").text(),
+            "fone Pet 1/2 cooP"
+        );
+
+        let text = "½ phone co-op";
+        let tokens = Analyzer::new(WhitespaceTokenizer)
+            .add_char_filter(filter)
+            .analyze(text);
+        let tokens: Vec<_> = tokens
+            .iter()
+            .map(|token| {
+                (
+                    token.text.as_str(),
+                    &text[token.start_offset..token.end_offset],
+                )
+            })
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![("1/2", "½"), ("fone", "phone"), ("cooP", "co-op")]
+        );
+    }
+
+    #[test]
+    fn test_split_replacement() {
+        // Tokens within a replacement span the whole replaced text.
+        let analyzer = Analyzer::new(StandardTokenizer)
+            .add_char_filter(PatternReplaceCharFilter::new([("½", "1/2"), ("ß", "ss")]));
+        let text = "½ x 2½ straße";
+        let tokens: Vec<_> = analyzer
+            .analyze(text)
+            .iter()
+            .map(|token| {
+                (
+                    token.text.clone(),
+                    &text[token.start_offset..token.end_offset],
+                )
+            })
+            .collect();
+        let expected: Vec<(String, &str)> = vec![
+            ("1".into(), "½"),
+            ("2".into(), "½"),
+            ("x".into(), "x"),
+            ("21".into(), "2½"),
+            ("2".into(), "½"),
+            ("strasse".into(), "straße"),
+        ];
+        assert_eq!(tokens, expected);
+    }
+}
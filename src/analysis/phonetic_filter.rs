@@ -0,0 +1,97 @@
+use super::{double_metaphone, soundex, Token, TokenFilter, TokenStream};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhoneticEncoder {
+    Soundex,
+    // Emits the alternate code as well when it differs from the primary one.
+    DoubleMetaphone,
+}
+
+impl PhoneticEncoder {
+    fn encode(&self, word: &str) -> Vec<String> {
+        match self {
+            PhoneticEncoder::Soundex => soundex(word).into_iter().collect(),
+            PhoneticEncoder::DoubleMetaphone => {
+                let (primary, alternate) = double_metaphone(word);
+                let mut codes = Vec::new();
+                if !primary.is_empty() {
+                    codes.push(primary.clone());
+                }
+                if !alternate.is_empty() && alternate != primary {
+                    codes.push(alternate);
+                }
+                codes
+            }
+        }
+    }
+}
+
+// Emits the phonetic codes of each token after it, at the same position, so
+// that names sounding alike match.
+#[derive(Clone)]
+pub struct PhoneticFilter {
+    encoder: PhoneticEncoder,
+}
+
+impl PhoneticFilter {
+    pub fn new(encoder: PhoneticEncoder) -> Self {
+        Self { encoder }
+    }
+}
+
+impl TokenFilter for PhoneticFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        Box::new(tokens.flat_map(move |token| {
+            let codes = self.encoder.encode(&token.text);
+            let mut tokens = vec![token];
+            for code in codes {
+                if code != tokens[0].text {
+                    tokens.push(Token {
+                        text: code,
+                        position_increment: 0,
+                        keyword: false,
+                        ..tokens[0].clone()
+                    });
+                }
+            }
+            tokens
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{TokenFilter, Tokenizer, WhitespaceTokenizer};
+
+    use super::{PhoneticEncoder, PhoneticFilter};
+
+    fn tokens(encoder: PhoneticEncoder, text: &str) -> Vec<(String, u32)> {
+        PhoneticFilter::new(encoder)
+            .filter(WhitespaceTokenizer.tokenize(text))
+            .map(|token| (token.text, token.position_increment))
+            .collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(
+            tokens(PhoneticEncoder::Soundex, "robert 42"),
+            vec![
+                ("robert".to_string(), 1),
+                ("R163".to_string(), 0),
+                ("42".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            tokens(PhoneticEncoder::DoubleMetaphone, "smith jones"),
+            vec![
+                ("smith".to_string(), 1),
+                ("SM0".to_string(), 0),
+                ("XMT".to_string(), 0),
+                ("jones".to_string(), 1),
+                ("JNS".to_string(), 0),
+                ("ANS".to_string(), 0),
+            ]
+        );
+    }
+}
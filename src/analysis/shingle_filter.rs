@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+
+use super::{Token, TokenFilter, TokenStream};
+
+const DEFAULT_SEPARATOR: &str = " ";
+
+// Emits, after each token, the shingles of `min_size` to `max_size` tokens
+// starting with it, joined by the separator. Shingles are at the position of
+// their first token, span the positions of all their tokens, and don't cross
+// the gaps left by removed tokens.
+#[derive(Clone)]
+pub struct ShingleFilter {
+    min_size: usize,
+    max_size: usize,
+    separator: String,
+}
+
+impl ShingleFilter {
+    pub fn new(min_size: usize, max_size: usize) -> Self {
+        assert!(
+            min_size >= 2 && min_size <= max_size,
+            "Invalid shingle sizes [{}, {}]",
+            min_size,
+            max_size
+        );
+        Self {
+            min_size,
+            max_size,
+            separator: DEFAULT_SEPARATOR.to_string(),
+        }
+    }
+
+    pub fn set_separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+}
+
+impl TokenFilter for ShingleFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        Box::new(ShingleStream {
+            filter: self,
+            tokens,
+            window: VecDeque::new(),
+            output: VecDeque::new(),
+        })
+    }
+}
+
+struct ShingleStream<'a> {
+    filter: &'a ShingleFilter,
+    tokens: TokenStream<'a>,
+    window: VecDeque<Token>,
+    output: VecDeque<Token>,
+}
+
+impl<'a> Iterator for ShingleStream<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        if let Some(token) = self.output.pop_front() {
+            return Some(token);
+        }
+        while self.window.len() < self.filter.max_size {
+            match self.tokens.next() {
+                Some(token) => self.window.push_back(token),
+                None => break,
+            }
+        }
+        let first = self.window.pop_front()?;
+        let mut text = first.text.clone();
+        let mut size = 1;
+        for token in &self.window {
+            if size == self.filter.max_size || token.position_increment != 1 {
+                break;
+            }
+            text.push_str(&self.filter.separator);
+            text.push_str(&token.text);
+            size += 1;
+            if size >= self.filter.min_size {
+                self.output.push_back(Token {
+                    text: text.clone(),
+                    position_increment: 0,
+                    position_length: size as u32,
+                    start_offset: first.start_offset,
+                    end_offset: token.end_offset,
+                    keyword: false,
+                });
+            }
+        }
+        Some(first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Analyzer, StopWordFilter, TokenFilter, Tokenizer, WhitespaceTokenizer};
+
+    use super::ShingleFilter;
+
+    #[test]
+    fn test_simple() {
+        let filter = ShingleFilter::new(2, 3);
+        let tokens: Vec<_> = filter
+            .filter(WhitespaceTokenizer.tokenize("please divide this"))
+            .map(|token| {
+                (
+                    token.text,
+                    token.position_increment,
+                    token.position_length,
+                    token.start_offset,
+                    token.end_offset,
+                )
+            })
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                ("please".to_string(), 1, 1, 0, 6),
+                ("please divide".to_string(), 0, 2, 0, 13),
+                ("please divide this".to_string(), 0, 3, 0, 18),
+                ("divide".to_string(), 1, 1, 7, 13),
+                ("divide this".to_string(), 0, 2, 7, 18),
+                ("this".to_string(), 1, 1, 14, 18),
+            ]
+        );
+
+        let filter = ShingleFilter::new(3, 3).set_separator("_");
+        let texts: Vec<_> = filter
+            .filter(WhitespaceTokenizer.tokenize("a b c d"))
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(texts, vec!["a", "a_b_c", "b", "b_c_d", "c", "d"]);
+    }
+
+    #[test]
+    fn test_position_gaps() {
+        let analyzer = Analyzer::new(WhitespaceTokenizer)
+            .add_token_filter(StopWordFilter::new(["the"]))
+            .add_token_filter(ShingleFilter::new(2, 2));
+        let texts: Vec<_> = analyzer
+            .analyze("over the lazy dog")
+            .into_iter()
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(texts, vec!["over", "lazy", "lazy dog", "dog"]);
+    }
+}
@@ -0,0 +1,73 @@
+fn soundex_code(c: char) -> Option<char> {
+    let code = match c {
+        'B' | 'F' | 'P' | 'V' => '1',
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => '2',
+        'D' | 'T' => '3',
+        'L' => '4',
+        'M' | 'N' => '5',
+        'R' => '6',
+        _ => return None,
+    };
+    Some(code)
+}
+
+// Encodes a name as its first letter followed by three digits coding the
+// following consonants, e.g. "Robert" and "Rupert" to "R163". Consonants of
+// the same code are coded once unless separated by a vowel. Only ASCII letters
+// are considered, and None is returned when there are none.
+pub fn soundex(word: &str) -> Option<String> {
+    let mut letters = word
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase());
+    let first = letters.next()?;
+    let mut code = String::with_capacity(4);
+    code.push(first);
+    let mut last_code = soundex_code(first);
+    for c in letters {
+        if code.len() == 4 {
+            break;
+        }
+        match c {
+            'H' | 'W' => {}
+            _ => {
+                let letter_code = soundex_code(c);
+                if let Some(digit) = letter_code {
+                    if letter_code != last_code {
+                        code.push(digit);
+                    }
+                }
+                last_code = letter_code;
+            }
+        }
+    }
+    while code.len() < 4 {
+        code.push('0');
+    }
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::soundex;
+
+    #[test]
+    fn test_simple() {
+        let names = [
+            ("Robert", "R163"),
+            ("Rupert", "R163"),
+            ("Rubin", "R150"),
+            ("Ashcraft", "A261"),
+            ("Ashcroft", "A261"),
+            ("Tymczak", "T522"),
+            ("Pfister", "P236"),
+            ("Honeyman", "H555"),
+            ("lee", "L000"),
+            ("O'Hara", "O600"),
+        ];
+        for (name, code) in names {
+            assert_eq!(soundex(name).as_deref(), Some(code), "encoding `{}`", name);
+        }
+        assert!(soundex("123").is_none());
+    }
+}
@@ -0,0 +1,51 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{Token, TokenStream, Tokenizer};
+
+// Splits text on Unicode word boundaries (UAX #29), keeping the words that
+// contain at least one alphanumeric character.
+#[derive(Clone, Default)]
+pub struct StandardTokenizer;
+
+impl Tokenizer for StandardTokenizer {
+    fn tokenize<'a>(&'a self, text: &'a str) -> TokenStream<'a> {
+        Box::new(
+            text.split_word_bound_indices()
+                .filter(|(_, word)| word.chars().any(char::is_alphanumeric))
+                .map(|(start, word)| Token::new(word.to_string(), start, start + word.len())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Token, Tokenizer};
+
+    use super::StandardTokenizer;
+
+    fn texts(text: &str) -> Vec<String> {
+        StandardTokenizer
+            .tokenize(text)
+            .map(|token| token.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        let tokens: Vec<_> = StandardTokenizer.tokenize("Hello, world!").collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::new("Hello".to_string(), 0, 5),
+                Token::new("world".to_string(), 7, 12),
+            ]
+        );
+
+        assert_eq!(
+            texts("The quick (\"brown\") fox can't jump 32.3 feet, right?"),
+            vec!["The", "quick", "brown", "fox", "can't", "jump", "32.3", "feet", "right"]
+        );
+        assert_eq!(texts("Crème brûlée"), vec!["Crème", "brûlée"]);
+        assert!(texts(" -- ... ").is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+
+use super::{TokenFilter, TokenStream};
+
+pub const ENGLISH_STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+// Removes the stop words. The positions of the removed tokens are kept, so
+// phrases don't match across them.
+#[derive(Clone)]
+pub struct StopWordFilter {
+    words: HashSet<String>,
+}
+
+impl StopWordFilter {
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            words: words.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn english() -> Self {
+        Self::new(ENGLISH_STOP_WORDS.iter().copied())
+    }
+}
+
+impl TokenFilter for StopWordFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        let mut skipped_positions = 0;
+        Box::new(tokens.filter_map(move |mut token| {
+            if self.words.contains(&token.text) {
+                skipped_positions += token.position_increment;
+                None
+            } else {
+                token.position_increment += skipped_positions;
+                skipped_positions = 0;
+                Some(token)
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{TokenFilter, Tokenizer, WhitespaceTokenizer};
+
+    use super::StopWordFilter;
+
+    #[test]
+    fn test_simple() {
+        let filter = StopWordFilter::english();
+        let tokenizer = WhitespaceTokenizer;
+        let tokens: Vec<_> = filter
+            .filter(tokenizer.tokenize("the quick fox is in the box"))
+            .map(|token| (token.text, token.position_increment))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                ("quick".to_string(), 2),
+                ("fox".to_string(), 1),
+                ("box".to_string(), 4),
+            ]
+        );
+
+        let filter = StopWordFilter::new(["foo"]);
+        let texts: Vec<_> = filter
+            .filter(tokenizer.tokenize("foo bar the"))
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(texts, vec!["bar", "the"]);
+    }
+}
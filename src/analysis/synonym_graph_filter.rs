@@ -0,0 +1,349 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use super::{SynonymMap, Token, TokenFilter, TokenStream};
+
+// Replaces the longest sequences of tokens found in the synonym map with
+// their synonyms, forming a graph where all the synonyms start at the
+// position of the first token and end at the position following the last one.
+// Each word of a synonym after the first one is given a position of its own,
+// and the position lengths tell where each token ends. Synonyms keep the
+// offsets of the whole matched sequence, while the original tokens keep their
+// own.
+//
+// The index doesn't record position lengths, so the graph is best used in
+// search analyzers, where `graph_phrase_query` turns it into the phrases of
+// all its paths. Analyzers used for indexing must flatten it with
+// `set_flatten`.
+#[derive(Clone)]
+pub struct SynonymGraphFilter {
+    synonym_map: Arc<SynonymMap>,
+    flatten: bool,
+}
+
+impl SynonymGraphFilter {
+    pub fn new(synonym_map: Arc<SynonymMap>) -> Self {
+        Self {
+            synonym_map,
+            flatten: false,
+        }
+    }
+
+    // Fits every synonym into the positions of the sequence it replaces,
+    // rather than giving each word a position of its own. The original tokens
+    // keep their positions, and so do the following tokens, so that phrases
+    // of the original text still match. A synonym with more words than the
+    // sequence stacks some of them at the same position, and one with fewer
+    // words spreads them out, so phrases going through a synonym with another
+    // number of words than the indexed text may not match.
+    pub fn set_flatten(mut self) -> Self {
+        self.flatten = true;
+        self
+    }
+}
+
+impl TokenFilter for SynonymGraphFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        Box::new(SynonymGraphStream {
+            synonym_map: &self.synonym_map,
+            flatten: self.flatten,
+            tokens,
+            lookahead: VecDeque::new(),
+            output: VecDeque::new(),
+            input_position: -1,
+            output_position: -1,
+            shift: 0,
+        })
+    }
+}
+
+struct SynonymGraphStream<'a> {
+    synonym_map: &'a SynonymMap,
+    flatten: bool,
+    tokens: TokenStream<'a>,
+    lookahead: VecDeque<Token>,
+    output: VecDeque<Token>,
+    input_position: i64,
+    output_position: i64,
+    // The difference between the output and input positions, from the
+    // positions inserted or removed by the previous synonyms.
+    shift: i64,
+}
+
+impl<'a> SynonymGraphStream<'a> {
+    fn longest_match(&self) -> Option<(usize, &'a [Vec<String>])> {
+        let mut words = Vec::new();
+        let mut longest = None;
+        for (i, token) in self.lookahead.iter().enumerate() {
+            if i > 0 && token.position_increment != 1 {
+                break;
+            }
+            words.push(token.text.clone());
+            if let Some(synonyms) = self.synonym_map.get(&words) {
+                longest = Some((i + 1, synonyms));
+            }
+        }
+        longest
+    }
+
+    fn push_output(&mut self, mut token: Token, position: i64) {
+        token.position_increment = (position - self.output_position) as u32;
+        self.output_position = position;
+        self.output.push_back(token);
+    }
+}
+
+impl<'a> Iterator for SynonymGraphStream<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        if let Some(token) = self.output.pop_front() {
+            return Some(token);
+        }
+        while self.lookahead.len() < self.synonym_map.max_input_len().max(1) {
+            match self.tokens.next() {
+                Some(token) => self.lookahead.push_back(token),
+                None => break,
+            }
+        }
+        let (len, synonyms) = match self.longest_match() {
+            Some(longest) => longest,
+            None => {
+                let token = self.lookahead.pop_front()?;
+                self.input_position += token.position_increment as i64;
+                self.push_output(token, self.input_position + self.shift);
+                return self.output.pop_front();
+            }
+        };
+
+        let matched: Vec<_> = self.lookahead.drain(..len).collect();
+        let start_position = self.input_position + matched[0].position_increment as i64;
+        self.input_position = start_position + len as i64 - 1;
+        let start = start_position + self.shift;
+        let (start_offset, end_offset) = (matched[0].start_offset, matched[len - 1].end_offset);
+        let words: Vec<_> = matched.iter().map(|token| &token.text).collect();
+        let synonym_tokens = |synonym: &Vec<String>| -> Vec<Token> {
+            if synonym.iter().eq(words.iter().copied()) {
+                matched.clone()
+            } else {
+                synonym
+                    .iter()
+                    .map(|word| Token {
+                        text: word.clone(),
+                        start_offset,
+                        end_offset,
+                        ..matched[0].clone()
+                    })
+                    .collect()
+            }
+        };
+
+        if self.flatten {
+            // The word `i` of `n` goes at `i * len / n` past the start.
+            let mut graph = Vec::new();
+            for synonym in synonyms {
+                let tokens = synonym_tokens(synonym);
+                let n = tokens.len() as i64;
+                for (i, mut token) in tokens.into_iter().enumerate() {
+                    let i = i as i64;
+                    let position = start + i * len as i64 / n;
+                    let end = start + (i + 1) * len as i64 / n;
+                    token.position_length = (end - position).max(1) as u32;
+                    graph.push((position, token));
+                }
+            }
+            graph.sort_by_key(|&(position, _)| position);
+            for (position, token) in graph {
+                self.push_output(token, position);
+            }
+            return self.output.pop_front();
+        }
+
+        // Each synonym goes through positions of its own between the start and
+        // the end, which is only known once they are all allocated.
+        let mut next_position = start + 1;
+        let mut paths = Vec::new();
+        for synonym in synonyms {
+            let tokens = synonym_tokens(synonym);
+            let mut positions = vec![start];
+            for _ in 1..tokens.len() {
+                positions.push(next_position);
+                next_position += 1;
+            }
+            paths.push((tokens, positions));
+        }
+        let end = next_position;
+
+        let mut graph = Vec::new();
+        for (tokens, mut positions) in paths {
+            positions.push(end);
+            for (i, mut token) in tokens.into_iter().enumerate() {
+                token.position_length = (positions[i + 1] - positions[i]) as u32;
+                graph.push((positions[i], token));
+            }
+        }
+        graph.sort_by_key(|&(position, _)| position);
+        for (position, token) in graph {
+            self.push_output(token, position);
+        }
+        self.shift = end - self.input_position - 1;
+        self.output.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::analysis::{
+        Analyzer, StopWordFilter, SynonymMap, TokenFilter, Tokenizer, WhitespaceTokenizer,
+    };
+
+    use super::SynonymGraphFilter;
+
+    fn filter(rules: &str) -> SynonymGraphFilter {
+        SynonymGraphFilter::new(Arc::new(SynonymMap::load(rules.as_bytes()).unwrap()))
+    }
+
+    // The text, position and position length of each token.
+    fn graph(filter: &SynonymGraphFilter, text: &str) -> Vec<(String, u32, u32)> {
+        let mut position = 0;
+        filter
+            .filter(WhitespaceTokenizer.tokenize(text))
+            .map(|token| {
+                position += token.position_increment;
+                (token.text, position - 1, token.position_length)
+            })
+            .collect()
+    }
+
+    fn node(text: &str, position: u32, position_length: u32) -> (String, u32, u32) {
+        (text.to_string(), position, position_length)
+    }
+
+    #[test]
+    fn test_simple() {
+        let filter = filter("new york, ny\nfast => quick");
+        assert_eq!(
+            graph(&filter, "visit new york city"),
+            vec![
+                node("visit", 0, 1),
+                node("new", 1, 1),
+                node("ny", 1, 2),
+                node("york", 2, 1),
+                node("city", 3, 1),
+            ]
+        );
+        assert_eq!(
+            graph(&filter, "ny city"),
+            vec![
+                node("new", 0, 1),
+                node("ny", 0, 2),
+                node("york", 1, 1),
+                node("city", 2, 1),
+            ]
+        );
+        assert_eq!(
+            graph(&filter, "fast new car"),
+            vec![node("quick", 0, 1), node("new", 1, 1), node("car", 2, 1)]
+        );
+
+        let tokens: Vec<_> = filter
+            .filter(WhitespaceTokenizer.tokenize("ny new york"))
+            .map(|token| (token.text, token.start_offset, token.end_offset))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                ("new".to_string(), 0, 2),
+                ("ny".to_string(), 0, 2),
+                ("york".to_string(), 0, 2),
+                ("new".to_string(), 3, 6),
+                ("ny".to_string(), 3, 11),
+                ("york".to_string(), 7, 11),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multi_word_synonyms() {
+        let filter = filter("wifi, wi fi, wireless network\nusa => united states of america");
+        assert_eq!(
+            graph(&filter, "wifi ok"),
+            vec![
+                node("wifi", 0, 3),
+                node("wi", 0, 1),
+                node("wireless", 0, 2),
+                node("fi", 1, 2),
+                node("network", 2, 1),
+                node("ok", 3, 1),
+            ]
+        );
+        assert_eq!(
+            graph(&filter, "usa a"),
+            vec![
+                node("united", 0, 1),
+                node("states", 1, 1),
+                node("of", 2, 1),
+                node("america", 3, 1),
+                node("a", 4, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flatten() {
+        let filter = filter("wifi, wireless network\nnew york, ny").set_flatten();
+        assert_eq!(
+            graph(&filter, "free wifi ok"),
+            vec![
+                node("free", 0, 1),
+                node("wifi", 1, 1),
+                node("wireless", 1, 1),
+                node("network", 1, 1),
+                node("ok", 2, 1),
+            ]
+        );
+        assert_eq!(
+            graph(&filter, "visit new york city"),
+            vec![
+                node("visit", 0, 1),
+                node("new", 1, 1),
+                node("ny", 1, 2),
+                node("york", 2, 1),
+                node("city", 3, 1),
+            ]
+        );
+        assert_eq!(
+            graph(&filter, "ny city"),
+            vec![
+                node("new", 0, 1),
+                node("york", 0, 1),
+                node("ny", 0, 1),
+                node("city", 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_position_gaps() {
+        let synonym_map = Arc::new(SynonymMap::load("new york, ny".as_bytes()).unwrap());
+        let analyzer = Analyzer::new(WhitespaceTokenizer)
+            .add_token_filter(StopWordFilter::new(["the"]))
+            .add_token_filter(SynonymGraphFilter::new(synonym_map));
+        let increments: Vec<_> = analyzer
+            .analyze("the ny the new the york")
+            .into_iter()
+            .map(|token| (token.text, token.position_increment))
+            .collect();
+        assert_eq!(
+            increments,
+            vec![
+                ("new".to_string(), 2),
+                ("ny".to_string(), 0),
+                ("york".to_string(), 1),
+                ("new".to_string(), 2),
+                ("york".to_string(), 2),
+            ]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead},
+};
+
+// Maps sequences of words to the sequences of words they are synonyms of.
+pub struct SynonymMap {
+    synonyms: HashMap<Vec<String>, Vec<Vec<String>>>,
+    max_input_len: usize,
+}
+
+impl Default for SynonymMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn split_words(phrase: &str) -> Vec<String> {
+    phrase.split_whitespace().map(str::to_string).collect()
+}
+
+impl SynonymMap {
+    pub fn new() -> Self {
+        Self {
+            synonyms: HashMap::new(),
+            max_input_len: 0,
+        }
+    }
+
+    // Loads rules in the Solr format, one per line:
+    //
+    //   # Equivalent phrases, each one matching all of them.
+    //   new york, ny, big apple
+    //   # Phrases on the left replaced by all the phrases on the right.
+    //   colour, color => color
+    pub fn load<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut synonym_map = Self::new();
+        for (line_number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parse_phrases = |phrases: &str| {
+                phrases
+                    .split(',')
+                    .map(|phrase| {
+                        let words = split_words(phrase);
+                        if words.is_empty() {
+                            Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("empty phrase at line {}", line_number + 1),
+                            ))
+                        } else {
+                            Ok(words)
+                        }
+                    })
+                    .collect::<io::Result<Vec<_>>>()
+            };
+            match line.split_once("=>") {
+                Some((inputs, outputs)) => {
+                    let outputs = parse_phrases(outputs)?;
+                    for input in parse_phrases(inputs)? {
+                        for output in &outputs {
+                            synonym_map.add_words(input.clone(), output.clone());
+                        }
+                    }
+                }
+                None => {
+                    let phrases = parse_phrases(line)?;
+                    for input in &phrases {
+                        for output in &phrases {
+                            synonym_map.add_words(input.clone(), output.clone());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(synonym_map)
+    }
+
+    // Adds `output` as a synonym of `input`, both being whitespace separated
+    // words. The input is replaced with its synonyms, so it must also be added
+    // as a synonym of itself to be kept.
+    pub fn add(&mut self, input: &str, output: &str) {
+        let (input, output) = (split_words(input), split_words(output));
+        assert!(
+            !input.is_empty() && !output.is_empty(),
+            "Synonyms must have at least one word"
+        );
+        self.add_words(input, output);
+    }
+
+    fn add_words(&mut self, input: Vec<String>, output: Vec<String>) {
+        self.max_input_len = self.max_input_len.max(input.len());
+        let outputs = self.synonyms.entry(input).or_default();
+        if !outputs.contains(&output) {
+            outputs.push(output);
+        }
+    }
+
+    pub fn get(&self, input: &[String]) -> Option<&[Vec<String>]> {
+        self.synonyms.get(input).map(Vec::as_slice)
+    }
+
+    pub fn max_input_len(&self) -> usize {
+        self.max_input_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.synonyms.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SynonymMap;
+
+    fn words(phrase: &str) -> Vec<String> {
+        phrase.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_load() {
+        let rules = "# comment\n\nnew york, ny\ncolour, color => color\n";
+        let synonym_map = SynonymMap::load(rules.as_bytes()).unwrap();
+        assert_eq!(synonym_map.max_input_len(), 2);
+        assert_eq!(
+            synonym_map.get(&words("new york")).unwrap(),
+            &[words("new york"), words("ny")]
+        );
+        assert_eq!(
+            synonym_map.get(&words("ny")).unwrap(),
+            &[words("new york"), words("ny")]
+        );
+        assert_eq!(
+            synonym_map.get(&words("colour")).unwrap(),
+            &[words("color")]
+        );
+        assert_eq!(synonym_map.get(&words("color")).unwrap(), &[words("color")]);
+        assert!(synonym_map.get(&words("york")).is_none());
+
+        assert!(SynonymMap::load("a, , b".as_bytes()).is_err());
+        assert!(SynonymMap::load("=> b".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+// A term produced by analysis.
+//
+// The position of a token is the position of the previous token plus its
+// increment, starting from -1 before the first token. Offsets are byte offsets
+// into the original text. A token spans `position_length` positions, more
+// than one for a synonym of several words. Keyword tokens are protected from
+// stemming.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub text: String,
+    pub position_increment: u32,
+    pub position_length: u32,
+    pub start_offset: usize,
+    pub end_offset: usize,
+    pub keyword: bool,
+}
+
+impl Token {
+    pub fn new(text: String, start_offset: usize, end_offset: usize) -> Self {
+        Self {
+            text,
+            position_increment: 1,
+            position_length: 1,
+            start_offset,
+            end_offset,
+            keyword: false,
+        }
+    }
+}
+
+pub type TokenStream<'a> = Box<dyn Iterator<Item = Token> + 'a>;
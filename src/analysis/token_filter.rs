@@ -0,0 +1,5 @@
+use super::TokenStream;
+
+pub trait TokenFilter: Send + Sync {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a>;
+}
@@ -0,0 +1,5 @@
+use super::TokenStream;
+
+pub trait Tokenizer: Send + Sync {
+    fn tokenize<'a>(&'a self, text: &'a str) -> TokenStream<'a>;
+}
@@ -0,0 +1,36 @@
+use super::{Token, TokenStream, Tokenizer};
+
+#[derive(Clone, Default)]
+pub struct WhitespaceTokenizer;
+
+impl Tokenizer for WhitespaceTokenizer {
+    fn tokenize<'a>(&'a self, text: &'a str) -> TokenStream<'a> {
+        Box::new(text.split_whitespace().map(move |word| {
+            let start = word.as_ptr() as usize - text.as_ptr() as usize;
+            Token::new(word.to_string(), start, start + word.len())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Token, Tokenizer};
+
+    use super::WhitespaceTokenizer;
+
+    #[test]
+    fn test_simple() {
+        let tokens: Vec<_> = WhitespaceTokenizer
+            .tokenize(" Hello,  world!\tfoo ")
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::new("Hello,".to_string(), 1, 7),
+                Token::new("world!".to_string(), 9, 15),
+                Token::new("foo".to_string(), 16, 19),
+            ]
+        );
+        assert_eq!(WhitespaceTokenizer.tokenize("   ").count(), 0);
+    }
+}
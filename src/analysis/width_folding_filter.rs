@@ -0,0 +1,96 @@
+use super::{TokenFilter, TokenStream};
+
+// Folds full-width ASCII variants to basic Latin, and half-width katakana to
+// their full-width forms, composing the voiced and semi-voiced sound marks
+// with the kana before them.
+#[derive(Clone, Default)]
+pub struct WidthFoldingFilter;
+
+impl TokenFilter for WidthFoldingFilter {
+    fn filter<'a>(&'a self, tokens: TokenStream<'a>) -> TokenStream<'a> {
+        Box::new(tokens.map(|mut token| {
+            if token
+                .text
+                .chars()
+                .any(|c| matches!(c, '\u{3000}' | '\u{FF01}'..='\u{FF9F}'))
+            {
+                token.text = fold_width(&token.text);
+            }
+            token
+        }))
+    }
+}
+
+const HALF_WIDTH_KANA: [char; 59] = [
+    '・', 'ヲ', 'ァ', 'ィ', 'ゥ', 'ェ', 'ォ', 'ャ', 'ュ', 'ョ', 'ッ', 'ー', 'ア', 'イ', 'ウ', 'エ',
+    'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ', 'ソ', 'タ', 'チ', 'ツ', 'テ', 'ト',
+    'ナ', 'ニ', 'ヌ', 'ネ', 'ノ', 'ハ', 'ヒ', 'フ', 'ヘ', 'ホ', 'マ', 'ミ', 'ム', 'メ', 'モ', 'ヤ',
+    'ユ', 'ヨ', 'ラ', 'リ', 'ル', 'レ', 'ロ', 'ワ', 'ン', '\u{3099}', '\u{309A}',
+];
+
+fn compose_voiced(c: char) -> Option<char> {
+    match c {
+        'ウ' => Some('ヴ'),
+        'カ' | 'キ' | 'ク' | 'ケ' | 'コ' | 'サ' | 'シ' | 'ス' | 'セ' | 'ソ' | 'タ' | 'チ'
+        | 'ツ' | 'テ' | 'ト' | 'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' => {
+            char::from_u32(c as u32 + 1)
+        }
+        _ => None,
+    }
+}
+
+fn compose_semi_voiced(c: char) -> Option<char> {
+    match c {
+        'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' => char::from_u32(c as u32 + 2),
+        _ => None,
+    }
+}
+
+pub fn fold_width(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\u{3000}' => folded.push(' '),
+            '\u{FF01}'..='\u{FF5E}' => folded.push(char::from_u32(c as u32 - 0xFEE0).unwrap()),
+            '\u{FF9E}' | '\u{FF9F}' => {
+                let compose = if c == '\u{FF9E}' {
+                    compose_voiced
+                } else {
+                    compose_semi_voiced
+                };
+                match folded.chars().last().and_then(compose) {
+                    Some(composed) => {
+                        folded.pop();
+                        folded.push(composed);
+                    }
+                    None => folded.push(HALF_WIDTH_KANA[c as usize - 0xFF65]),
+                }
+            }
+            '\u{FF65}'..='\u{FF9D}' => folded.push(HALF_WIDTH_KANA[c as usize - 0xFF65]),
+            _ => folded.push(c),
+        }
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{TokenFilter, Tokenizer, WhitespaceTokenizer};
+
+    use super::{fold_width, WidthFoldingFilter};
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(fold_width("Ｒｕｓｔ　１．７０！"), "Rust 1.70!");
+        assert_eq!(fold_width("ｶﾞｷﾞﾊﾟｰﾃｨｰ"), "ガギパーティー");
+        assert_eq!(fold_width("ｳﾞｧｲｵﾘﾝ"), "ヴァイオリン");
+        assert_eq!(fold_width("ｱﾟ"), "ア\u{309A}");
+        assert_eq!(fold_width("ガキ"), "ガキ");
+
+        let texts: Vec<_> = WidthFoldingFilter
+            .filter(WhitespaceTokenizer.tokenize("ＡＢＣ abc ﾃｽﾄ"))
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(texts, vec!["ABC", "abc", "テスト"]);
+    }
+}
@@ -0,0 +1,172 @@
+use std::io::{self, BufRead};
+
+struct TrieNode {
+    children: Vec<(char, usize)>,
+    freq: Option<u64>,
+}
+
+impl TrieNode {
+    fn new() -> Self {
+        Self {
+            children: Vec::new(),
+            freq: None,
+        }
+    }
+
+    fn child(&self, c: char) -> Option<usize> {
+        self.children
+            .binary_search_by_key(&c, |&(child, _)| child)
+            .ok()
+            .map(|index| self.children[index].1)
+    }
+}
+
+// Words and their frequencies, stored in a trie so that all the words
+// starting at a given char can be found in a single walk.
+pub struct WordDictionary {
+    nodes: Vec<TrieNode>,
+    total_freq: u64,
+    word_count: usize,
+}
+
+impl Default for WordDictionary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordDictionary {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![TrieNode::new()],
+            total_freq: 0,
+            word_count: 0,
+        }
+    }
+
+    // Loads a dictionary with one `word freq` entry per line. Anything after
+    // the frequency, such as a part-of-speech tag, is ignored, and a missing
+    // frequency defaults to one.
+    pub fn load<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut dictionary = Self::new();
+        for (line_number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let mut parts = line.split_whitespace();
+            let word = match parts.next() {
+                Some(word) => word,
+                None => continue,
+            };
+            let freq = match parts.next() {
+                Some(freq) => freq.parse().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid frequency `{}` at line {}", freq, line_number + 1),
+                    )
+                })?,
+                None => 1,
+            };
+            dictionary.add_word(word, freq);
+        }
+        Ok(dictionary)
+    }
+
+    pub fn add_word(&mut self, word: &str, freq: u64) {
+        let mut node = 0;
+        for c in word.chars() {
+            node = match self.nodes[node].child(c) {
+                Some(child) => child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(TrieNode::new());
+                    let children = &mut self.nodes[node].children;
+                    let index = children.partition_point(|&(other, _)| other < c);
+                    children.insert(index, (c, child));
+                    child
+                }
+            };
+        }
+        match self.nodes[node].freq.replace(freq) {
+            Some(old_freq) => self.total_freq -= old_freq,
+            None => self.word_count += 1,
+        }
+        self.total_freq += freq;
+    }
+
+    pub fn freq(&self, word: &str) -> Option<u64> {
+        let mut node = 0;
+        for c in word.chars() {
+            node = self.nodes[node].child(c)?;
+        }
+        self.nodes[node].freq
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.freq(word).is_some()
+    }
+
+    pub fn total_freq(&self) -> u64 {
+        self.total_freq
+    }
+
+    pub fn len(&self) -> usize {
+        self.word_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.word_count == 0
+    }
+
+    // Returns the char length and frequency of each word that `chars` starts
+    // with, shortest first.
+    pub fn prefixes(&self, chars: &[char]) -> Vec<(usize, u64)> {
+        let mut prefixes = Vec::new();
+        let mut node = 0;
+        for (i, &c) in chars.iter().enumerate() {
+            node = match self.nodes[node].child(c) {
+                Some(child) => child,
+                None => break,
+            };
+            if let Some(freq) = self.nodes[node].freq {
+                prefixes.push((i + 1, freq));
+            }
+        }
+        prefixes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WordDictionary;
+
+    #[test]
+    fn test_simple() {
+        let mut dictionary = WordDictionary::new();
+        assert!(dictionary.is_empty());
+        dictionary.add_word("北京", 10);
+        dictionary.add_word("北京大学", 5);
+        dictionary.add_word("北", 3);
+        dictionary.add_word("北京", 20);
+        assert_eq!(dictionary.len(), 3);
+        assert_eq!(dictionary.total_freq(), 28);
+        assert_eq!(dictionary.freq("北京"), Some(20));
+        assert!(dictionary.contains("北京大学"));
+        assert!(!dictionary.contains("北京大"));
+        assert!(!dictionary.contains("南"));
+
+        let chars: Vec<_> = "北京大学生".chars().collect();
+        assert_eq!(dictionary.prefixes(&chars), vec![(1, 3), (2, 20), (4, 5)]);
+        assert!(dictionary.prefixes(&chars[1..]).is_empty());
+    }
+
+    #[test]
+    fn test_load() {
+        let text = "清华 40 nt\n大学 70\n\n华大\n";
+        let dictionary = WordDictionary::load(text.as_bytes()).unwrap();
+        assert_eq!(dictionary.len(), 3);
+        assert_eq!(dictionary.freq("清华"), Some(40));
+        assert_eq!(dictionary.freq("华大"), Some(1));
+        assert_eq!(dictionary.total_freq(), 111);
+
+        assert!(WordDictionary::load("清华 many".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+// A deterministic automaton over the chars of terms, used to find the terms
+// of a dictionary matching a pattern without testing them one by one.
+pub trait Automaton {
+    type State: Clone;
+
+    fn start(&self) -> Self::State;
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State;
+
+    // Whether the chars accepted so far match.
+    fn is_match(&self, state: &Self::State) -> bool;
+
+    // Whether any continuation of the chars accepted so far may match, so
+    // that all the terms sharing them as prefix can be skipped otherwise.
+    fn can_match(&self, state: &Self::State) -> bool;
+
+    fn matches(&self, text: &str) -> bool {
+        let mut state = self.start();
+        for c in text.chars() {
+            state = self.accept(&state, c);
+            if !self.can_match(&state) {
+                return false;
+            }
+        }
+        self.is_match(&state)
+    }
+}
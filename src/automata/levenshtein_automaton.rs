@@ -0,0 +1,136 @@
+use super::Automaton;
+
+// Matches the texts within `max_distance` edits of a term, where an edit
+// inserts, deletes or substitutes a char, or swaps two adjacent chars when
+// transpositions are enabled.
+pub struct LevenshteinAutomaton {
+    term: Vec<char>,
+    max_distance: u32,
+    transpositions: bool,
+}
+
+// The last row of the edit distance table between the term and the chars
+// accepted so far, with the row before it and the last char for
+// transpositions. Distances are capped just above the max distance.
+#[derive(Clone, Debug)]
+pub struct LevenshteinState {
+    row: Vec<u32>,
+    previous_row: Vec<u32>,
+    last_char: Option<char>,
+}
+
+impl LevenshteinAutomaton {
+    pub fn new(term: &str, max_distance: u32, transpositions: bool) -> Self {
+        Self {
+            term: term.chars().collect(),
+            max_distance,
+            transpositions,
+        }
+    }
+
+    // The distance between the term and the chars accepted so far, if it is
+    // at most the max distance.
+    pub fn distance(&self, state: &LevenshteinState) -> Option<u32> {
+        let distance = state.row[self.term.len()];
+        (distance <= self.max_distance).then_some(distance)
+    }
+}
+
+impl Automaton for LevenshteinAutomaton {
+    type State = LevenshteinState;
+
+    fn start(&self) -> Self::State {
+        let row: Vec<u32> = (0..=self.term.len() as u32)
+            .map(|distance| distance.min(self.max_distance + 1))
+            .collect();
+        LevenshteinState {
+            previous_row: row.clone(),
+            row,
+            last_char: None,
+        }
+    }
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State {
+        let cap = self.max_distance + 1;
+        let mut row = Vec::with_capacity(state.row.len());
+        row.push((state.row[0] + 1).min(cap));
+        for j in 1..=self.term.len() {
+            let substitution = state.row[j - 1] + (self.term[j - 1] != c) as u32;
+            let mut distance = substitution.min(state.row[j] + 1).min(row[j - 1] + 1);
+            if self.transpositions
+                && j > 1
+                && state.last_char == Some(self.term[j - 1])
+                && c == self.term[j - 2]
+            {
+                distance = distance.min(state.previous_row[j - 2] + 1);
+            }
+            row.push(distance.min(cap));
+        }
+        LevenshteinState {
+            previous_row: state.row.clone(),
+            row,
+            last_char: Some(c),
+        }
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        self.distance(state).is_some()
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        state
+            .row
+            .iter()
+            .any(|&distance| distance <= self.max_distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Automaton, LevenshteinAutomaton};
+
+    fn distance(automaton: &LevenshteinAutomaton, text: &str) -> Option<u32> {
+        let mut state = automaton.start();
+        for c in text.chars() {
+            state = automaton.accept(&state, c);
+        }
+        automaton.distance(&state)
+    }
+
+    #[test]
+    fn test_simple() {
+        let automaton = LevenshteinAutomaton::new("kitten", 2, false);
+        assert_eq!(distance(&automaton, "kitten"), Some(0));
+        assert_eq!(distance(&automaton, "sitten"), Some(1));
+        assert_eq!(distance(&automaton, "sittin"), Some(2));
+        assert_eq!(distance(&automaton, "sitting"), None);
+        assert_eq!(distance(&automaton, "kittens"), Some(1));
+        assert_eq!(distance(&automaton, "kiten"), Some(1));
+        assert_eq!(distance(&automaton, "iktten"), Some(2));
+
+        let automaton = LevenshteinAutomaton::new("kitten", 1, true);
+        assert_eq!(distance(&automaton, "iktten"), Some(1));
+        assert_eq!(distance(&automaton, "kittne"), Some(1));
+        assert_eq!(distance(&automaton, "ikttne"), None);
+        assert!(automaton.matches("kitte"));
+        assert!(!automaton.matches("mitte"));
+
+        let automaton = LevenshteinAutomaton::new("café", 1, true);
+        assert!(automaton.matches("cafe"));
+        assert!(automaton.matches("caf"));
+        assert!(!automaton.matches("cfa"));
+    }
+
+    #[test]
+    fn test_can_match() {
+        let automaton = LevenshteinAutomaton::new("abc", 1, true);
+        let mut state = automaton.start();
+        for c in "xy".chars() {
+            state = automaton.accept(&state, c);
+        }
+        assert!(!automaton.can_match(&state));
+
+        let state = automaton.accept(&automaton.start(), 'x');
+        assert!(automaton.can_match(&state));
+    }
+}
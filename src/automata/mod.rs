@@ -0,0 +1,9 @@
+mod automaton;
+mod levenshtein_automaton;
+mod prefix_automaton;
+mod regex_automaton;
+
+pub use automaton::Automaton;
+pub use levenshtein_automaton::{LevenshteinAutomaton, LevenshteinState};
+pub use prefix_automaton::PrefixAutomaton;
+pub use regex_automaton::{BuildError, RegexAutomaton};
@@ -0,0 +1,57 @@
+use super::Automaton;
+
+// Matches the texts starting with a prefix.
+pub struct PrefixAutomaton {
+    prefix: Vec<char>,
+}
+
+impl PrefixAutomaton {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.chars().collect(),
+        }
+    }
+}
+
+impl Automaton for PrefixAutomaton {
+    // The number of chars of the prefix matched, or `None` on a mismatch.
+    type State = Option<usize>;
+
+    fn start(&self) -> Self::State {
+        Some(0)
+    }
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State {
+        let matched = (*state)?;
+        if matched == self.prefix.len() {
+            Some(matched)
+        } else if self.prefix[matched] == c {
+            Some(matched + 1)
+        } else {
+            None
+        }
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        *state == Some(self.prefix.len())
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Automaton, PrefixAutomaton};
+
+    #[test]
+    fn test_simple() {
+        let automaton = PrefixAutomaton::new("héll");
+        assert!(automaton.matches("héll"));
+        assert!(automaton.matches("héllo"));
+        assert!(!automaton.matches("hél"));
+        assert!(!automaton.matches("hello"));
+        assert!(PrefixAutomaton::new("").matches("anything"));
+    }
+}
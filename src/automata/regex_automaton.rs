@@ -0,0 +1,107 @@
+use regex_automata::{
+    dfa::{dense, Automaton as _, StartKind},
+    util::{primitives::StateID, start},
+    Anchored,
+};
+
+use super::Automaton;
+
+pub use regex_automata::dfa::dense::BuildError;
+
+// Matches the texts matching a regular expression as a whole, with the syntax
+// of the `regex` crate.
+pub struct RegexAutomaton {
+    dfa: dense::DFA<Vec<u32>>,
+    start: StateID,
+}
+
+impl RegexAutomaton {
+    pub fn new(pattern: &str) -> Result<Self, Box<BuildError>> {
+        let dfa = dense::Builder::new()
+            .configure(dense::Config::new().start_kind(StartKind::Anchored))
+            .build(&format!("(?:{})$", pattern))
+            .map_err(Box::new)?;
+        let start = dfa
+            .start_state(&start::Config::new().anchored(Anchored::Yes))
+            .expect("Anchored start state");
+        Ok(Self { dfa, start })
+    }
+
+    // Compiles a wildcard pattern, where `*` matches any chars, `?` matches a
+    // single char, and `\` escapes the next char.
+    pub fn wildcard(pattern: &str) -> Self {
+        let mut regex = String::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' => regex.push_str("(?s:.*)"),
+                '?' => regex.push_str("(?s:.)"),
+                '\\' => {
+                    if let Some(c) = chars.next() {
+                        regex.push_str(&regex_syntax::escape(c.encode_utf8(&mut [0; 4])));
+                    }
+                }
+                c => regex.push_str(&regex_syntax::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        Self::new(&regex).expect("Escaped wildcard pattern")
+    }
+}
+
+impl Automaton for RegexAutomaton {
+    type State = StateID;
+
+    fn start(&self) -> Self::State {
+        self.start
+    }
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State {
+        let mut state = *state;
+        for &byte in c.encode_utf8(&mut [0; 4]).as_bytes() {
+            state = self.dfa.next_state(state, byte);
+        }
+        state
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        self.dfa.is_match_state(self.dfa.next_eoi_state(*state))
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        !self.dfa.is_dead_state(*state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Automaton, RegexAutomaton};
+
+    #[test]
+    fn test_simple() {
+        let automaton = RegexAutomaton::new("ab+c?").unwrap();
+        assert!(automaton.matches("ab"));
+        assert!(automaton.matches("abbbc"));
+        assert!(!automaton.matches("abcc"));
+        assert!(!automaton.matches("xab"));
+        assert!(!automaton.can_match(&automaton.accept(&automaton.start(), 'x')));
+
+        let automaton = RegexAutomaton::new("[é-ü]+").unwrap();
+        assert!(automaton.matches("éü"));
+        assert!(!automaton.matches("e"));
+        assert!(RegexAutomaton::new("a(").is_err());
+    }
+
+    #[test]
+    fn test_wildcard() {
+        let automaton = RegexAutomaton::wildcard("t?st*");
+        assert!(automaton.matches("test"));
+        assert!(automaton.matches("tést\ning"));
+        assert!(!automaton.matches("tst"));
+        assert!(!automaton.matches("atest"));
+
+        let automaton = RegexAutomaton::wildcard(r"a.b\*");
+        assert!(automaton.matches("a.b*"));
+        assert!(!automaton.matches("axb*"));
+        assert!(!automaton.matches("a.bc"));
+    }
+}
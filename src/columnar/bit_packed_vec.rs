@@ -0,0 +1,93 @@
+// Values of a fixed number of bits packed into words, each value starting
+// right after the previous one, possibly across two words.
+pub struct BitPackedVec {
+    bits: u32,
+    len: usize,
+    words: Vec<u64>,
+}
+
+// The number of bits of the largest value.
+pub fn bits_needed(max: u64) -> u32 {
+    u64::BITS - max.leading_zeros()
+}
+
+impl BitPackedVec {
+    pub fn new<I: IntoIterator<Item = u64>>(bits: u32, values: I) -> Self {
+        assert!(bits <= u64::BITS);
+        let mut words = Vec::new();
+        let mut len = 0;
+        for value in values {
+            debug_assert!(bits_needed(value) <= bits);
+            let bit = len * bits as usize;
+            let (word, shift) = (bit / 64, bit % 64);
+            if bits > 0 {
+                words.resize((bit + bits as usize).div_ceil(64), 0);
+                words[word] |= value << shift;
+                if shift + bits as usize > 64 {
+                    words[word + 1] |= value >> (64 - shift);
+                }
+            }
+            len += 1;
+        }
+        Self { bits, len, words }
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> u64 {
+        assert!(index < self.len, "Index out of bounds");
+        if self.bits == 0 {
+            return 0;
+        }
+        let bit = index * self.bits as usize;
+        let (word, shift) = (bit / 64, bit % 64);
+        let mut value = self.words[word] >> shift;
+        if shift + self.bits as usize > 64 {
+            value |= self.words[word + 1] << (64 - shift);
+        }
+        if self.bits == u64::BITS {
+            value
+        } else {
+            value & ((1 << self.bits) - 1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bits_needed, BitPackedVec};
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(bits_needed(0), 0);
+        assert_eq!(bits_needed(1), 1);
+        assert_eq!(bits_needed(255), 8);
+        assert_eq!(bits_needed(u64::MAX), 64);
+
+        for bits in [0, 1, 3, 7, 13, 32, 63, 64] {
+            let mask = if bits == 64 {
+                u64::MAX
+            } else {
+                (1 << bits) - 1
+            };
+            let values: Vec<u64> = (0..200u64)
+                .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15) & mask)
+                .collect();
+            let packed = BitPackedVec::new(bits, values.iter().copied());
+            assert_eq!(packed.len(), values.len());
+            for (index, &value) in values.iter().enumerate() {
+                assert_eq!(packed.get(index), value);
+            }
+        }
+    }
+}
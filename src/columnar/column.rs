@@ -0,0 +1,127 @@
+use crate::DocId;
+
+use super::NumericColumn;
+
+enum ColumnIndex {
+    // One value per doc.
+    Full,
+    // At most one value per doc, with a bit set for each doc that has one.
+    // Docs without a value hold a placeholder, so that values are per doc.
+    Optional(Vec<u64>),
+    // Any number of values per doc, the values of a doc ending at its entry.
+    Multi(NumericColumn),
+}
+
+// The u64 values of the docs of an immutable segment, indexed by whichever
+// layout fits how many values the docs have.
+pub struct Column {
+    index: ColumnIndex,
+    values: NumericColumn,
+}
+
+impl Column {
+    // `ends` has the end of the values of each doc in `values`.
+    pub fn build(ends: &[u64], values: &[u64]) -> Self {
+        let mut start = 0;
+        let mut max_len = 0;
+        let mut is_full = true;
+        for &end in ends {
+            max_len = max_len.max(end - start);
+            is_full &= end - start == 1;
+            start = end;
+        }
+        if is_full {
+            return Self {
+                index: ColumnIndex::Full,
+                values: NumericColumn::build(values),
+            };
+        }
+        if max_len > 1 {
+            return Self {
+                index: ColumnIndex::Multi(NumericColumn::build(ends)),
+                values: NumericColumn::build(values),
+            };
+        }
+
+        let placeholder = values.iter().copied().min().unwrap_or(0);
+        let mut words = vec![0u64; ends.len().div_ceil(64)];
+        let mut doc_values = Vec::with_capacity(ends.len());
+        let mut start = 0;
+        for (doc, &end) in ends.iter().enumerate() {
+            if end > start {
+                words[doc / 64] |= 1 << (doc % 64);
+                doc_values.push(values[start as usize]);
+            } else {
+                doc_values.push(placeholder);
+            }
+            start = end;
+        }
+        Self {
+            index: ColumnIndex::Optional(words),
+            values: NumericColumn::build(&doc_values),
+        }
+    }
+
+    // Replaces the content of `values` with the values of the doc.
+    pub fn values(&self, doc: DocId, values: &mut Vec<u64>) {
+        values.clear();
+        let doc = doc as usize;
+        match &self.index {
+            ColumnIndex::Full => values.push(self.values.get(doc)),
+            ColumnIndex::Optional(words) => {
+                if words[doc / 64] & (1 << (doc % 64)) != 0 {
+                    values.push(self.values.get(doc));
+                }
+            }
+            ColumnIndex::Multi(ends) => {
+                let start = if doc == 0 { 0 } else { ends.get(doc - 1) };
+                values.extend((start..ends.get(doc)).map(|index| self.values.get(index as usize)));
+            }
+        }
+    }
+
+    pub fn first(&self, doc: DocId) -> Option<u64> {
+        let doc = doc as usize;
+        match &self.index {
+            ColumnIndex::Full => Some(self.values.get(doc)),
+            ColumnIndex::Optional(words) => {
+                (words[doc / 64] & (1 << (doc % 64)) != 0).then(|| self.values.get(doc))
+            }
+            ColumnIndex::Multi(ends) => {
+                let start = if doc == 0 { 0 } else { ends.get(doc - 1) };
+                (start < ends.get(doc)).then(|| self.values.get(start as usize))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Column;
+
+    fn doc_values(column: &Column, max_doc: u32) -> Vec<Vec<u64>> {
+        let mut values = Vec::new();
+        (0..max_doc)
+            .map(|doc| {
+                column.values(doc, &mut values);
+                assert_eq!(column.first(doc), values.first().copied());
+                values.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        let column = Column::build(&[1, 2, 3], &[10, 5, 10]);
+        assert_eq!(doc_values(&column, 3), [vec![10], vec![5], vec![10]]);
+
+        let column = Column::build(&[1, 1, 2, 2], &[7, 9]);
+        assert_eq!(doc_values(&column, 4), [vec![7], vec![], vec![9], vec![]]);
+
+        let column = Column::build(&[2, 2, 5], &[1, 3, 2, 2, 8]);
+        assert_eq!(doc_values(&column, 3), [vec![1, 3], vec![], vec![2, 2, 8]]);
+
+        let column = Column::build(&[0, 0], &[]);
+        assert_eq!(doc_values(&column, 2), [vec![], vec![]]);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{util::ChunkedVector, DocId};
+
+const CHUNK_EXPONENT: usize = 10;
+const TREE_EXPONENT: usize = 4;
+
+// The values of a field for each doc of a realtime segment, appended in doc
+// order.
+//
+// The values of a doc are pushed before the end of its values, so that
+// readers seeing the doc also see its values.
+pub struct ColumnWriter<T> {
+    ends: ChunkedVector<usize>,
+    values: ChunkedVector<T>,
+}
+
+impl<T> Default for ColumnWriter<T> {
+    fn default() -> Self {
+        Self {
+            ends: ChunkedVector::new(CHUNK_EXPONENT, TREE_EXPONENT),
+            values: ChunkedVector::new(CHUNK_EXPONENT, TREE_EXPONENT),
+        }
+    }
+}
+
+impl<T> ColumnWriter<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Must only be called from the single writer thread, once for each doc in
+    // order, including the docs without values.
+    pub fn add_doc<I: IntoIterator<Item = T>>(&self, values: I) {
+        for value in values {
+            self.values.push(value);
+        }
+        self.ends.push(self.values.len());
+    }
+
+    // The number of docs added.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    // The values of the doc, none if it was not added yet.
+    pub fn values(&self, doc: DocId) -> impl Iterator<Item = &T> {
+        let doc = doc as usize;
+        let (start, end) = match self.ends.get(doc) {
+            Some(&end) if doc == 0 => (0, end),
+            Some(&end) => (*self.ends.get(doc - 1).unwrap(), end),
+            None => (0, 0),
+        };
+        (start..end).map(|index| self.values.get(index).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColumnWriter;
+
+    #[test]
+    fn test_simple() {
+        let writer = ColumnWriter::new();
+        assert!(writer.is_empty());
+        writer.add_doc([3u64, 1]);
+        writer.add_doc([]);
+        writer.add_doc([7]);
+        assert_eq!(writer.len(), 3);
+        let values = |doc| writer.values(doc).copied().collect::<Vec<_>>();
+        assert_eq!(values(0), [3, 1]);
+        assert!(values(1).is_empty());
+        assert_eq!(values(2), [7]);
+        assert!(values(3).is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+use std::borrow::Cow;
+
+use crate::DocId;
+
+// The numbers of a field for each doc, as sortable u64s.
+pub trait NumericDocValues {
+    // Replaces the content of `values` with the values of the doc, in
+    // increasing order.
+    fn values(&self, doc: DocId, values: &mut Vec<u64>);
+
+    // The smallest value of the doc.
+    fn first(&self, doc: DocId) -> Option<u64> {
+        let mut values = Vec::new();
+        self.values(doc, &mut values);
+        values.first().copied()
+    }
+}
+
+// A byte string for each doc.
+pub trait BinaryDocValues {
+    fn value(&self, doc: DocId) -> Option<Cow<'_, [u8]>>;
+}
+
+// The strings of a field for each doc, as ordinals in the sorted dictionary
+// of the strings of the field.
+pub trait SortedDocValues {
+    // Replaces the content of `ords` with the distinct ordinals of the doc,
+    // in increasing order.
+    fn ords(&self, doc: DocId, ords: &mut Vec<u64>);
+
+    // The smallest ordinal of the doc.
+    fn first_ord(&self, doc: DocId) -> Option<u64> {
+        let mut ords = Vec::new();
+        self.ords(doc, &mut ords);
+        ords.first().copied()
+    }
+
+    fn term_count(&self) -> u64;
+
+    fn term(&self, ord: u64) -> &str;
+
+    // The ordinal of the term, or the ordinal it would have if absent.
+    fn term_ord(&self, term: &str) -> Result<u64, u64> {
+        let (mut low, mut high) = (0, self.term_count());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.term(mid).cmp(term) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+}
+
+impl<T: NumericDocValues + ?Sized> NumericDocValues for &T {
+    fn values(&self, doc: DocId, values: &mut Vec<u64>) {
+        (**self).values(doc, values);
+    }
+
+    fn first(&self, doc: DocId) -> Option<u64> {
+        (**self).first(doc)
+    }
+}
+
+impl<T: BinaryDocValues + ?Sized> BinaryDocValues for &T {
+    fn value(&self, doc: DocId) -> Option<Cow<'_, [u8]>> {
+        (**self).value(doc)
+    }
+}
+
+impl<T: SortedDocValues + ?Sized> SortedDocValues for &T {
+    fn ords(&self, doc: DocId, ords: &mut Vec<u64>) {
+        (**self).ords(doc, ords);
+    }
+
+    fn first_ord(&self, doc: DocId) -> Option<u64> {
+        (**self).first_ord(doc)
+    }
+
+    fn term_count(&self) -> u64 {
+        (**self).term_count()
+    }
+
+    fn term(&self, ord: u64) -> &str {
+        (**self).term(ord)
+    }
+}
@@ -0,0 +1,307 @@
+use std::{borrow::Cow, collections::hash_map::RandomState};
+
+use crate::{
+    document::Value,
+    schema::{Cardinality, FieldType},
+    util::{ChunkedVector, ExponentialCapacityPolicy, LayeredHashMap},
+    DocId,
+};
+
+use super::{BinaryDocValues, ColumnWriter, NumericDocValues, NumericUpdates, SortedDocValues};
+
+const TERM_IDS_INITIAL_CAPACITY: usize = 64;
+const TERMS_CHUNK_EXPONENT: usize = 8;
+const TERMS_TREE_EXPONENT: usize = 4;
+
+// The strings of a field for each doc, as ids in order of first occurrence.
+// Terms are pushed before their id is used, so that readers seeing an id also
+// see its term.
+pub(super) struct SortedColumnWriter {
+    term_ids: LayeredHashMap<String, u64, RandomState, ExponentialCapacityPolicy>,
+    pub terms: ChunkedVector<String>,
+    pub column: ColumnWriter<u64>,
+}
+
+impl SortedColumnWriter {
+    fn new() -> Self {
+        Self {
+            term_ids: LayeredHashMap::with_initial_capacity(
+                TERM_IDS_INITIAL_CAPACITY,
+                RandomState::new(),
+                ExponentialCapacityPolicy,
+            ),
+            terms: ChunkedVector::new(TERMS_CHUNK_EXPONENT, TERMS_TREE_EXPONENT),
+            column: ColumnWriter::new(),
+        }
+    }
+
+    fn term_id(&self, term: &str) -> u64 {
+        if let Some(&term_id) = self.term_ids.get(term) {
+            return term_id;
+        }
+        let term_id = self.terms.len() as u64;
+        self.terms.push(term.to_string());
+        self.term_ids.insert(term.to_string(), term_id);
+        term_id
+    }
+}
+
+pub(super) enum ColumnWriters {
+    Numeric(ColumnWriter<u64>),
+    Binary(ColumnWriter<Box<[u8]>>),
+    Sorted(SortedColumnWriter),
+}
+
+// The doc values of a field in a realtime segment: numbers of numeric fields,
+// byte strings of bytes fields, and strings of text fields. The numbers of
+// updatable fields come with the updates made after the docs were added.
+pub struct DocValuesWriter {
+    field_type: FieldType,
+    cardinality: Cardinality,
+    pub(super) columns: ColumnWriters,
+    updates: Option<NumericUpdates>,
+}
+
+impl DocValuesWriter {
+    // `None` if the field has no doc values.
+    pub fn new(field_type: &FieldType) -> Option<Self> {
+        let cardinality = field_type.doc_values()?;
+        let columns = match field_type {
+            FieldType::Text(_) => ColumnWriters::Sorted(SortedColumnWriter::new()),
+            FieldType::Bytes(_) => ColumnWriters::Binary(ColumnWriter::new()),
+            _ => ColumnWriters::Numeric(ColumnWriter::new()),
+        };
+        Some(Self {
+            field_type: field_type.clone(),
+            cardinality,
+            columns,
+            updates: field_type.is_updatable().then(NumericUpdates::new),
+        })
+    }
+
+    // Must only be called from the single writer thread, once for each doc in
+    // order. Values of another type than the field are ignored, as well as
+    // the values after the first one without multiple values.
+    pub fn add_doc<'a, I: IntoIterator<Item = &'a Value>>(&self, values: I) {
+        let limit = match self.cardinality {
+            Cardinality::Single => 1,
+            Cardinality::Multi => usize::MAX,
+        };
+        match &self.columns {
+            ColumnWriters::Numeric(column) => {
+                let mut numbers: Vec<u64> = values
+                    .into_iter()
+                    .filter_map(|value| value.to_field_sortable_u64(&self.field_type))
+                    .take(limit)
+                    .collect();
+                numbers.sort_unstable();
+                column.add_doc(numbers);
+            }
+            ColumnWriters::Binary(column) => column.add_doc(
+                values
+                    .into_iter()
+                    .filter_map(|value| value.as_bytes())
+                    .map(Box::from)
+                    .take(limit),
+            ),
+            ColumnWriters::Sorted(sorted) => {
+                let term_ids: Vec<u64> = values
+                    .into_iter()
+                    .filter_map(|value| value.as_str())
+                    .take(limit)
+                    .map(|term| sorted.term_id(term))
+                    .collect();
+                sorted.column.add_doc(term_ids);
+            }
+        }
+    }
+
+    // `None` if the field is not updatable.
+    pub fn updates(&self) -> Option<&NumericUpdates> {
+        self.updates.as_ref()
+    }
+
+    pub fn numeric_doc_values(&self, max_doc: DocId) -> Option<RealtimeNumericDocValues<'_>> {
+        match &self.columns {
+            ColumnWriters::Numeric(column) => Some(RealtimeNumericDocValues { column, max_doc }),
+            _ => None,
+        }
+    }
+
+    pub fn binary_doc_values(&self, max_doc: DocId) -> Option<RealtimeBinaryDocValues<'_>> {
+        match &self.columns {
+            ColumnWriters::Binary(column) => Some(RealtimeBinaryDocValues { column, max_doc }),
+            _ => None,
+        }
+    }
+
+    pub fn sorted_doc_values(&self, max_doc: DocId) -> Option<RealtimeSortedDocValues<'_>> {
+        match &self.columns {
+            ColumnWriters::Sorted(sorted) => Some(RealtimeSortedDocValues::new(sorted, max_doc)),
+            _ => None,
+        }
+    }
+}
+
+pub struct RealtimeNumericDocValues<'a> {
+    column: &'a ColumnWriter<u64>,
+    max_doc: DocId,
+}
+
+impl<'a> NumericDocValues for RealtimeNumericDocValues<'a> {
+    fn values(&self, doc: DocId, values: &mut Vec<u64>) {
+        values.clear();
+        if doc < self.max_doc {
+            values.extend(self.column.values(doc).copied());
+        }
+    }
+}
+
+pub struct RealtimeBinaryDocValues<'a> {
+    column: &'a ColumnWriter<Box<[u8]>>,
+    max_doc: DocId,
+}
+
+impl<'a> BinaryDocValues for RealtimeBinaryDocValues<'a> {
+    fn value(&self, doc: DocId) -> Option<Cow<'_, [u8]>> {
+        if doc >= self.max_doc {
+            return None;
+        }
+        self.column
+            .values(doc)
+            .next()
+            .map(|bytes| Cow::Borrowed(&bytes[..]))
+    }
+}
+
+// Sorts the terms added so far when created, which may include terms of docs
+// above the watermark.
+pub struct RealtimeSortedDocValues<'a> {
+    column: &'a ColumnWriter<u64>,
+    max_doc: DocId,
+    // The ordinal of each term id.
+    ords: Vec<u64>,
+    terms: Vec<&'a str>,
+}
+
+impl<'a> RealtimeSortedDocValues<'a> {
+    fn new(sorted: &'a SortedColumnWriter, max_doc: DocId) -> Self {
+        let mut terms: Vec<(&str, usize)> = (0..sorted.terms.len())
+            .map(|term_id| (sorted.terms.get(term_id).unwrap().as_str(), term_id))
+            .collect();
+        terms.sort_unstable();
+        let mut ords = vec![0; terms.len()];
+        for (ord, &(_, term_id)) in terms.iter().enumerate() {
+            ords[term_id] = ord as u64;
+        }
+        Self {
+            column: &sorted.column,
+            max_doc,
+            ords,
+            terms: terms.into_iter().map(|(term, _)| term).collect(),
+        }
+    }
+}
+
+impl<'a> SortedDocValues for RealtimeSortedDocValues<'a> {
+    fn ords(&self, doc: DocId, ords: &mut Vec<u64>) {
+        ords.clear();
+        if doc < self.max_doc {
+            ords.extend(
+                self.column
+                    .values(doc)
+                    .map(|&term_id| self.ords[term_id as usize]),
+            );
+            ords.sort_unstable();
+            ords.dedup();
+        }
+    }
+
+    fn term_count(&self) -> u64 {
+        self.terms.len() as u64
+    }
+
+    fn term(&self, ord: u64) -> &str {
+        self.terms[ord as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        columnar::{BinaryDocValues, NumericDocValues, SortedDocValues},
+        document::Value,
+        schema::{BytesOptions, Cardinality, FieldType, NumericOptions, TextOptions},
+    };
+
+    use super::DocValuesWriter;
+
+    #[test]
+    fn test_numeric() {
+        let options = NumericOptions::default();
+        assert!(DocValuesWriter::new(&FieldType::I64(options.clone())).is_none());
+
+        let field_type = FieldType::I64(options.clone().set_doc_values(Cardinality::Single));
+        let writer = DocValuesWriter::new(&field_type).unwrap();
+        writer.add_doc(&[Value::I64(-3), Value::I64(-5)]);
+        writer.add_doc(&[]);
+        // Values of another type are ignored.
+        writer.add_doc(&[Value::U64(1), Value::I64(8)]);
+        let doc_values = writer.numeric_doc_values(3).unwrap();
+        let first = |doc| {
+            doc_values
+                .first(doc)
+                .map(|value| Value::from_sortable_u64(&field_type, value).unwrap())
+        };
+        assert_eq!(first(0), Some(Value::I64(-3)));
+        assert_eq!(first(1), None);
+        assert_eq!(first(2), Some(Value::I64(8)));
+        assert!(writer.binary_doc_values(3).is_none());
+
+        let field_type = FieldType::U64(options.set_doc_values(Cardinality::Multi));
+        let writer = DocValuesWriter::new(&field_type).unwrap();
+        writer.add_doc(&[Value::U64(9), Value::U64(2), Value::U64(9)]);
+        writer.add_doc(&[Value::U64(4)]);
+        // Docs above the watermark are not visible.
+        let doc_values = writer.numeric_doc_values(1).unwrap();
+        let mut values = Vec::new();
+        doc_values.values(0, &mut values);
+        assert_eq!(values, [2, 9, 9]);
+        doc_values.values(1, &mut values);
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn test_binary() {
+        let field_type = FieldType::Bytes(BytesOptions::default().set_doc_values());
+        let writer = DocValuesWriter::new(&field_type).unwrap();
+        writer.add_doc(&[Value::Bytes(vec![1, 2]), Value::Bytes(vec![3])]);
+        writer.add_doc(&[Value::Str("a".to_string())]);
+        writer.add_doc(&[Value::Bytes(vec![])]);
+        let doc_values = writer.binary_doc_values(3).unwrap();
+        assert_eq!(doc_values.value(0).as_deref(), Some(&[1, 2][..]));
+        assert_eq!(doc_values.value(1), None);
+        assert_eq!(doc_values.value(2).as_deref(), Some(&[][..]));
+    }
+
+    #[test]
+    fn test_sorted() {
+        let field_type = FieldType::Text(TextOptions::default().set_doc_values(Cardinality::Multi));
+        let writer = DocValuesWriter::new(&field_type).unwrap();
+        let text = |text: &str| Value::Str(text.to_string());
+        writer.add_doc(&[text("red"), text("blue"), text("red")]);
+        writer.add_doc(&[]);
+        writer.add_doc(&[text("green")]);
+        let doc_values = writer.sorted_doc_values(3).unwrap();
+        assert_eq!(doc_values.term_count(), 3);
+        let terms: Vec<_> = (0..3).map(|ord| doc_values.term(ord)).collect();
+        assert_eq!(terms, ["blue", "green", "red"]);
+        let mut ords = Vec::new();
+        doc_values.ords(0, &mut ords);
+        assert_eq!(ords, [0, 2]);
+        assert_eq!(doc_values.first_ord(1), None);
+        assert_eq!(doc_values.first_ord(2), Some(1));
+        assert_eq!(doc_values.term_ord("green"), Ok(1));
+        assert_eq!(doc_values.term_ord("orange"), Err(2));
+    }
+}
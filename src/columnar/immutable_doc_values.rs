@@ -0,0 +1,277 @@
+use std::borrow::Cow;
+
+use crate::DocId;
+
+use super::{
+    doc_values_writer::ColumnWriters, BinaryDocValues, Column, DocValuesWriter, NumericColumn,
+    NumericDocValues, SortedDocValues,
+};
+
+impl NumericDocValues for Column {
+    fn values(&self, doc: DocId, values: &mut Vec<u64>) {
+        Column::values(self, doc, values);
+    }
+
+    fn first(&self, doc: DocId) -> Option<u64> {
+        Column::first(self, doc)
+    }
+}
+
+// The byte strings of the docs, concatenated.
+pub struct BinaryColumn {
+    // The start of the bytes of each doc, then the end of the last one.
+    offsets: NumericColumn,
+    // A bit set for each doc with a value, absent when all of them have one.
+    present: Option<Vec<u64>>,
+    bytes: Vec<u8>,
+}
+
+impl BinaryDocValues for BinaryColumn {
+    fn value(&self, doc: DocId) -> Option<Cow<'_, [u8]>> {
+        let doc = doc as usize;
+        if let Some(words) = &self.present {
+            if words[doc / 64] & (1 << (doc % 64)) == 0 {
+                return None;
+            }
+        }
+        let range = self.offsets.get(doc) as usize..self.offsets.get(doc + 1) as usize;
+        Some(Cow::Borrowed(&self.bytes[range]))
+    }
+}
+
+pub struct SortedColumn {
+    terms: Vec<String>,
+    ords: Column,
+}
+
+impl SortedDocValues for SortedColumn {
+    fn ords(&self, doc: DocId, ords: &mut Vec<u64>) {
+        self.ords.values(doc, ords);
+    }
+
+    fn first_ord(&self, doc: DocId) -> Option<u64> {
+        self.ords.first(doc)
+    }
+
+    fn term_count(&self) -> u64 {
+        self.terms.len() as u64
+    }
+
+    fn term(&self, ord: u64) -> &str {
+        &self.terms[ord as usize]
+    }
+}
+
+// The doc values of a field in an immutable segment.
+pub enum ImmutableDocValues {
+    Numeric(Column),
+    Binary(BinaryColumn),
+    Sorted(SortedColumn),
+}
+
+impl ImmutableDocValues {
+    // Numbers of updatable fields are taken with the updates up to
+    // `update_seq` applied.
+    pub fn build(writer: &DocValuesWriter, max_doc: DocId, update_seq: u64) -> Self {
+        let mut ends = Vec::with_capacity(max_doc as usize);
+        match &writer.columns {
+            ColumnWriters::Numeric(column) => {
+                let mut values = Vec::new();
+                for doc in 0..max_doc {
+                    match writer
+                        .updates()
+                        .and_then(|updates| updates.get(doc, update_seq))
+                    {
+                        Some(value) => values.push(value),
+                        None => values.extend(column.values(doc).copied()),
+                    }
+                    ends.push(values.len() as u64);
+                }
+                ImmutableDocValues::Numeric(Column::build(&ends, &values))
+            }
+            ColumnWriters::Binary(column) => {
+                let mut offsets = Vec::with_capacity(max_doc as usize + 1);
+                let mut words = vec![0u64; (max_doc as usize).div_ceil(64)];
+                let mut is_full = true;
+                let mut bytes = Vec::new();
+                for doc in 0..max_doc {
+                    offsets.push(bytes.len() as u64);
+                    match column.values(doc).next() {
+                        Some(value) => {
+                            words[doc as usize / 64] |= 1 << (doc % 64);
+                            bytes.extend_from_slice(value);
+                        }
+                        None => is_full = false,
+                    }
+                }
+                offsets.push(bytes.len() as u64);
+                ImmutableDocValues::Binary(BinaryColumn {
+                    offsets: NumericColumn::build(&offsets),
+                    present: (!is_full).then_some(words),
+                    bytes,
+                })
+            }
+            ColumnWriters::Sorted(sorted) => {
+                // Only the terms of the docs below `max_doc` are kept.
+                let mut term_ids = Vec::new();
+                for doc in 0..max_doc {
+                    term_ids.extend(sorted.column.values(doc).copied());
+                    ends.push(term_ids.len() as u64);
+                }
+                let mut terms: Vec<(&str, u64)> = term_ids
+                    .iter()
+                    .map(|&term_id| {
+                        let term = sorted.terms.get(term_id as usize).unwrap();
+                        (term.as_str(), term_id)
+                    })
+                    .collect();
+                terms.sort_unstable();
+                terms.dedup();
+                let mut ords = vec![0; sorted.terms.len()];
+                for (ord, &(_, term_id)) in terms.iter().enumerate() {
+                    ords[term_id as usize] = ord as u64;
+                }
+
+                // The ordinals of each doc are sorted and deduplicated.
+                let mut all_ords = Vec::with_capacity(term_ids.len());
+                let mut ord_ends = Vec::with_capacity(ends.len());
+                let mut start = 0;
+                for end in ends {
+                    let mut doc_ords: Vec<u64> = term_ids[start..end as usize]
+                        .iter()
+                        .map(|&term_id| ords[term_id as usize])
+                        .collect();
+                    doc_ords.sort_unstable();
+                    doc_ords.dedup();
+                    all_ords.extend(doc_ords);
+                    ord_ends.push(all_ords.len() as u64);
+                    start = end as usize;
+                }
+                ImmutableDocValues::Sorted(SortedColumn {
+                    terms: terms
+                        .into_iter()
+                        .map(|(term, _)| term.to_string())
+                        .collect(),
+                    ords: Column::build(&ord_ends, &all_ords),
+                })
+            }
+        }
+    }
+
+    pub fn numeric(&self) -> Option<&Column> {
+        match self {
+            ImmutableDocValues::Numeric(column) => Some(column),
+            _ => None,
+        }
+    }
+
+    pub fn binary(&self) -> Option<&BinaryColumn> {
+        match self {
+            ImmutableDocValues::Binary(column) => Some(column),
+            _ => None,
+        }
+    }
+
+    pub fn sorted(&self) -> Option<&SortedColumn> {
+        match self {
+            ImmutableDocValues::Sorted(column) => Some(column),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        columnar::{BinaryDocValues, DocValuesWriter, NumericDocValues, SortedDocValues},
+        document::Value,
+        schema::{BytesOptions, Cardinality, FieldType, NumericOptions, TextOptions},
+    };
+
+    use super::ImmutableDocValues;
+
+    #[test]
+    fn test_numeric() {
+        for cardinality in [Cardinality::Single, Cardinality::Multi] {
+            let field_type = FieldType::U64(NumericOptions::default().set_doc_values(cardinality));
+            let writer = DocValuesWriter::new(&field_type).unwrap();
+            for doc in 0..300u64 {
+                let values: Vec<_> = (0..doc % 3).map(|i| Value::U64(doc * 1000 + i)).collect();
+                writer.add_doc(&values);
+            }
+            let doc_values = ImmutableDocValues::build(&writer, 250, u64::MAX);
+            let column = doc_values.numeric().unwrap();
+            let realtime = writer.numeric_doc_values(250).unwrap();
+            let (mut values, mut expected) = (Vec::new(), Vec::new());
+            for doc in 0..250 {
+                column.values(doc, &mut values);
+                realtime.values(doc, &mut expected);
+                assert_eq!(values, expected);
+            }
+            assert!(doc_values.sorted().is_none());
+        }
+    }
+
+    #[test]
+    fn test_updatable() {
+        let field_type = FieldType::U64(NumericOptions::default().set_updatable());
+        let writer = DocValuesWriter::new(&field_type).unwrap();
+        for doc in 0..10 {
+            writer.add_doc(&[Value::U64(doc)]);
+        }
+        writer.add_doc(&[]);
+        let updates = writer.updates().unwrap();
+        updates.update(2, 20, 1, 0);
+        updates.update(10, 100, 2, 0);
+        updates.update(2, 21, 3, 0);
+
+        let doc_values = ImmutableDocValues::build(&writer, 11, 2);
+        let column = doc_values.numeric().unwrap();
+        let values: Vec<_> = (0..11).filter_map(|doc| column.first(doc)).collect();
+        assert_eq!(values, [0, 1, 20, 3, 4, 5, 6, 7, 8, 9, 100]);
+        let doc_values = ImmutableDocValues::build(&writer, 11, u64::MAX);
+        assert_eq!(doc_values.numeric().unwrap().first(2), Some(21));
+    }
+
+    #[test]
+    fn test_binary() {
+        let field_type = FieldType::Bytes(BytesOptions::default().set_doc_values());
+        let writer = DocValuesWriter::new(&field_type).unwrap();
+        for doc in 0..100u8 {
+            if doc % 4 == 0 {
+                writer.add_doc(&[]);
+            } else {
+                writer.add_doc(&[Value::Bytes(vec![doc; doc as usize % 5])]);
+            }
+        }
+        let doc_values = ImmutableDocValues::build(&writer, 100, u64::MAX);
+        let column = doc_values.binary().unwrap();
+        let realtime = writer.binary_doc_values(100).unwrap();
+        for doc in 0..100 {
+            assert_eq!(column.value(doc), realtime.value(doc));
+        }
+    }
+
+    #[test]
+    fn test_sorted() {
+        let field_type = FieldType::Text(TextOptions::default().set_doc_values(Cardinality::Multi));
+        let writer = DocValuesWriter::new(&field_type).unwrap();
+        let text = |text: &str| Value::Str(text.to_string());
+        writer.add_doc(&[text("red"), text("blue"), text("red")]);
+        writer.add_doc(&[]);
+        writer.add_doc(&[text("green")]);
+        // Terms of the docs above `max_doc` are left out.
+        writer.add_doc(&[text("amber")]);
+        let doc_values = ImmutableDocValues::build(&writer, 3, u64::MAX);
+        let column = doc_values.sorted().unwrap();
+        let terms: Vec<_> = (0..column.term_count())
+            .map(|ord| column.term(ord))
+            .collect();
+        assert_eq!(terms, ["blue", "green", "red"]);
+        let mut ords = Vec::new();
+        column.ords(0, &mut ords);
+        assert_eq!(ords, [0, 2]);
+        assert_eq!(column.first_ord(1), None);
+        assert_eq!(column.first_ord(2), Some(1));
+    }
+}
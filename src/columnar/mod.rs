@@ -0,0 +1,19 @@
+mod bit_packed_vec;
+mod column;
+mod column_writer;
+mod doc_values;
+mod doc_values_writer;
+mod immutable_doc_values;
+mod numeric_column;
+mod numeric_updates;
+
+pub use bit_packed_vec::{bits_needed, BitPackedVec};
+pub use column::Column;
+pub use column_writer::ColumnWriter;
+pub use doc_values::{BinaryDocValues, NumericDocValues, SortedDocValues};
+pub use doc_values_writer::{
+    DocValuesWriter, RealtimeBinaryDocValues, RealtimeNumericDocValues, RealtimeSortedDocValues,
+};
+pub use immutable_doc_values::{BinaryColumn, ImmutableDocValues, SortedColumn};
+pub use numeric_column::NumericColumn;
+pub use numeric_updates::{NumericUpdates, UpdatedNumericDocValues};
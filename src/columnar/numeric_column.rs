@@ -0,0 +1,138 @@
+use super::bit_packed_vec::{bits_needed, BitPackedVec};
+
+// Tables are only used up to this number of distinct values.
+const MAX_TABLE_LEN: usize = 256;
+
+enum Encoding {
+    // Values are stored as `(value - min) / gcd`.
+    MinGcd { min: u64, gcd: u64 },
+    // Values are stored as indexes in a sorted table of the distinct values.
+    Table(Vec<u64>),
+}
+
+// A read-only sequence of u64s, bit-packed with whichever encoding takes the
+// fewest bits: the offsets from the min value divided by their greatest
+// common divisor, e.g. for timestamps in whole seconds, or indexes in a table
+// when there are few distinct values, e.g. for prices.
+pub struct NumericColumn {
+    encoding: Encoding,
+    packed: BitPackedVec,
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl NumericColumn {
+    pub fn build(values: &[u64]) -> Self {
+        let min = values.iter().copied().min().unwrap_or(0);
+        let max = values.iter().copied().max().unwrap_or(0);
+        let gcd = values
+            .iter()
+            .fold(0, |gcd_so_far, &value| gcd(gcd_so_far, value - min))
+            .max(1);
+        let bits = bits_needed((max - min) / gcd);
+
+        let mut table = Vec::new();
+        for &value in values {
+            if let Err(index) = table.binary_search(&value) {
+                if table.len() == MAX_TABLE_LEN {
+                    table.clear();
+                    break;
+                }
+                table.insert(index, value);
+            }
+        }
+        if !table.is_empty() {
+            let table_bits = bits_needed(table.len() as u64 - 1);
+            if table_bits < bits {
+                let packed = BitPackedVec::new(
+                    table_bits,
+                    values
+                        .iter()
+                        .map(|value| table.binary_search(value).unwrap() as u64),
+                );
+                return Self {
+                    encoding: Encoding::Table(table),
+                    packed,
+                };
+            }
+        }
+
+        Self {
+            encoding: Encoding::MinGcd { min, gcd },
+            packed: BitPackedVec::new(bits, values.iter().map(|value| (value - min) / gcd)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.packed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packed.is_empty()
+    }
+
+    pub fn bits_per_value(&self) -> u32 {
+        self.packed.bits()
+    }
+
+    pub fn get(&self, index: usize) -> u64 {
+        let packed = self.packed.get(index);
+        match &self.encoding {
+            Encoding::MinGcd { min, gcd } => min + packed * gcd,
+            Encoding::Table(table) => table[packed as usize],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NumericColumn;
+
+    fn check(values: &[u64]) -> NumericColumn {
+        let column = NumericColumn::build(values);
+        assert_eq!(column.len(), values.len());
+        for (index, &value) in values.iter().enumerate() {
+            assert_eq!(column.get(index), value);
+        }
+        column
+    }
+
+    #[test]
+    fn test_simple() {
+        assert!(check(&[]).is_empty());
+        assert_eq!(check(&[7, 7, 7]).bits_per_value(), 0);
+        // Offsets from the min.
+        let values: Vec<u64> = (0..1000).map(|i| 1_000_000 + i).collect();
+        assert_eq!(check(&values).bits_per_value(), 10);
+        // A table of three values.
+        assert_eq!(check(&[u64::MAX, 0, 12345]).bits_per_value(), 2);
+        let values: Vec<u64> = (0..1000u64)
+            .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+            .chain([u64::MAX])
+            .collect();
+        assert_eq!(check(&values).bits_per_value(), 64);
+    }
+
+    #[test]
+    fn test_gcd() {
+        let values: Vec<u64> = (0..1000)
+            .map(|i| 1_700_000_000_000_000 + i * 1_000_000)
+            .collect();
+        assert_eq!(check(&values).bits_per_value(), 10);
+    }
+
+    #[test]
+    fn test_table() {
+        let values: Vec<u64> = (0..1000).map(|i| [5, 1 << 40, 3 << 50][i % 3]).collect();
+        assert_eq!(check(&values).bits_per_value(), 2);
+        // Too many distinct values for a table.
+        let values: Vec<u64> = (0..1000).map(|i| (i * 7919) << 20).collect();
+        let column = check(&values);
+        assert!(column.bits_per_value() > 9);
+    }
+}
@@ -0,0 +1,303 @@
+use std::{collections::hash_map::RandomState, ptr};
+
+use crate::{
+    util::{AcqRelAtomicPtr, AcqRelUsize, ExponentialCapacityPolicy, LayeredHashMap},
+    DocId,
+};
+
+use super::NumericDocValues;
+
+const HEADS_INITIAL_CAPACITY: usize = 64;
+
+struct Update {
+    value: u64,
+    seq: u64,
+    // The previous update of the doc, or null.
+    prev: AcqRelAtomicPtr<Update>,
+}
+
+// The last update of a doc, overwritten by each new one.
+struct Head(AcqRelAtomicPtr<Update>);
+
+impl Drop for Head {
+    fn drop(&mut self) {
+        free_updates(self.0.load());
+    }
+}
+
+// Frees the update and the ones before it, returning how many there were.
+fn free_updates(mut update: *mut Update) -> usize {
+    let mut count = 0;
+    while !update.is_null() {
+        let boxed = unsafe { Box::from_raw(update) };
+        update = boxed.prev.load();
+        count += 1;
+    }
+    count
+}
+
+// The values given to the docs of an updatable field after they were added,
+// overlaid on the values they were added with.
+//
+// Each updated doc has a cell pointing to its last update, and each update
+// links to the previous one of the doc. Updates are numbered with increasing
+// seqs, so that a reader only seeing the updates up to a seq gets the same
+// values however many updates come after it. An update is written whole
+// before the cell of its doc points to it.
+//
+// Only the updates still visible to a reader are kept: the writer gives the
+// lowest seq a reader may be reading at, and the updates of a doc older than
+// the last one up to that seq are freed. Readers walk a chain from its head
+// and stop at the first update up to their seq, so they never reach them.
+pub struct NumericUpdates {
+    heads: LayeredHashMap<DocId, Head, RandomState, ExponentialCapacityPolicy>,
+    len: AcqRelUsize,
+}
+
+impl Default for NumericUpdates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NumericUpdates {
+    pub fn new() -> Self {
+        Self {
+            heads: LayeredHashMap::with_initial_capacity(
+                HEADS_INITIAL_CAPACITY,
+                RandomState::new(),
+                ExponentialCapacityPolicy,
+            ),
+            len: AcqRelUsize::new(0),
+        }
+    }
+
+    // Must only be called from the single writer thread, with seqs greater
+    // than the ones of the previous updates. No reader may read at a seq
+    // below `min_live_seq`, other than one reading every update from the
+    // writer thread.
+    pub fn update(&self, doc: DocId, value: u64, seq: u64, min_live_seq: u64) {
+        let head = match self.heads.get(&doc) {
+            Some(head) => head,
+            None => {
+                self.heads
+                    .insert(doc, Head(AcqRelAtomicPtr::new(ptr::null_mut())));
+                self.heads.get(&doc).unwrap()
+            }
+        };
+        let update = Box::new(Update {
+            value,
+            seq,
+            prev: AcqRelAtomicPtr::new(head.0.load()),
+        });
+        head.0.store(Box::into_raw(update));
+        let mut len = self.len.load() + 1;
+
+        let mut update = head.0.load();
+        while !update.is_null() {
+            let current = unsafe { &*update };
+            if current.seq <= min_live_seq {
+                let prev = current.prev.load();
+                current.prev.store(ptr::null_mut());
+                len -= free_updates(prev);
+                break;
+            }
+            update = current.prev.load();
+        }
+        self.len.store(len);
+    }
+
+    // The value of the last update of the doc with a seq up to `seq`.
+    pub fn get(&self, doc: DocId, seq: u64) -> Option<u64> {
+        let mut update = self.heads.get(&doc)?.0.load();
+        while !update.is_null() {
+            let current = unsafe { &*update };
+            if current.seq <= seq {
+                return Some(current.value);
+            }
+            update = current.prev.load();
+        }
+        None
+    }
+
+    // The number of updates kept.
+    pub fn len(&self) -> usize {
+        self.len.load()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// Doc values with the updates up to a seq applied over them.
+pub struct UpdatedNumericDocValues<'a, D> {
+    doc_values: D,
+    updates: Option<&'a NumericUpdates>,
+    update_seq: u64,
+    max_doc: DocId,
+}
+
+impl<'a, D: NumericDocValues> UpdatedNumericDocValues<'a, D> {
+    // `updates` is `None` for fields that are not updatable.
+    pub fn new(
+        doc_values: D,
+        updates: Option<&'a NumericUpdates>,
+        update_seq: u64,
+        max_doc: DocId,
+    ) -> Self {
+        Self {
+            doc_values,
+            updates,
+            update_seq,
+            max_doc,
+        }
+    }
+
+    fn updated_value(&self, doc: DocId) -> Option<u64> {
+        if doc >= self.max_doc {
+            return None;
+        }
+        self.updates?.get(doc, self.update_seq)
+    }
+}
+
+impl<'a, D: NumericDocValues> NumericDocValues for UpdatedNumericDocValues<'a, D> {
+    fn values(&self, doc: DocId, values: &mut Vec<u64>) {
+        match self.updated_value(doc) {
+            Some(value) => {
+                values.clear();
+                values.push(value);
+            }
+            None => self.doc_values.values(doc, values),
+        }
+    }
+
+    fn first(&self, doc: DocId) -> Option<u64> {
+        self.updated_value(doc)
+            .or_else(|| self.doc_values.first(doc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::{
+        columnar::{Column, NumericDocValues},
+        util::AcqRelU64,
+    };
+
+    use super::{NumericUpdates, UpdatedNumericDocValues};
+
+    #[test]
+    fn test_simple() {
+        let updates = NumericUpdates::new();
+        assert!(updates.is_empty());
+        updates.update(3, 30, 1, 0);
+        updates.update(5, 50, 2, 0);
+        updates.update(3, 31, 4, 0);
+        assert_eq!(updates.len(), 3);
+
+        assert_eq!(updates.get(3, 0), None);
+        assert_eq!(updates.get(3, 1), Some(30));
+        assert_eq!(updates.get(3, 3), Some(30));
+        assert_eq!(updates.get(3, u64::MAX), Some(31));
+        assert_eq!(updates.get(5, 1), None);
+        assert_eq!(updates.get(5, 2), Some(50));
+        assert_eq!(updates.get(4, u64::MAX), None);
+    }
+
+    #[test]
+    fn test_doc_values() {
+        // Docs 0 and 2 have a value, doc 1 doesn't.
+        let column = Column::build(&[1, 1, 2], &[7, 9]);
+        let updates = NumericUpdates::new();
+        updates.update(1, 4, 1, 0);
+        updates.update(2, 3, 2, 0);
+        // Docs above `max_doc` are not visible.
+        updates.update(3, 1, 3, 0);
+
+        let doc_values = UpdatedNumericDocValues::new(&column, Some(&updates), 1, 3);
+        let values: Vec<_> = (0..4).map(|doc| doc_values.first(doc)).collect();
+        assert_eq!(values, [Some(7), Some(4), Some(9), None]);
+        let doc_values = UpdatedNumericDocValues::new(&column, Some(&updates), 3, 3);
+        let mut values = Vec::new();
+        doc_values.values(2, &mut values);
+        assert_eq!(values, [3]);
+        assert_eq!(doc_values.first(3), None);
+        let doc_values = UpdatedNumericDocValues::new(&column, None, 3, 3);
+        assert_eq!(doc_values.first(2), Some(9));
+    }
+
+    #[test]
+    fn test_multithreads() {
+        let updates = NumericUpdates::new();
+        let seq = AcqRelU64::new(0);
+        // The seq the reader is reading at, below which history is freed.
+        let live_seq = AcqRelU64::new(0);
+        let count = 10_000;
+        thread::scope(|scope| {
+            let t = scope.spawn(|| loop {
+                // The value of each doc is the seq of its last update, which
+                // a reader sees up to the seq it started from.
+                let snapshot = seq.load();
+                live_seq.store(snapshot);
+                for doc in 0..10 {
+                    if let Some(value) = updates.get(doc, snapshot) {
+                        assert!(value <= snapshot);
+                        assert_eq!(value % 10, doc as u64);
+                    }
+                }
+                if snapshot == count {
+                    break;
+                }
+            });
+
+            for i in 1..=count {
+                updates.update((i % 10) as u32, i, i, live_seq.load());
+                seq.store(i);
+            }
+
+            t.join().unwrap();
+        });
+        // Once the reader is gone, only the last update of each doc is kept.
+        for doc in 0..10 {
+            let seq = count + 1 + doc as u64;
+            updates.update(doc, seq, seq, seq);
+        }
+        assert_eq!(updates.len(), 10);
+    }
+
+    #[test]
+    fn test_prune() {
+        let updates = NumericUpdates::new();
+        updates.update(1, 10, 1, 0);
+        updates.update(1, 11, 2, 0);
+        updates.update(2, 20, 3, 0);
+        // A reader may be at seq 1: the update at seq 1 is kept.
+        updates.update(1, 12, 4, 1);
+        assert_eq!(updates.len(), 4);
+        assert_eq!(updates.get(1, 1), Some(10));
+        assert_eq!(updates.get(1, 3), Some(11));
+
+        // Readers are at seq 3 and above: the update at seq 2 is the one they
+        // may see, and older ones are freed.
+        updates.update(1, 13, 5, 3);
+        assert_eq!(updates.len(), 4);
+        assert_eq!(updates.get(1, 3), Some(11));
+        assert_eq!(updates.get(1, 4), Some(12));
+        assert_eq!(updates.get(1, 5), Some(13));
+        assert_eq!(updates.get(2, 5), Some(20));
+
+        // Without older readers, only the last update of a doc is kept.
+        for seq in 6..100 {
+            updates.update(1, seq, seq, seq - 1);
+        }
+        assert_eq!(updates.len(), 3);
+        updates.update(1, 100, 100, 100);
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates.get(1, u64::MAX), Some(100));
+        assert_eq!(updates.get(2, u64::MAX), Some(20));
+    }
+}
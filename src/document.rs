@@ -0,0 +1,314 @@
+use crate::{
+    schema::{Field, FieldType},
+    util::{f64_to_u64, i64_to_u64, u64_to_f64, u64_to_i64},
+};
+
+// A point in time, in microseconds since the Unix epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime(i64);
+
+impl DateTime {
+    pub fn from_timestamp_secs(secs: i64) -> Self {
+        Self(secs * 1_000_000)
+    }
+
+    pub fn from_timestamp_millis(millis: i64) -> Self {
+        Self(millis * 1_000)
+    }
+
+    pub fn from_timestamp_micros(micros: i64) -> Self {
+        Self(micros)
+    }
+
+    pub fn into_timestamp_micros(self) -> i64 {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Str(String),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Date(DateTime),
+    Bytes(Vec<u8>),
+    // A number for each dimension of a point, all of the same type.
+    Point(Vec<Value>),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::I64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::U64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::F64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_date(&self) -> Option<DateTime> {
+        match self {
+            Value::Date(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    // Numbers mapped to u64s of the same order, which is how they are
+    // indexed.
+    pub fn to_sortable_u64(&self) -> Option<u64> {
+        match self {
+            Value::I64(value) => Some(i64_to_u64(*value)),
+            Value::U64(value) => Some(*value),
+            Value::F64(value) => Some(f64_to_u64(*value)),
+            Value::Date(value) => Some(i64_to_u64(value.into_timestamp_micros())),
+            _ => None,
+        }
+    }
+
+    // The sortable u64 of the number if it has the type of the field.
+    pub fn to_field_sortable_u64(&self, field_type: &FieldType) -> Option<u64> {
+        match (field_type, self) {
+            (FieldType::I64(_), Value::I64(_))
+            | (FieldType::U64(_), Value::U64(_))
+            | (FieldType::F64(_), Value::F64(_))
+            | (FieldType::Date(_), Value::Date(_)) => self.to_sortable_u64(),
+            _ => None,
+        }
+    }
+
+    // The number of a numeric field type mapped back from its sortable u64.
+    pub fn from_sortable_u64(field_type: &FieldType, value: u64) -> Option<Value> {
+        match field_type {
+            FieldType::Text(_) | FieldType::Bytes(_) => None,
+            FieldType::I64(_) => Some(Value::I64(u64_to_i64(value))),
+            FieldType::U64(_) => Some(Value::U64(value)),
+            FieldType::F64(_) => Some(Value::F64(u64_to_f64(value))),
+            FieldType::Date(_) => Some(Value::Date(DateTime::from_timestamp_micros(u64_to_i64(
+                value,
+            )))),
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_string())
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::I64(value)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Value::U64(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::F64(value)
+    }
+}
+
+impl From<DateTime> for Value {
+    fn from(value: DateTime) -> Self {
+        Value::Date(value)
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(value: Vec<u8>) -> Self {
+        Value::Bytes(value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldValue {
+    field: Field,
+    value: Value,
+}
+
+impl FieldValue {
+    pub fn new(field: Field, value: Value) -> Self {
+        Self { field, value }
+    }
+
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Document {
+    field_values: Vec<FieldValue>,
+}
+
+impl Document {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_field_value<V: Into<Value>>(&mut self, field: Field, value: V) {
+        self.field_values.push(FieldValue::new(field, value.into()));
+    }
+
+    pub fn add_text<S: Into<String>>(&mut self, field: Field, text: S) {
+        self.add_field_value(field, Value::Str(text.into()));
+    }
+
+    pub fn add_i64(&mut self, field: Field, value: i64) {
+        self.add_field_value(field, Value::I64(value));
+    }
+
+    pub fn add_u64(&mut self, field: Field, value: u64) {
+        self.add_field_value(field, Value::U64(value));
+    }
+
+    pub fn add_f64(&mut self, field: Field, value: f64) {
+        self.add_field_value(field, Value::F64(value));
+    }
+
+    pub fn add_date(&mut self, field: Field, value: DateTime) {
+        self.add_field_value(field, Value::Date(value));
+    }
+
+    pub fn add_bytes<B: Into<Vec<u8>>>(&mut self, field: Field, bytes: B) {
+        self.add_field_value(field, Value::Bytes(bytes.into()));
+    }
+
+    pub fn add_point<V: Into<Value>, I: IntoIterator<Item = V>>(&mut self, field: Field, point: I) {
+        self.add_field_value(
+            field,
+            Value::Point(point.into_iter().map(Into::into).collect()),
+        );
+    }
+
+    pub fn field_values(&self) -> &[FieldValue] {
+        &self.field_values
+    }
+
+    pub fn get_all(&self, field: Field) -> impl Iterator<Item = &Value> {
+        self.field_values
+            .iter()
+            .filter(move |field_value| field_value.field() == field)
+            .map(|field_value| field_value.value())
+    }
+
+    pub fn get_first(&self, field: Field) -> Option<&Value> {
+        self.get_all(field).next()
+    }
+
+    pub fn len(&self) -> usize {
+        self.field_values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.field_values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::{Field, FieldType};
+
+    use super::{DateTime, Document, Value};
+
+    #[test]
+    fn test_simple() {
+        let title = Field::from_field_id(0);
+        let body = Field::from_field_id(1);
+        let mut doc = Document::new();
+        assert!(doc.is_empty());
+        doc.add_text(title, "hello");
+        doc.add_text(body, "hello world");
+        doc.add_text(title, "rust");
+        assert_eq!(doc.len(), 3);
+
+        let titles: Vec<_> = doc.get_all(title).collect();
+        assert_eq!(
+            titles,
+            vec![
+                &Value::Str("hello".to_string()),
+                &Value::Str("rust".to_string())
+            ]
+        );
+        assert_eq!(doc.get_first(body).unwrap().as_str(), Some("hello world"));
+        assert!(doc.get_first(Field::from_field_id(2)).is_none());
+    }
+
+    #[test]
+    fn test_numeric() {
+        let price = Field::from_field_id(0);
+        let location = Field::from_field_id(1);
+        let mut doc = Document::new();
+        doc.add_f64(price, 9.5);
+        doc.add_point(location, [48i64, 2]);
+        doc.add_date(price, DateTime::from_timestamp_secs(1));
+
+        assert_eq!(doc.get_first(price).unwrap().as_f64(), Some(9.5));
+        assert_eq!(
+            doc.get_first(location),
+            Some(&Value::Point(vec![Value::I64(48), Value::I64(2)]))
+        );
+        assert_eq!(
+            doc.get_all(price).nth(1).unwrap().as_date(),
+            Some(DateTime::from_timestamp_micros(1_000_000))
+        );
+
+        assert!(Value::I64(-1).to_sortable_u64() < Value::I64(1).to_sortable_u64());
+        assert!(Value::F64(-0.5).to_sortable_u64() < Value::F64(0.25).to_sortable_u64());
+        assert!(Value::Str("a".to_string()).to_sortable_u64().is_none());
+        for (field_type, value) in [
+            (FieldType::I64(Default::default()), Value::I64(-7)),
+            (FieldType::F64(Default::default()), Value::F64(-0.5)),
+            (
+                FieldType::Date(Default::default()),
+                Value::Date(DateTime::from_timestamp_secs(-3)),
+            ),
+        ] {
+            let sortable = value.to_sortable_u64().unwrap();
+            assert_eq!(Value::from_sortable_u64(&field_type, sortable), Some(value));
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::{Arc, RwLock};
+
+use crate::{
+    analysis::{AnalyzerRegistry, Token},
+    schema::{Field, FieldType, Schema},
+    similarity::{Bm25Similarity, Similarity},
+};
+
+use super::{IndexSegments, Searcher};
+
+#[derive(Clone)]
+pub struct IndexReader {
+    segments: Arc<RwLock<IndexSegments>>,
+    schema: Schema,
+    analyzer_registry: Arc<AnalyzerRegistry>,
+    similarity: Arc<dyn Similarity>,
+}
+
+impl IndexReader {
+    pub(crate) fn new(
+        segments: Arc<RwLock<IndexSegments>>,
+        schema: Schema,
+        analyzer_registry: Arc<AnalyzerRegistry>,
+    ) -> Self {
+        Self {
+            segments,
+            schema,
+            analyzer_registry,
+            similarity: Arc::new(Bm25Similarity::default()),
+        }
+    }
+
+    pub fn set_similarity<S: Similarity + 'static>(mut self, similarity: S) -> Self {
+        self.similarity = Arc::new(similarity);
+        self
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    // Analyzes query text with the search analyzer of the field, or keeps it
+    // as a single term when the field has none, like its values are indexed.
+    // Numeric fields have no terms.
+    pub fn search_tokens(&self, field: Field, text: &str) -> Vec<Token> {
+        match self.schema.field_entry(field).field_type() {
+            FieldType::Text(options) => match options.search_analyzer() {
+                Some(name) => self.analyzer_registry.get(name).unwrap().analyze(text),
+                None => vec![Token::new(text.to_string(), 0, text.len())],
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn doc_count(&self) -> usize {
+        self.segments.read().unwrap().doc_count()
+    }
+
+    pub fn searcher(&self) -> Searcher {
+        let segments = self.segments.read().unwrap().clone();
+        let (segment_readers, update_seq_guard) = segments.segment_readers();
+        Searcher::new(
+            self.schema.clone(),
+            segment_readers,
+            update_seq_guard,
+            self.similarity.clone(),
+        )
+    }
+}
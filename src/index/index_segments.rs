@@ -0,0 +1,156 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    columnar::NumericUpdates,
+    schema::Field,
+    segment::{
+        ImmutableSegment, ImmutableSegmentReader, RealtimeSegment, RealtimeSegmentReader,
+        SegmentReader,
+    },
+    util::AcqRelU64,
+    DocId,
+};
+
+// The segments of an index: the immutable segments in flush order, followed by
+// the realtime segment currently being written.
+//
+// The update seq counts the updates of doc values across all segments. It is
+// published after each update, and readers capture it once for all of their
+// segments, so that a searcher sees either all of the updates up to a point
+// or none of them, whatever segment they are made in. The seqs of the live
+// searchers are tracked, so that updates no searcher can see anymore are
+// freed.
+#[derive(Clone)]
+pub(crate) struct IndexSegments {
+    immutable_segments: Vec<Arc<ImmutableSegment>>,
+    realtime_segment: Arc<RealtimeSegment>,
+    update_seq: Arc<AcqRelU64>,
+    live_update_seqs: Arc<LiveUpdateSeqs>,
+}
+
+// The update seqs of the live searchers, with the number of searchers at each.
+#[derive(Default)]
+pub(crate) struct LiveUpdateSeqs(Mutex<BTreeMap<u64, usize>>);
+
+// Keeps the update seq of a searcher live until dropped.
+pub(crate) struct UpdateSeqGuard {
+    live_update_seqs: Arc<LiveUpdateSeqs>,
+    update_seq: u64,
+}
+
+impl Drop for UpdateSeqGuard {
+    fn drop(&mut self) {
+        let mut seqs = self.live_update_seqs.0.lock().unwrap();
+        let count = seqs.get_mut(&self.update_seq).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            seqs.remove(&self.update_seq);
+        }
+    }
+}
+
+impl IndexSegments {
+    pub fn new(realtime_segment: Arc<RealtimeSegment>) -> Self {
+        Self {
+            immutable_segments: Vec::new(),
+            realtime_segment,
+            update_seq: Arc::new(AcqRelU64::new(0)),
+            live_update_seqs: Arc::new(LiveUpdateSeqs::default()),
+        }
+    }
+
+    pub fn doc_count(&self) -> usize {
+        self.immutable_segments
+            .iter()
+            .map(|segment| segment.max_doc() as usize)
+            .sum::<usize>()
+            + self.realtime_segment.doc_count()
+    }
+
+    // Replaces the realtime segment by its immutable version and a new,
+    // empty realtime segment.
+    pub fn flush(
+        &mut self,
+        immutable_segment: Arc<ImmutableSegment>,
+        realtime_segment: Arc<RealtimeSegment>,
+    ) {
+        self.immutable_segments.push(immutable_segment);
+        self.realtime_segment = realtime_segment;
+    }
+
+    pub fn update_seq(&self) -> u64 {
+        self.update_seq.load()
+    }
+
+    // Must only be called from the single writer thread. The segment is given
+    // by its ordinal in the segment readers, which is kept when the realtime
+    // segment is flushed.
+    pub fn update_numeric(&self, segment_ord: usize, doc: DocId, field: Field, value: u64) {
+        let (updates, max_doc): (Option<&NumericUpdates>, DocId) =
+            match self.immutable_segments.get(segment_ord) {
+                Some(segment) => (segment.numeric_updates(field), segment.max_doc()),
+                None => {
+                    assert_eq!(
+                        segment_ord,
+                        self.immutable_segments.len(),
+                        "Segment {} does not exist",
+                        segment_ord
+                    );
+                    (
+                        self.realtime_segment
+                            .doc_values_writer(field)
+                            .and_then(|doc_values_writer| doc_values_writer.updates()),
+                        self.realtime_segment.doc_count() as DocId,
+                    )
+                }
+            };
+        let updates = updates.expect("Field is not updatable");
+        assert!(
+            doc < max_doc,
+            "Doc {} does not exist in segment {}",
+            doc,
+            segment_ord
+        );
+        // Searchers created from now on see at least the current seq, and
+        // the others registered theirs under the lock.
+        let current_seq = self.update_seq.load();
+        let min_live_seq = match self.live_update_seqs.0.lock().unwrap().keys().next() {
+            Some(&seq) => seq.min(current_seq),
+            None => current_seq,
+        };
+        updates.update(doc, value, current_seq + 1, min_live_seq);
+        self.update_seq.store(current_seq + 1);
+    }
+
+    // The readers of the segments at the current update seq, which is kept
+    // live until the guard is dropped.
+    pub fn segment_readers(&self) -> (Vec<SegmentReader>, UpdateSeqGuard) {
+        let update_seq = {
+            let mut seqs = self.live_update_seqs.0.lock().unwrap();
+            let update_seq = self.update_seq();
+            *seqs.entry(update_seq).or_default() += 1;
+            update_seq
+        };
+        let guard = UpdateSeqGuard {
+            live_update_seqs: self.live_update_seqs.clone(),
+            update_seq,
+        };
+        let mut segment_readers: Vec<_> = self
+            .immutable_segments
+            .iter()
+            .map(|segment| {
+                SegmentReader::Immutable(ImmutableSegmentReader::with_update_seq(
+                    segment.clone(),
+                    update_seq,
+                ))
+            })
+            .collect();
+        segment_readers.push(SegmentReader::Realtime(
+            RealtimeSegmentReader::with_update_seq(self.realtime_segment.clone(), update_seq),
+        ));
+        (segment_readers, guard)
+    }
+}
@@ -0,0 +1,632 @@
+use std::sync::{Arc, RwLock};
+
+use crate::{
+    analysis::AnalyzerRegistry,
+    document::{Document, Value},
+    schema::{Field, FieldType, Schema},
+    segment::{ImmutableSegment, RealtimeSegment, RealtimeSegmentReader},
+    DocId,
+};
+
+use super::{IndexReader, IndexSegments};
+
+pub struct IndexWriter {
+    schema: Schema,
+    analyzer_registry: Arc<AnalyzerRegistry>,
+    segment: Arc<RealtimeSegment>,
+    segments: Arc<RwLock<IndexSegments>>,
+}
+
+impl IndexWriter {
+    pub fn new(schema: Schema) -> Self {
+        Self::with_analyzer_registry(schema, AnalyzerRegistry::default())
+    }
+
+    // Panics if a field refers to an analyzer missing from the registry.
+    pub fn with_analyzer_registry(schema: Schema, analyzer_registry: AnalyzerRegistry) -> Self {
+        for (_, field_entry) in schema.fields() {
+            if let FieldType::Text(options) = field_entry.field_type() {
+                if let Some(name) = options.search_analyzer() {
+                    assert!(
+                        analyzer_registry.get(name).is_some(),
+                        "Analyzer `{}` of field `{}` is not registered",
+                        name,
+                        field_entry.name()
+                    );
+                }
+            }
+        }
+        let analyzer_registry = Arc::new(analyzer_registry);
+        let segment = Arc::new(RealtimeSegment::new(schema.clone(), &analyzer_registry));
+        let segments = Arc::new(RwLock::new(IndexSegments::new(segment.clone())));
+        Self {
+            schema,
+            analyzer_registry,
+            segment,
+            segments,
+        }
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn analyzer_registry(&self) -> &AnalyzerRegistry {
+        &self.analyzer_registry
+    }
+
+    // Returns the id of the doc within the current realtime segment.
+    pub fn add_document(&mut self, doc: &Document) -> DocId {
+        self.segment.add_document(doc)
+    }
+
+    // Overwrites the value of an updatable numeric field of a doc, given by
+    // the ordinal of its segment and its id in the segment, as found in
+    // search results. Searchers created from then on see the new value,
+    // while the existing ones keep the values they were created with.
+    //
+    // Panics if the field is not updatable, the value is not of the type of
+    // the field, or the doc doesn't exist.
+    pub fn update_numeric_value<V: Into<Value>>(
+        &mut self,
+        segment_ord: usize,
+        doc: DocId,
+        field: Field,
+        value: V,
+    ) {
+        let field_entry = self.schema.field_entry(field);
+        assert!(
+            field_entry.field_type().is_updatable(),
+            "Field `{}` is not updatable",
+            field_entry.name()
+        );
+        let value = value
+            .into()
+            .to_field_sortable_u64(field_entry.field_type())
+            .unwrap_or_else(|| panic!("Value is not of the type of `{}`", field_entry.name()));
+        self.segments
+            .read()
+            .unwrap()
+            .update_numeric(segment_ord, doc, field, value);
+    }
+
+    // Turns the realtime segment into an immutable segment, and starts a new
+    // realtime segment for the following documents. The doc values of the
+    // immutable segment include every update made so far.
+    pub fn flush(&mut self) {
+        if self.segment.doc_count() == 0 {
+            return;
+        }
+        let segment_reader = RealtimeSegmentReader::new(self.segment.clone());
+        let immutable_segment = Arc::new(ImmutableSegment::build(&segment_reader));
+        self.segment = Arc::new(RealtimeSegment::new(
+            self.schema.clone(),
+            &self.analyzer_registry,
+        ));
+        self.segments
+            .write()
+            .unwrap()
+            .flush(immutable_segment, self.segment.clone());
+    }
+
+    pub fn reader(&self) -> IndexReader {
+        IndexReader::new(
+            self.segments.clone(),
+            self.schema.clone(),
+            self.analyzer_registry.clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use crate::{
+        analysis::{
+            Analyzer, AnalyzerRegistry, EdgeNGramFilter, LowerCaseFilter, StandardTokenizer,
+            WhitespaceTokenizer,
+        },
+        document::Document,
+        index::Searcher,
+        postings::collect_postings,
+        schema::{BytesOptions, Cardinality, NumericOptions, Schema, TextOptions},
+        search::{TermQuery, TopDocs},
+        similarity::{
+            Bm25Similarity, CollectionStatistics, ConstantSimilarity, Similarity, TermStatistics,
+        },
+        TERMINATED,
+    };
+
+    use super::IndexWriter;
+
+    #[test]
+    fn test_simple() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        let reader = writer.reader();
+        assert_eq!(reader.doc_count(), 0);
+
+        let mut doc = Document::new();
+        doc.add_text(title, "hello world");
+        assert_eq!(writer.add_document(&doc), 0);
+        assert_eq!(reader.doc_count(), 1);
+
+        let mut doc = Document::new();
+        doc.add_text(title, "hello");
+        assert_eq!(writer.add_document(&doc), 1);
+        assert_eq!(reader.doc_count(), 2);
+
+        let searcher = reader.searcher();
+        assert_eq!(searcher.max_doc(), 2);
+        let segment_reader = searcher.segment_reader(0);
+        let hello = collect_postings(&mut *segment_reader.postings(title, "hello").unwrap());
+        assert_eq!(hello, vec![(0, 1), (1, 1)]);
+        let world = collect_postings(&mut *segment_reader.postings(title, "world").unwrap());
+        assert_eq!(world, vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_flush() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        let reader = writer.reader();
+        writer.flush();
+        assert_eq!(reader.searcher().segment_readers().len(), 1);
+
+        let mut doc = Document::new();
+        doc.add_text(title, "hello world");
+        writer.add_document(&doc);
+        let searcher_before_flush = reader.searcher();
+
+        writer.flush();
+        let mut doc = Document::new();
+        doc.add_text(title, "hello");
+        assert_eq!(writer.add_document(&doc), 0);
+        assert_eq!(reader.doc_count(), 2);
+
+        let searcher = reader.searcher();
+        assert_eq!(searcher.max_doc(), 2);
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let hello: Vec<_> = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment_reader| {
+                collect_postings(&mut *segment_reader.postings(title, "hello").unwrap())
+            })
+            .collect();
+        assert_eq!(hello, vec![vec![(0, 1)], vec![(0, 1)]]);
+        assert!(searcher
+            .segment_reader(1)
+            .postings(title, "world")
+            .is_none());
+
+        assert_eq!(searcher_before_flush.max_doc(), 1);
+        assert_eq!(searcher_before_flush.segment_readers().len(), 1);
+        let mut postings = searcher_before_flush
+            .segment_reader(0)
+            .postings(title, "hello")
+            .unwrap();
+        assert_eq!(postings.doc(), 0);
+        assert_eq!(postings.next(), TERMINATED);
+    }
+
+    #[test]
+    fn test_analyzer_registry() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default().set_indexed().set_analyzer("custom"),
+        );
+        let schema = builder.build();
+
+        let mut analyzer_registry = AnalyzerRegistry::empty();
+        analyzer_registry.register(
+            "custom",
+            Analyzer::new(WhitespaceTokenizer).add_token_filter(LowerCaseFilter),
+        );
+        let mut writer = IndexWriter::with_analyzer_registry(schema, analyzer_registry);
+        let mut doc = Document::new();
+        doc.add_text(title, "Hello, World!");
+        writer.add_document(&doc);
+
+        let searcher = writer.reader().searcher();
+        let segment_reader = searcher.segment_reader(0);
+        assert!(segment_reader.postings(title, "hello,").is_some());
+        assert!(segment_reader.postings(title, "world!").is_some());
+        assert!(segment_reader.postings(title, "hello").is_none());
+    }
+
+    #[test]
+    fn test_search_analyzer() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("autocomplete")
+                .set_search_analyzer("standard"),
+        );
+        let title_keyword =
+            builder.add_sub_field(title, "keyword", TextOptions::default().set_indexed());
+        let schema = builder.build();
+
+        let mut analyzer_registry = AnalyzerRegistry::default();
+        analyzer_registry.register(
+            "autocomplete",
+            Analyzer::new(StandardTokenizer)
+                .add_token_filter(LowerCaseFilter)
+                .add_token_filter(EdgeNGramFilter::new(1, 10)),
+        );
+        let mut writer = IndexWriter::with_analyzer_registry(schema, analyzer_registry);
+        let mut doc = Document::new();
+        doc.add_text(title, "Rust Search");
+        writer.add_document(&doc);
+
+        let reader = writer.reader();
+        let terms: Vec<_> = reader
+            .search_tokens(title, "Sea")
+            .into_iter()
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(terms, vec!["sea"]);
+        let terms: Vec<_> = reader
+            .search_tokens(title_keyword, "Rust Search")
+            .into_iter()
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(terms, vec!["Rust Search"]);
+
+        let searcher = reader.searcher();
+        let segment_reader = searcher.segment_reader(0);
+        assert!(segment_reader.postings(title, "sea").is_some());
+        assert!(segment_reader
+            .postings(title_keyword, "Rust Search")
+            .is_some());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_missing_search_analyzer() {
+        let mut builder = Schema::builder();
+        builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard")
+                .set_search_analyzer("missing"),
+        );
+        IndexWriter::new(builder.build());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_missing_analyzer() {
+        let mut builder = Schema::builder();
+        builder.add_text_field(
+            "title",
+            TextOptions::default().set_indexed().set_analyzer("missing"),
+        );
+        IndexWriter::new(builder.build());
+    }
+
+    #[test]
+    fn test_multithreads() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        let reader = writer.reader();
+        let count = 1024;
+
+        thread::scope(|scope| {
+            let t = scope.spawn(|| loop {
+                let searcher = reader.searcher();
+                let mut hello_count = 0;
+                for segment_reader in searcher.segment_readers() {
+                    let postings = segment_reader
+                        .postings(title, "hello")
+                        .map(|mut postings| collect_postings(&mut *postings))
+                        .unwrap_or_default();
+                    assert_eq!(postings.len(), segment_reader.max_doc() as usize);
+                    for (i, &(doc, freq)) in postings.iter().enumerate() {
+                        assert_eq!(doc, i as u32);
+                        assert_eq!(freq, 1);
+                    }
+                    hello_count += postings.len();
+                }
+                assert_eq!(hello_count, searcher.max_doc() as usize);
+                if searcher.max_doc() == count {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(1));
+            });
+
+            for i in 0..count {
+                let mut doc = Document::new();
+                doc.add_text(title, format!("hello term{}", i));
+                writer.add_document(&doc);
+                if i % 100 == 99 {
+                    writer.flush();
+                }
+            }
+
+            t.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn test_update_numeric_value() {
+        let mut builder = Schema::builder();
+        let views = builder.add_u64_field("views", NumericOptions::default().set_updatable());
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        let reader = writer.reader();
+        for value in [1, 2] {
+            let mut doc = Document::new();
+            doc.add_u64(views, value);
+            writer.add_document(&doc);
+        }
+        writer.add_document(&Document::new());
+        let all_views = |searcher: &Searcher| -> Vec<Vec<Option<u64>>> {
+            searcher
+                .segment_readers()
+                .iter()
+                .map(|segment_reader| {
+                    let doc_values = segment_reader.numeric_doc_values(views).unwrap();
+                    (0..segment_reader.max_doc())
+                        .map(|doc| doc_values.first(doc))
+                        .collect()
+                })
+                .collect()
+        };
+
+        let searcher_before_update = reader.searcher();
+        writer.update_numeric_value(0, 1, views, 20u64);
+        writer.update_numeric_value(0, 2, views, 30u64);
+        let searcher = reader.searcher();
+        assert_eq!(all_views(&searcher), [[Some(1), Some(20), Some(30)]]);
+        // Existing searchers keep the values they were created with.
+        assert_eq!(
+            all_views(&searcher_before_update),
+            [[Some(1), Some(2), None]]
+        );
+
+        // Updates are kept by the flush, and the segment keeps its ordinal.
+        writer.flush();
+        let mut doc = Document::new();
+        doc.add_u64(views, 4);
+        writer.add_document(&doc);
+        writer.update_numeric_value(0, 1, views, 21u64);
+        writer.update_numeric_value(1, 0, views, 40u64);
+        assert_eq!(
+            all_views(&reader.searcher()),
+            [vec![Some(1), Some(21), Some(30)], vec![Some(40)]]
+        );
+        assert_eq!(all_views(&searcher), [[Some(1), Some(20), Some(30)]]);
+    }
+
+    #[test]
+    fn test_update_history() {
+        let mut builder = Schema::builder();
+        let views = builder.add_u64_field("views", NumericOptions::default().set_updatable());
+        let mut writer = IndexWriter::new(builder.build());
+        let reader = writer.reader();
+        writer.add_document(&Document::new());
+        let update_count = |writer: &IndexWriter| {
+            let doc_values_writer = writer.segment.doc_values_writer(views).unwrap();
+            doc_values_writer.updates().unwrap().len()
+        };
+        let first_value = |searcher: &Searcher| {
+            let segment_reader = searcher.segment_reader(0);
+            segment_reader.numeric_doc_values(views).unwrap().first(0)
+        };
+
+        // Without searchers, only the last update and the one a new searcher
+        // may still be created with are kept.
+        for value in 1..=10u64 {
+            writer.update_numeric_value(0, 0, views, value);
+        }
+        assert_eq!(update_count(&writer), 2);
+
+        // The updates a searcher may see are kept while it is alive.
+        let searcher = reader.searcher();
+        for value in 11..=20u64 {
+            writer.update_numeric_value(0, 0, views, value);
+        }
+        assert_eq!(update_count(&writer), 11);
+        assert_eq!(first_value(&searcher), Some(10));
+        assert_eq!(first_value(&reader.searcher()), Some(20));
+
+        drop(searcher);
+        writer.update_numeric_value(0, 0, views, 21u64);
+        assert_eq!(update_count(&writer), 2);
+        assert_eq!(first_value(&reader.searcher()), Some(21));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_update_not_updatable() {
+        let mut builder = Schema::builder();
+        let views = builder.add_u64_field(
+            "views",
+            NumericOptions::default().set_doc_values(Cardinality::Single),
+        );
+        let mut writer = IndexWriter::new(builder.build());
+        let mut doc = Document::new();
+        doc.add_u64(views, 1);
+        writer.add_document(&doc);
+        writer.update_numeric_value(0, 0, views, 2u64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_update_missing_doc() {
+        let mut builder = Schema::builder();
+        let views = builder.add_u64_field("views", NumericOptions::default().set_updatable());
+        let mut writer = IndexWriter::new(builder.build());
+        writer.update_numeric_value(0, 0, views, 2u64);
+    }
+
+    #[test]
+    fn test_stored_fields() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard")
+                .set_stored(),
+        );
+        // Copies are not stored, even into stored fields.
+        builder.add_sub_field(title, "raw", TextOptions::default().set_stored());
+        let body = builder.add_text_field(
+            "body",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let price = builder.add_f64_field("price", NumericOptions::default().set_stored());
+        let thumbnail = builder.add_bytes_field("thumbnail", BytesOptions::default().set_stored());
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        let reader = writer.reader();
+        let new_doc = |i: usize| {
+            let mut doc = Document::new();
+            doc.add_text(title, format!("title {}", i));
+            doc.add_text(body, "hello world");
+            doc.add_f64(price, i as f64 / 2.0);
+            if i.is_multiple_of(2) {
+                doc.add_bytes(thumbnail, vec![i as u8; i % 5]);
+            }
+            doc.add_text(title, "second title");
+            doc
+        };
+        let stored_doc = |i: usize| {
+            let mut doc = Document::new();
+            for field_value in new_doc(i).field_values() {
+                if field_value.field() != body {
+                    doc.add_field_value(field_value.field(), field_value.value().clone());
+                }
+            }
+            doc
+        };
+        for i in 0..1000 {
+            if i == 600 {
+                writer.flush();
+            }
+            writer.add_document(&new_doc(i));
+        }
+
+        let searcher = reader.searcher();
+        let docs = searcher.search(&TermQuery::new(body, "hello"), &TopDocs::new(1000));
+        assert_eq!(docs.len(), 1000);
+        for scored_doc in docs {
+            let i = scored_doc.segment_ord * 600 + scored_doc.doc as usize;
+            assert_eq!(
+                searcher.doc(scored_doc.segment_ord, scored_doc.doc),
+                stored_doc(i)
+            );
+            let doc =
+                searcher.doc_with_fields(scored_doc.segment_ord, scored_doc.doc, &[title, body]);
+            let titles: Vec<_> = doc
+                .get_all(title)
+                .filter_map(|value| value.as_str())
+                .collect();
+            assert_eq!(titles, [format!("title {}", i).as_str(), "second title"]);
+            assert_eq!(doc.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_statistics() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        for text in ["hello world", "hello", "big small world"] {
+            let mut doc = Document::new();
+            doc.add_text(title, text);
+            writer.add_document(&doc);
+        }
+        writer.flush();
+        let mut doc = Document::new();
+        doc.add_text(title, "world peace");
+        writer.add_document(&doc);
+        writer.add_document(&Document::new());
+
+        let searcher = writer.reader().searcher();
+        // The last doc has no title.
+        assert_eq!(
+            searcher.collection_statistics(title),
+            CollectionStatistics {
+                doc_count: 4,
+                total_field_length: 8,
+            }
+        );
+        assert_eq!(
+            searcher.term_statistics(title, "world"),
+            TermStatistics { doc_freq: 3 }
+        );
+        assert_eq!(searcher.segment_reader(0).field_length(title, 2), 3);
+        assert_eq!(searcher.segment_reader(1).field_length(title, 0), 2);
+        assert_eq!(searcher.segment_reader(1).field_length(title, 1), 0);
+
+        let scorer = searcher.similarity().scorer(
+            1.0,
+            &searcher.collection_statistics(title),
+            &[searcher.term_statistics(title, "world")],
+        );
+        let expect = Bm25Similarity::default().scorer(
+            1.0,
+            &CollectionStatistics {
+                doc_count: 4,
+                total_field_length: 8,
+            },
+            &[TermStatistics { doc_freq: 3 }],
+        );
+        assert_eq!(scorer.score(1.0, 2), expect.score(1.0, 2));
+        assert!(scorer.score(1.0, 2) > scorer.score(1.0, 3));
+
+        let searcher = writer
+            .reader()
+            .set_similarity(ConstantSimilarity)
+            .searcher();
+        let scorer = searcher.similarity().scorer(
+            2.0,
+            &searcher.collection_statistics(title),
+            &[searcher.term_statistics(title, "world")],
+        );
+        assert_eq!(scorer.score(1.0, 2), 2.0);
+    }
+}
@@ -0,0 +1,9 @@
+mod index_reader;
+mod index_segments;
+mod index_writer;
+mod searcher;
+
+pub use index_reader::IndexReader;
+pub(crate) use index_segments::{IndexSegments, UpdateSeqGuard};
+pub use index_writer::IndexWriter;
+pub use searcher::Searcher;
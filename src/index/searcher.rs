@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use crate::{
+    document::Document,
+    schema::{Field, Schema},
+    search::{Collector, Query, SegmentCollector},
+    segment::SegmentReader,
+    similarity::{CollectionStatistics, Similarity, TermStatistics},
+    DocId, TERMINATED,
+};
+
+use super::UpdateSeqGuard;
+
+pub struct Searcher {
+    schema: Schema,
+    segment_readers: Vec<SegmentReader>,
+    // Keeps the updates seen by the segment readers from being freed.
+    _update_seq_guard: UpdateSeqGuard,
+    similarity: Arc<dyn Similarity>,
+}
+
+impl Searcher {
+    pub(crate) fn new(
+        schema: Schema,
+        segment_readers: Vec<SegmentReader>,
+        update_seq_guard: UpdateSeqGuard,
+        similarity: Arc<dyn Similarity>,
+    ) -> Self {
+        Self {
+            schema,
+            segment_readers,
+            _update_seq_guard: update_seq_guard,
+            similarity,
+        }
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn similarity(&self) -> &dyn Similarity {
+        self.similarity.as_ref()
+    }
+
+    pub fn max_doc(&self) -> DocId {
+        self.segment_readers
+            .iter()
+            .map(|segment_reader| segment_reader.max_doc())
+            .sum()
+    }
+
+    pub fn segment_readers(&self) -> &[SegmentReader] {
+        &self.segment_readers
+    }
+
+    pub fn segment_reader(&self, segment_ord: usize) -> &SegmentReader {
+        &self.segment_readers[segment_ord]
+    }
+
+    // The stored field values of a doc, as found in search results.
+    pub fn doc(&self, segment_ord: usize, doc: DocId) -> Document {
+        self.segment_readers[segment_ord].doc(doc)
+    }
+
+    pub fn doc_with_fields(&self, segment_ord: usize, doc: DocId, fields: &[Field]) -> Document {
+        self.segment_readers[segment_ord].doc_with_fields(doc, fields)
+    }
+
+    // Runs the query over every segment, in order, passing each match to the
+    // collector with its score.
+    pub fn search<C: Collector>(&self, query: &dyn Query, collector: &C) -> C::Fruit {
+        let weight = query.weight(self);
+        let mut min_competitive_score = 0.0;
+        let mut fruits = Vec::with_capacity(self.segment_readers.len());
+        for (segment_ord, segment_reader) in self.segment_readers.iter().enumerate() {
+            let mut segment_collector = collector.for_segment(segment_ord, segment_reader);
+            if let Some(mut scorer) = weight.scorer(segment_reader) {
+                if min_competitive_score > 0.0 {
+                    scorer.set_min_competitive_score(min_competitive_score);
+                }
+                while scorer.doc() != TERMINATED {
+                    segment_collector.collect(scorer.doc(), scorer.score());
+                    let min_score = segment_collector.min_competitive_score();
+                    if min_score > min_competitive_score {
+                        min_competitive_score = min_score;
+                        scorer.set_min_competitive_score(min_score);
+                    }
+                    scorer.next();
+                }
+            }
+            fruits.push(segment_collector.harvest());
+        }
+        collector.merge_fruits(fruits)
+    }
+
+    // Statistics over the docs of the snapshot with the field, across all
+    // segments so that scores are comparable between them. Docs without the
+    // field would lower the average field length and raise every idf.
+    pub fn collection_statistics(&self, field: Field) -> CollectionStatistics {
+        CollectionStatistics {
+            doc_count: self
+                .segment_readers
+                .iter()
+                .map(|segment_reader| segment_reader.field_doc_count(field))
+                .sum(),
+            total_field_length: self
+                .segment_readers
+                .iter()
+                .map(|segment_reader| segment_reader.total_field_length(field))
+                .sum(),
+        }
+    }
+
+    pub fn term_statistics(&self, field: Field, term: &str) -> TermStatistics {
+        TermStatistics {
+            doc_freq: self
+                .segment_readers
+                .iter()
+                .map(|segment_reader| segment_reader.doc_freq(field, term) as u64)
+                .sum(),
+        }
+    }
+}
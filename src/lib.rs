@@ -1,5 +1,21 @@
+pub mod analysis;
+pub mod automata;
+pub mod columnar;
+pub mod document;
+pub mod index;
+pub mod points;
+pub mod postings;
+pub mod schema;
+pub mod search;
+pub mod segment;
+pub mod similarity;
+pub mod store;
 pub mod util;
 
+pub type DocId = u32;
+
+pub const TERMINATED: DocId = DocId::MAX;
+
 pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
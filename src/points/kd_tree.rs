@@ -0,0 +1,244 @@
+use std::ops::RangeInclusive;
+
+use crate::DocId;
+
+const MAX_POINTS_IN_LEAF: usize = 512;
+
+struct KdNode {
+    // The points of the node, which are contiguous in leaf order.
+    start: usize,
+    end: usize,
+    children: Option<(usize, usize)>,
+}
+
+// The points of a field in an immutable segment, split recursively at the
+// median of their widest dimension into blocks of up to 512 points.
+//
+// Every node records the bounding box of its points, so that range queries
+// skip the nodes outside the range, take all the docs of the nodes inside it,
+// and only test the points of the leaves crossing it.
+pub struct KdTree {
+    dimensions: usize,
+    docs: Vec<DocId>,
+    // The values of the points in leaf order, `dimensions` per point.
+    values: Vec<u64>,
+    nodes: Vec<KdNode>,
+    // The min then the max of each dimension, for each node.
+    bounds: Vec<u64>,
+}
+
+impl KdTree {
+    pub fn build(dimensions: usize, docs: Vec<DocId>, values: Vec<u64>) -> Self {
+        Self::build_with_leaf_size(dimensions, docs, values, MAX_POINTS_IN_LEAF)
+    }
+
+    fn build_with_leaf_size(
+        dimensions: usize,
+        docs: Vec<DocId>,
+        values: Vec<u64>,
+        max_points_in_leaf: usize,
+    ) -> Self {
+        assert_eq!(docs.len() * dimensions, values.len());
+        let mut order: Vec<usize> = (0..docs.len()).collect();
+        let mut tree = Self {
+            dimensions,
+            docs: Vec::new(),
+            values: Vec::new(),
+            nodes: Vec::new(),
+            bounds: Vec::new(),
+        };
+        if !order.is_empty() {
+            tree.build_node(&mut order, 0, &values, max_points_in_leaf);
+        }
+        tree.docs = order.iter().map(|&point| docs[point]).collect();
+        tree.values = order
+            .iter()
+            .flat_map(|&point| &values[point * dimensions..(point + 1) * dimensions])
+            .copied()
+            .collect();
+        tree
+    }
+
+    // Builds the node of the points of `order`, which start at `start` in leaf
+    // order, and returns its index.
+    fn build_node(
+        &mut self,
+        order: &mut [usize],
+        start: usize,
+        values: &[u64],
+        max_points_in_leaf: usize,
+    ) -> usize {
+        let dimensions = self.dimensions;
+        let value = |point: usize, dimension: usize| values[point * dimensions + dimension];
+        let mut min = vec![u64::MAX; dimensions];
+        let mut max = vec![0; dimensions];
+        for &point in order.iter() {
+            for dimension in 0..dimensions {
+                min[dimension] = min[dimension].min(value(point, dimension));
+                max[dimension] = max[dimension].max(value(point, dimension));
+            }
+        }
+        let (split_dimension, spread) = (0..dimensions)
+            .map(|dimension| (dimension, max[dimension] - min[dimension]))
+            .max_by_key(|&(_, spread)| spread)
+            .unwrap();
+
+        let node = self.nodes.len();
+        self.nodes.push(KdNode {
+            start,
+            end: start + order.len(),
+            children: None,
+        });
+        self.bounds.extend_from_slice(&min);
+        self.bounds.extend_from_slice(&max);
+
+        if order.len() > max_points_in_leaf && spread > 0 {
+            let mid = order.len() / 2;
+            order.select_nth_unstable_by_key(mid, |&point| value(point, split_dimension));
+            let (left, right) = order.split_at_mut(mid);
+            let left = self.build_node(left, start, values, max_points_in_leaf);
+            let right = self.build_node(right, start + mid, values, max_points_in_leaf);
+            self.nodes[node].children = Some((left, right));
+        }
+        node
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    // Calls `visitor` with each point, in no particular order.
+    pub fn for_each<F: FnMut(DocId, &[u64])>(&self, mut visitor: F) {
+        for (point, &doc) in self.docs.iter().enumerate() {
+            visitor(
+                doc,
+                &self.values[point * self.dimensions..(point + 1) * self.dimensions],
+            );
+        }
+    }
+
+    // Calls `visitor` with the doc of each point within the range of every
+    // dimension. Docs with several matching points are visited as many times.
+    pub fn visit<F: FnMut(DocId)>(&self, ranges: &[RangeInclusive<u64>], mut visitor: F) {
+        assert_eq!(ranges.len(), self.dimensions, "Dimensions don't match");
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            let bounds = &self.bounds[node_index * 2 * self.dimensions..];
+            let (min, max) = bounds[..2 * self.dimensions].split_at(self.dimensions);
+            let mut is_inside = true;
+            let mut is_outside = false;
+            for (dimension, range) in ranges.iter().enumerate() {
+                if max[dimension] < *range.start() || min[dimension] > *range.end() {
+                    is_outside = true;
+                    break;
+                }
+                if min[dimension] < *range.start() || max[dimension] > *range.end() {
+                    is_inside = false;
+                }
+            }
+            if is_outside {
+                continue;
+            }
+            if is_inside {
+                self.docs[node.start..node.end]
+                    .iter()
+                    .for_each(|&doc| visitor(doc));
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+                None => {
+                    for point in node.start..node.end {
+                        let values =
+                            &self.values[point * self.dimensions..(point + 1) * self.dimensions];
+                        if ranges
+                            .iter()
+                            .zip(values)
+                            .all(|(range, value)| range.contains(value))
+                        {
+                            visitor(self.docs[point]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use crate::util::Random;
+
+    use super::KdTree;
+
+    fn visit_sorted(tree: &KdTree, ranges: &[RangeInclusive<u64>]) -> Vec<u32> {
+        let mut docs = vec![];
+        tree.visit(ranges, |doc| docs.push(doc));
+        docs.sort_unstable();
+        docs
+    }
+
+    #[test]
+    fn test_simple() {
+        let tree = KdTree::build(1, vec![], vec![]);
+        assert!(tree.is_empty());
+        assert!(visit_sorted(&tree, &[0..=u64::MAX]).is_empty());
+
+        let tree = KdTree::build(1, vec![0, 1, 1, 3], vec![10, 5, 20, 15]);
+        assert_eq!(tree.len(), 4);
+        let mut points = vec![];
+        tree.for_each(|doc, point| points.push((doc, point[0])));
+        points.sort_unstable();
+        assert_eq!(points, [(0, 10), (1, 5), (1, 20), (3, 15)]);
+        assert_eq!(visit_sorted(&tree, &[5..=15]), [0, 1, 3]);
+        assert_eq!(visit_sorted(&tree, &[16..=30]), [1]);
+        assert!(visit_sorted(&tree, &[21..=30]).is_empty());
+    }
+
+    #[test]
+    fn test_random() {
+        let dimensions = 2;
+        let len = 5000;
+        let docs: Vec<u32> = (0..len as u32).map(|point| point / 2).collect();
+        let mut random = Random::new(42);
+        let values: Vec<u64> = (0..len * dimensions).map(|_| random.below(1000)).collect();
+        let tree = KdTree::build_with_leaf_size(dimensions, docs.clone(), values.clone(), 16);
+
+        for ranges in [
+            [0..=u64::MAX, 0..=u64::MAX],
+            [100..=200, 0..=999],
+            [100..=200, 300..=310],
+            [500..=500, 0..=u64::MAX],
+            [1000..=2000, 0..=u64::MAX],
+        ] {
+            let mut expected: Vec<_> = (0..len)
+                .filter(|&point| {
+                    ranges
+                        .iter()
+                        .zip(&values[point * dimensions..(point + 1) * dimensions])
+                        .all(|(range, value)| range.contains(value))
+                })
+                .map(|point| docs[point])
+                .collect();
+            expected.sort_unstable();
+            assert_eq!(visit_sorted(&tree, &ranges), expected);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod kd_tree;
+mod point_writer;
+mod uninverted_points;
+
+pub use kd_tree::KdTree;
+pub use point_writer::PointWriter;
+pub use uninverted_points::UninvertedPoints;
@@ -0,0 +1,102 @@
+use std::ops::RangeInclusive;
+
+use crate::{util::ChunkedVector, DocId};
+
+const CHUNK_EXPONENT: usize = 10;
+const TREE_EXPONENT: usize = 4;
+
+// The points of a field in a realtime segment, appended in doc order and
+// scanned linearly by range queries.
+//
+// The values of a point are pushed before its doc, so that readers seeing the
+// doc also see the values.
+pub struct PointWriter {
+    dimensions: usize,
+    docs: ChunkedVector<DocId>,
+    values: ChunkedVector<u64>,
+}
+
+impl PointWriter {
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions,
+            docs: ChunkedVector::new(CHUNK_EXPONENT, TREE_EXPONENT),
+            values: ChunkedVector::new(CHUNK_EXPONENT, TREE_EXPONENT),
+        }
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    // Must only be called from the single writer thread, with docs in
+    // increasing order.
+    pub fn add_point(&self, doc: DocId, point: &[u64]) {
+        assert_eq!(point.len(), self.dimensions);
+        for &value in point {
+            self.values.push(value);
+        }
+        self.docs.push(doc);
+    }
+
+    // Calls `visitor` with each point of the docs below `max_doc`.
+    pub fn for_each<F: FnMut(DocId, &[u64])>(&self, max_doc: DocId, mut visitor: F) {
+        let mut point = vec![0; self.dimensions];
+        for index in 0..self.docs.len() {
+            let doc = *self.docs.get(index).unwrap();
+            if doc >= max_doc {
+                break;
+            }
+            for (dimension, value) in point.iter_mut().enumerate() {
+                *value = *self
+                    .values
+                    .get(index * self.dimensions + dimension)
+                    .unwrap();
+            }
+            visitor(doc, &point);
+        }
+    }
+
+    // Calls `visitor` with the doc of each point below `max_doc` within the
+    // range of every dimension. Docs with several matching points are visited
+    // as many times.
+    pub fn visit<F: FnMut(DocId)>(
+        &self,
+        max_doc: DocId,
+        ranges: &[RangeInclusive<u64>],
+        mut visitor: F,
+    ) {
+        assert_eq!(ranges.len(), self.dimensions, "Dimensions don't match");
+        self.for_each(max_doc, |doc, point| {
+            if ranges
+                .iter()
+                .zip(point)
+                .all(|(range, value)| range.contains(value))
+            {
+                visitor(doc);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PointWriter;
+
+    #[test]
+    fn test_simple() {
+        let point_writer = PointWriter::new(2);
+        point_writer.add_point(0, &[1, 10]);
+        point_writer.add_point(0, &[5, 50]);
+        point_writer.add_point(2, &[3, 30]);
+        point_writer.add_point(3, &[4, 40]);
+
+        let mut docs = vec![];
+        point_writer.visit(3, &[2..=5, 0..=40], |doc| docs.push(doc));
+        assert_eq!(docs, [2]);
+
+        let mut docs = vec![];
+        point_writer.visit(4, &[0..=u64::MAX, 10..=50], |doc| docs.push(doc));
+        assert_eq!(docs, [0, 0, 2, 3]);
+    }
+}
@@ -0,0 +1,45 @@
+use crate::DocId;
+
+// The smallest and greatest value of each doc in the points of a field indexed
+// in one dimension, to read them by doc when the field has no doc values.
+pub struct UninvertedPoints {
+    bounds: Vec<Option<(u64, u64)>>,
+}
+
+impl UninvertedPoints {
+    pub fn new(max_doc: DocId) -> Self {
+        Self {
+            bounds: vec![None; max_doc as usize],
+        }
+    }
+
+    pub fn add(&mut self, doc: DocId, value: u64) {
+        let bounds = &mut self.bounds[doc as usize];
+        *bounds = Some(match *bounds {
+            Some((min, max)) => (min.min(value), max.max(value)),
+            None => (value, value),
+        });
+    }
+
+    // The smallest and greatest values of the doc.
+    pub fn bounds(&self, doc: DocId) -> Option<(u64, u64)> {
+        self.bounds[doc as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UninvertedPoints;
+
+    #[test]
+    fn test_simple() {
+        let mut points = UninvertedPoints::new(3);
+        points.add(0, 5);
+        points.add(2, 7);
+        points.add(0, 3);
+        points.add(0, 4);
+        assert_eq!(points.bounds(0), Some((3, 5)));
+        assert_eq!(points.bounds(1), None);
+        assert_eq!(points.bounds(2), Some((7, 7)));
+    }
+}
@@ -0,0 +1,580 @@
+use std::{iter::Copied, slice::Iter};
+
+use crate::{
+    schema::IndexRecordOption,
+    util::{read_vint, write_vint},
+    DocId, TERMINATED,
+};
+
+use super::{Impact, PostingsIterator, TermInfo};
+
+pub const BLOCK_SIZE: usize = 128;
+
+// The posting list format of immutable segments.
+//
+// Docs are grouped into blocks of `BLOCK_SIZE`. A posting list starts with one
+// skip entry per block: the last doc of the block as a delta from the last doc
+// of the previous block, the byte length of the block, the byte length of its
+// positions, and the max freq and min field length of its docs, which bound
+// their scores. The blocks follow, each doc encoded the same way as in the
+// realtime segment. Positions and offsets live in a separate stream, and are
+// only decoded for the docs whose positions are read.
+pub struct BlockPostingsSerializer {
+    record_option: IndexRecordOption,
+    postings: Vec<u8>,
+    positions: Vec<u8>,
+}
+
+struct BlockBuffer {
+    skip_entries: Vec<u8>,
+    blocks: Vec<u8>,
+    block: Vec<u8>,
+    block_positions_len: usize,
+    block_len: usize,
+    block_max_freq: u32,
+    block_min_field_length: u32,
+    last_block_doc: DocId,
+    last_doc: DocId,
+}
+
+impl Default for BlockBuffer {
+    fn default() -> Self {
+        Self {
+            skip_entries: Vec::new(),
+            blocks: Vec::new(),
+            block: Vec::new(),
+            block_positions_len: 0,
+            block_len: 0,
+            block_max_freq: 0,
+            block_min_field_length: u32::MAX,
+            last_block_doc: 0,
+            last_doc: 0,
+        }
+    }
+}
+
+impl BlockPostingsSerializer {
+    pub fn new(record_option: IndexRecordOption) -> Self {
+        Self {
+            record_option,
+            postings: Vec::new(),
+            positions: Vec::new(),
+        }
+    }
+
+    pub fn record_option(&self) -> IndexRecordOption {
+        self.record_option
+    }
+
+    // Serializes all the remaining docs of `postings`, given the length of the
+    // field in each doc as it is scored.
+    pub fn serialize<P: PostingsIterator + ?Sized, F: Fn(DocId) -> u32>(
+        &mut self,
+        postings: &mut P,
+        field_length: F,
+    ) -> TermInfo {
+        let postings_offset = self.postings.len();
+        let positions_offset = self.positions.len();
+        let mut doc_freq = 0;
+        let mut buffer = BlockBuffer::default();
+
+        while postings.doc() != TERMINATED {
+            let doc = postings.doc();
+            let freq = postings.freq() as u64;
+            let delta = (doc - buffer.last_doc) as u64;
+            if freq == 1 {
+                write_vint(delta << 1 | 1, &mut buffer.block);
+            } else {
+                write_vint(delta << 1, &mut buffer.block);
+                write_vint(freq, &mut buffer.block);
+            }
+            buffer.last_doc = doc;
+            buffer.block_max_freq = buffer.block_max_freq.max(freq as u32);
+            buffer.block_min_field_length = buffer.block_min_field_length.min(field_length(doc));
+
+            if self.record_option.has_positions() {
+                let len = self.positions.len();
+                let mut last_position = 0;
+                for i in 0..freq as usize {
+                    let position = postings.positions()[i];
+                    write_vint((position - last_position) as u64, &mut self.positions);
+                    last_position = position;
+                    if self.record_option.has_offsets() {
+                        let (start, end) = postings.offsets()[i];
+                        write_vint(start as u64, &mut self.positions);
+                        write_vint((end - start) as u64, &mut self.positions);
+                    }
+                }
+                buffer.block_positions_len += self.positions.len() - len;
+            }
+
+            doc_freq += 1;
+            buffer.block_len += 1;
+            if buffer.block_len == BLOCK_SIZE {
+                self.flush_block(&mut buffer);
+            }
+            postings.next();
+        }
+        if buffer.block_len > 0 {
+            self.flush_block(&mut buffer);
+        }
+
+        self.postings.extend_from_slice(&buffer.skip_entries);
+        self.postings.extend_from_slice(&buffer.blocks);
+
+        TermInfo {
+            doc_freq,
+            postings_offset,
+            positions_offset,
+        }
+    }
+
+    pub fn finish(self) -> (Vec<u8>, Vec<u8>) {
+        (self.postings, self.positions)
+    }
+
+    fn flush_block(&self, buffer: &mut BlockBuffer) {
+        write_vint(
+            (buffer.last_doc - buffer.last_block_doc) as u64,
+            &mut buffer.skip_entries,
+        );
+        write_vint(buffer.block.len() as u64, &mut buffer.skip_entries);
+        if self.record_option.has_positions() {
+            write_vint(buffer.block_positions_len as u64, &mut buffer.skip_entries);
+        }
+        write_vint(buffer.block_max_freq as u64, &mut buffer.skip_entries);
+        write_vint(
+            buffer.block_min_field_length as u64,
+            &mut buffer.skip_entries,
+        );
+        buffer.blocks.append(&mut buffer.block);
+        buffer.block_positions_len = 0;
+        buffer.block_len = 0;
+        buffer.block_max_freq = 0;
+        buffer.block_min_field_length = u32::MAX;
+        buffer.last_block_doc = buffer.last_doc;
+    }
+}
+
+struct SkipEntry {
+    last_doc: DocId,
+    postings_offset: usize,
+    positions_offset: usize,
+    max_impact: Impact,
+}
+
+pub struct BlockPostingsIterator<'a> {
+    record_option: IndexRecordOption,
+    postings: &'a [u8],
+    positions: &'a [u8],
+    skip_entries: Vec<SkipEntry>,
+    max_impact: Impact,
+    block: usize,
+    block_docs: Vec<DocId>,
+    block_freqs: Vec<u32>,
+    index_in_block: usize,
+    positions_reader: Copied<Iter<'a, u8>>,
+    // The number of positions of the previous docs of the block which were
+    // never decoded, to skip before decoding those of the current doc.
+    pending_positions: u32,
+    positions_loaded: bool,
+    doc: DocId,
+    doc_positions: Vec<u32>,
+    doc_offsets: Vec<(u32, u32)>,
+}
+
+impl<'a> BlockPostingsIterator<'a> {
+    pub fn new(
+        record_option: IndexRecordOption,
+        postings: &'a [u8],
+        positions: &'a [u8],
+        term_info: &TermInfo,
+    ) -> Self {
+        let num_blocks = (term_info.doc_freq as usize).div_ceil(BLOCK_SIZE);
+        let mut reader = postings[term_info.postings_offset..].iter().copied();
+        let mut skip_entries = Vec::with_capacity(num_blocks);
+        let mut last_doc = 0;
+        let mut block_lens = Vec::with_capacity(num_blocks);
+        let mut positions_offset = term_info.positions_offset;
+        for _ in 0..num_blocks {
+            last_doc += read_vint(&mut reader).unwrap() as DocId;
+            block_lens.push(read_vint(&mut reader).unwrap() as usize);
+            let block_positions_offset = positions_offset;
+            if record_option.has_positions() {
+                positions_offset += read_vint(&mut reader).unwrap() as usize;
+            }
+            let max_impact = Impact {
+                freq: read_vint(&mut reader).unwrap() as u32,
+                field_length: read_vint(&mut reader).unwrap() as u32,
+            };
+            skip_entries.push(SkipEntry {
+                last_doc,
+                postings_offset: 0,
+                positions_offset: block_positions_offset,
+                max_impact,
+            });
+        }
+        let max_impact = Impact {
+            freq: skip_entries
+                .iter()
+                .map(|entry| entry.max_impact.freq)
+                .max()
+                .unwrap_or(0),
+            field_length: skip_entries
+                .iter()
+                .map(|entry| entry.max_impact.field_length)
+                .min()
+                .unwrap_or(0),
+        };
+        let mut postings_offset = postings.len() - reader.len();
+        for (skip_entry, block_len) in skip_entries.iter_mut().zip(block_lens) {
+            skip_entry.postings_offset = postings_offset;
+            postings_offset += block_len;
+        }
+
+        let mut iterator = Self {
+            record_option,
+            postings,
+            positions,
+            skip_entries,
+            max_impact,
+            block: 0,
+            block_docs: Vec::with_capacity(BLOCK_SIZE),
+            block_freqs: Vec::with_capacity(BLOCK_SIZE),
+            index_in_block: 0,
+            positions_reader: positions[..0].iter().copied(),
+            pending_positions: 0,
+            positions_loaded: false,
+            doc: TERMINATED,
+            doc_positions: Vec::new(),
+            doc_offsets: Vec::new(),
+        };
+        iterator.load_block(0);
+        iterator
+    }
+
+    fn load_block(&mut self, block: usize) {
+        self.block = block;
+        self.block_docs.clear();
+        self.block_freqs.clear();
+        self.index_in_block = 0;
+        self.pending_positions = 0;
+        if block >= self.skip_entries.len() {
+            self.doc = TERMINATED;
+            self.positions_loaded = false;
+            return;
+        }
+
+        let skip_entry = &self.skip_entries[block];
+        let mut reader = self.postings[skip_entry.postings_offset..].iter().copied();
+        let mut doc = if block > 0 {
+            self.skip_entries[block - 1].last_doc
+        } else {
+            0
+        };
+        loop {
+            let code = read_vint(&mut reader).unwrap();
+            doc += (code >> 1) as DocId;
+            let freq = if code & 1 == 1 {
+                1
+            } else {
+                read_vint(&mut reader).unwrap() as u32
+            };
+            self.block_docs.push(doc);
+            self.block_freqs.push(freq);
+            if doc == skip_entry.last_doc {
+                break;
+            }
+        }
+        self.positions_reader = self.positions[skip_entry.positions_offset..]
+            .iter()
+            .copied();
+        self.load_doc();
+    }
+
+    fn load_doc(&mut self) {
+        self.doc = self.block_docs[self.index_in_block];
+        self.positions_loaded = false;
+    }
+
+    // Decodes the positions of the current doc the first time they are read.
+    fn load_positions(&mut self) {
+        if self.positions_loaded {
+            return;
+        }
+        self.positions_loaded = true;
+        self.doc_positions.clear();
+        self.doc_offsets.clear();
+        if self.doc != TERMINATED && self.record_option.has_positions() {
+            let vints_per_position = if self.record_option.has_offsets() {
+                3
+            } else {
+                1
+            };
+            for _ in 0..self.pending_positions * vints_per_position {
+                read_vint(&mut self.positions_reader).unwrap();
+            }
+            self.pending_positions = 0;
+            let mut position = 0;
+            for _ in 0..self.block_freqs[self.index_in_block] {
+                position += read_vint(&mut self.positions_reader).unwrap() as u32;
+                self.doc_positions.push(position);
+                if self.record_option.has_offsets() {
+                    let start = read_vint(&mut self.positions_reader).unwrap() as u32;
+                    let len = read_vint(&mut self.positions_reader).unwrap() as u32;
+                    self.doc_offsets.push((start, start + len));
+                }
+            }
+        }
+    }
+}
+
+impl<'a> PostingsIterator for BlockPostingsIterator<'a> {
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn next(&mut self) -> DocId {
+        if self.doc == TERMINATED {
+            return TERMINATED;
+        }
+        if !self.positions_loaded {
+            self.pending_positions += self.block_freqs[self.index_in_block];
+        }
+        self.index_in_block += 1;
+        if self.index_in_block < self.block_docs.len() {
+            self.load_doc();
+        } else {
+            self.load_block(self.block + 1);
+        }
+        self.doc
+    }
+
+    // Skips the blocks ending before the target without decoding them.
+    fn advance(&mut self, target: DocId) -> DocId {
+        if self.doc >= target {
+            return self.doc;
+        }
+        let block = self.block
+            + self.skip_entries[self.block..].partition_point(|entry| entry.last_doc < target);
+        if block != self.block {
+            self.load_block(block);
+        }
+        while self.doc < target {
+            self.next();
+        }
+        self.doc
+    }
+
+    fn freq(&self) -> u32 {
+        if self.doc == TERMINATED {
+            0
+        } else {
+            self.block_freqs[self.index_in_block]
+        }
+    }
+
+    fn positions(&mut self) -> &[u32] {
+        self.load_positions();
+        &self.doc_positions
+    }
+
+    fn offsets(&mut self) -> &[(u32, u32)] {
+        self.load_positions();
+        &self.doc_offsets
+    }
+
+    fn max_impact(&self) -> Impact {
+        self.max_impact
+    }
+
+    fn block_max_impact(&self, target: DocId) -> (DocId, Impact) {
+        let block = self.block
+            + self.skip_entries[self.block..].partition_point(|entry| entry.last_doc < target);
+        match self.skip_entries.get(block) {
+            Some(entry) => (entry.last_doc, entry.max_impact),
+            None => (
+                TERMINATED,
+                Impact {
+                    freq: 0,
+                    field_length: u32::MAX,
+                },
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        postings::{Impact, PostingWriter, PostingsIterator},
+        schema::IndexRecordOption,
+        DocId, TERMINATED,
+    };
+
+    use super::{BlockPostingsIterator, BlockPostingsSerializer, BLOCK_SIZE};
+
+    fn positions(doc: DocId) -> Vec<u32> {
+        (0..doc % 3 + 1).map(|i| doc + i * 7).collect()
+    }
+
+    fn offsets(doc: DocId) -> Vec<(u32, u32)> {
+        positions(doc).iter().map(|&p| (p * 5, p * 5 + 4)).collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        for record_option in [
+            IndexRecordOption::Basic,
+            IndexRecordOption::WithPositions,
+            IndexRecordOption::WithPositionsAndOffsets,
+        ] {
+            let term_docs: Vec<Vec<DocId>> = vec![
+                vec![0],
+                vec![3, 5, 8],
+                (0..BLOCK_SIZE as DocId).map(|i| i * 3 + 1).collect(),
+                (0..1000).map(|i| i * 2).collect(),
+            ];
+
+            let mut serializer = BlockPostingsSerializer::new(record_option);
+            let mut term_infos = vec![];
+            for docs in &term_docs {
+                let writer = PostingWriter::new(record_option);
+                for &doc in docs {
+                    writer.add_doc(doc, &positions(doc), &offsets(doc));
+                }
+                let term_info = serializer.serialize(&mut writer.postings(TERMINATED), |_| 1);
+                assert_eq!(term_info.doc_freq as usize, docs.len());
+                term_infos.push(term_info);
+            }
+            let (postings, positions_bytes) = serializer.finish();
+
+            for (docs, term_info) in term_docs.iter().zip(term_infos.iter()) {
+                let mut iterator = BlockPostingsIterator::new(
+                    record_option,
+                    &postings,
+                    &positions_bytes,
+                    term_info,
+                );
+                for &doc in docs {
+                    assert_eq!(iterator.doc(), doc);
+                    assert_eq!(iterator.freq(), doc % 3 + 1);
+                    if record_option.has_positions() {
+                        assert_eq!(iterator.positions(), positions(doc));
+                    } else {
+                        assert!(iterator.positions().is_empty());
+                    }
+                    if record_option.has_offsets() {
+                        assert_eq!(iterator.offsets(), offsets(doc));
+                    } else {
+                        assert!(iterator.offsets().is_empty());
+                    }
+                    iterator.next();
+                }
+                assert_eq!(iterator.doc(), TERMINATED);
+                assert_eq!(iterator.next(), TERMINATED);
+            }
+        }
+    }
+
+    #[test]
+    fn test_advance() {
+        let record_option = IndexRecordOption::WithPositionsAndOffsets;
+        let docs: Vec<DocId> = (0..1000).map(|i| i * 3).collect();
+        let writer = PostingWriter::new(record_option);
+        for &doc in &docs {
+            writer.add_doc(doc, &positions(doc), &offsets(doc));
+        }
+        let mut serializer = BlockPostingsSerializer::new(record_option);
+        let term_info = serializer.serialize(&mut writer.postings(TERMINATED), |_| 1);
+        let (postings, positions_bytes) = serializer.finish();
+
+        let mut iterator =
+            BlockPostingsIterator::new(record_option, &postings, &positions_bytes, &term_info);
+        assert_eq!(iterator.advance(0), 0);
+        assert_eq!(iterator.advance(4), 6);
+        assert_eq!(iterator.advance(6), 6);
+        assert_eq!(iterator.advance(1000), 1002);
+        assert_eq!(iterator.positions(), positions(1002));
+        assert_eq!(iterator.offsets(), offsets(1002));
+        assert_eq!(iterator.next(), 1005);
+        assert_eq!(iterator.advance(2997), 2997);
+        assert_eq!(iterator.positions(), positions(2997));
+        assert_eq!(iterator.advance(2998), TERMINATED);
+        assert_eq!(iterator.advance(3000), TERMINATED);
+
+        let mut realtime = writer.postings(TERMINATED);
+        assert_eq!(realtime.advance(1000), 1002);
+        assert_eq!(realtime.positions(), positions(1002));
+        assert_eq!(realtime.advance(3000), TERMINATED);
+    }
+
+    #[test]
+    fn test_skipped_positions() {
+        for record_option in [
+            IndexRecordOption::WithPositions,
+            IndexRecordOption::WithPositionsAndOffsets,
+        ] {
+            let docs: Vec<DocId> = (0..500).collect();
+            let writer = PostingWriter::new(record_option);
+            for &doc in &docs {
+                writer.add_doc(doc, &positions(doc), &offsets(doc));
+            }
+            let mut serializer = BlockPostingsSerializer::new(record_option);
+            let term_info = serializer.serialize(&mut writer.postings(TERMINATED), |_| 1);
+            let (postings, positions_bytes) = serializer.finish();
+
+            // Only some docs have their positions read, offsets first.
+            let iterators: [Box<dyn PostingsIterator>; 2] = [
+                Box::new(BlockPostingsIterator::new(
+                    record_option,
+                    &postings,
+                    &positions_bytes,
+                    &term_info,
+                )),
+                Box::new(writer.postings(TERMINATED)),
+            ];
+            for mut iterator in iterators {
+                for &doc in &docs {
+                    assert_eq!(iterator.doc(), doc);
+                    if doc % 7 == 0 {
+                        if record_option.has_offsets() {
+                            assert_eq!(iterator.offsets(), offsets(doc));
+                        }
+                        assert_eq!(iterator.positions(), positions(doc));
+                    }
+                    iterator.next();
+                }
+                assert_eq!(iterator.doc(), TERMINATED);
+            }
+        }
+    }
+
+    #[test]
+    fn test_impacts() {
+        let record_option = IndexRecordOption::Basic;
+        let writer = PostingWriter::new(record_option);
+        let docs: Vec<DocId> = (0..300).collect();
+        for &doc in &docs {
+            let freq = if doc == 200 { 9 } else { doc % 3 + 1 };
+            writer.add_doc(doc, &vec![0; freq as usize], &[]);
+        }
+        let mut realtime = writer.postings(TERMINATED);
+        assert_eq!(realtime.max_impact().freq, 9);
+        assert_eq!(realtime.block_max_impact(100).0, TERMINATED);
+
+        let mut serializer = BlockPostingsSerializer::new(record_option);
+        let term_info = serializer.serialize(&mut realtime, |doc| 1000 - doc);
+        let (postings, positions_bytes) = serializer.finish();
+
+        let mut iterator =
+            BlockPostingsIterator::new(record_option, &postings, &positions_bytes, &term_info);
+        let max_impact = |freq, field_length| Impact { freq, field_length };
+        assert_eq!(iterator.max_impact(), max_impact(9, 701));
+        assert_eq!(iterator.block_max_impact(0), (127, max_impact(3, 873)));
+        assert_eq!(iterator.block_max_impact(130), (255, max_impact(9, 745)));
+        iterator.advance(256);
+        assert_eq!(iterator.block_max_impact(256), (299, max_impact(3, 701)));
+        assert_eq!(iterator.block_max_impact(300).0, TERMINATED);
+    }
+}
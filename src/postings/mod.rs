@@ -0,0 +1,14 @@
+mod block_postings;
+mod posting_writer;
+mod postings_iterator;
+mod term_dictionary;
+mod union_postings_iterator;
+
+pub use block_postings::{BlockPostingsIterator, BlockPostingsSerializer, BLOCK_SIZE};
+pub use posting_writer::{PostingWriter, RealtimePostingsIterator};
+pub use postings_iterator::{Impact, PostingsIterator};
+
+#[cfg(test)]
+pub(crate) use postings_iterator::collect_postings;
+pub use term_dictionary::{TermDictionary, TermDictionaryBuilder, TermInfo};
+pub use union_postings_iterator::UnionPostingsIterator;
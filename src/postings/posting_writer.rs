@@ -0,0 +1,435 @@
+use std::sync::OnceLock;
+
+use crate::{
+    schema::IndexRecordOption,
+    util::{
+        read_vint, write_vint, AcqRelUsize, ByteSliceList, ByteSliceReader, ChunkedVector,
+        ExponentialCapacityPolicy, RelaxedUsize,
+    },
+    DocId, TERMINATED,
+};
+
+use super::{Impact, PostingsIterator};
+
+const INITIAL_SLICE_CAPACITY: usize = 16;
+const CHECKPOINT_INTERVAL: usize = 128;
+const CHECKPOINTS_CHUNK_EXPONENT: usize = 4;
+const CHECKPOINTS_TREE_EXPONENT: usize = 4;
+
+// Where a doc starts, recorded every `CHECKPOINT_INTERVAL` docs.
+struct Checkpoint {
+    doc: DocId,
+    prev_doc: DocId,
+    // The number of docs before it.
+    doc_freq: usize,
+    offset: usize,
+}
+
+// Postings are appended as variable-length bytes. Each doc is encoded as the
+// delta from the previous doc, shifted left by one with the low bit set when
+// the freq is one, otherwise followed by the freq. With positions recorded,
+// the position deltas of the doc come next, each followed by its start offset
+// and length when offsets are recorded too.
+//
+// The end offset is published once a doc is fully written, so readers can
+// decode concurrently up to it. Checkpoints let readers count the docs below
+// their watermark while decoding at most `CHECKPOINT_INTERVAL` of them, and
+// are only allocated for postings that long.
+pub struct PostingWriter {
+    record_option: IndexRecordOption,
+    bytes: ByteSliceList,
+    last_doc: RelaxedUsize,
+    max_freq: RelaxedUsize,
+    doc_freq: AcqRelUsize,
+    end: AcqRelUsize,
+    checkpoints: OnceLock<ChunkedVector<Checkpoint>>,
+}
+
+impl PostingWriter {
+    pub fn new(record_option: IndexRecordOption) -> Self {
+        Self {
+            record_option,
+            bytes: ByteSliceList::with_initial_capacity(
+                INITIAL_SLICE_CAPACITY,
+                ExponentialCapacityPolicy,
+            ),
+            last_doc: RelaxedUsize::new(0),
+            max_freq: RelaxedUsize::new(0),
+            doc_freq: AcqRelUsize::new(0),
+            end: AcqRelUsize::new(0),
+            checkpoints: OnceLock::new(),
+        }
+    }
+
+    pub fn record_option(&self) -> IndexRecordOption {
+        self.record_option
+    }
+
+    // Must only be called from the single writer thread, with increasing docs
+    // and, for each doc, non-decreasing positions. The freq is the number of
+    // positions, and offsets are expected to be given for each position when
+    // they are recorded. An offset ending before it starts is recorded empty.
+    pub fn add_doc(&self, doc: DocId, positions: &[u32], offsets: &[(u32, u32)]) {
+        let freq = positions.len() as u64;
+        let doc_freq = self.doc_freq();
+        if doc_freq > 0 && doc_freq.is_multiple_of(CHECKPOINT_INTERVAL) {
+            let checkpoints = self.checkpoints.get_or_init(|| {
+                ChunkedVector::new(CHECKPOINTS_CHUNK_EXPONENT, CHECKPOINTS_TREE_EXPONENT)
+            });
+            checkpoints.push(Checkpoint {
+                doc,
+                prev_doc: self.last_doc.load() as DocId,
+                doc_freq,
+                offset: self.bytes.len(),
+            });
+        }
+        let delta = (doc as usize - self.last_doc.load()) as u64;
+        let mut buf = Vec::new();
+        if freq == 1 {
+            write_vint(delta << 1 | 1, &mut buf);
+        } else {
+            write_vint(delta << 1, &mut buf);
+            write_vint(freq, &mut buf);
+        }
+        if self.record_option.has_positions() {
+            let mut last_position = 0;
+            for (i, &position) in positions.iter().enumerate() {
+                debug_assert!(position >= last_position, "Positions must be sorted");
+                write_vint((position - last_position) as u64, &mut buf);
+                last_position = position;
+                if self.record_option.has_offsets() {
+                    let (start, end) = offsets[i];
+                    write_vint(start as u64, &mut buf);
+                    write_vint(end.saturating_sub(start) as u64, &mut buf);
+                }
+            }
+        }
+        self.bytes.extend_from_slice(&buf);
+        self.last_doc.store(doc as usize);
+        self.max_freq.store(self.max_freq.load().max(freq as usize));
+        self.doc_freq.store(doc_freq + 1);
+        self.end.store(self.bytes.len());
+    }
+
+    pub fn doc_freq(&self) -> usize {
+        self.doc_freq.load()
+    }
+
+    // The number of docs below `max_doc`, which must be a watermark below
+    // which docs are fully written.
+    pub fn doc_freq_below(&self, max_doc: DocId) -> usize {
+        let doc_freq = self.doc_freq();
+        // The docs counted include the last one, and docs below the watermark
+        // were counted before it was published.
+        if doc_freq == 0 || (self.last_doc.load() as DocId) < max_doc {
+            return doc_freq;
+        }
+        // Counts from the last checkpoint below the watermark.
+        let checkpoint = self.checkpoints.get().and_then(|checkpoints| {
+            let (mut low, mut high) = (0, checkpoints.len());
+            while low < high {
+                let mid = (low + high) / 2;
+                if checkpoints.get(mid).unwrap().doc < max_doc {
+                    low = mid + 1;
+                } else {
+                    high = mid;
+                }
+            }
+            low.checked_sub(1).map(|i| checkpoints.get(i).unwrap())
+        });
+        let (mut doc_freq, mut postings) = match checkpoint {
+            Some(checkpoint) => (
+                checkpoint.doc_freq,
+                self.postings_from(checkpoint.offset, checkpoint.prev_doc, max_doc),
+            ),
+            None => (0, self.postings(max_doc)),
+        };
+        while postings.doc() != TERMINATED {
+            doc_freq += 1;
+            postings.next();
+        }
+        doc_freq
+    }
+
+    // Docs at or beyond `max_doc` may still be in flight and are never returned.
+    pub fn postings(&self, max_doc: DocId) -> RealtimePostingsIterator<'_> {
+        self.postings_from(0, 0, max_doc)
+    }
+
+    // The postings from the doc starting at `offset`, following `prev_doc`.
+    fn postings_from(
+        &self,
+        offset: usize,
+        prev_doc: DocId,
+        max_doc: DocId,
+    ) -> RealtimePostingsIterator<'_> {
+        let mut postings = RealtimePostingsIterator {
+            record_option: self.record_option,
+            reader: self.bytes.reader_from(offset, self.end.load()),
+            max_doc,
+            // The max freq may include docs still in flight, which only
+            // loosens the bound.
+            max_freq: self.max_freq.load() as u32,
+            doc: 0,
+            freq: 0,
+            positions_pending: false,
+            positions: Vec::new(),
+            offsets: Vec::new(),
+        };
+        postings.decode_next(prev_doc);
+        postings
+    }
+}
+
+pub struct RealtimePostingsIterator<'a> {
+    record_option: IndexRecordOption,
+    reader: ByteSliceReader<'a>,
+    max_doc: DocId,
+    max_freq: u32,
+    doc: DocId,
+    freq: u32,
+    // Whether the reader is on the positions of the current doc, which are
+    // only decoded when read and skipped otherwise.
+    positions_pending: bool,
+    positions: Vec<u32>,
+    offsets: Vec<(u32, u32)>,
+}
+
+impl<'a> RealtimePostingsIterator<'a> {
+    fn decode_next(&mut self, last_doc: DocId) {
+        if self.positions_pending {
+            let vints_per_position = if self.record_option.has_offsets() {
+                3
+            } else {
+                1
+            };
+            for _ in 0..self.freq * vints_per_position {
+                read_vint(&mut self.reader).unwrap();
+            }
+        }
+        self.positions.clear();
+        self.offsets.clear();
+        self.positions_pending = false;
+        let code = match read_vint(&mut self.reader) {
+            Some(code) => code,
+            None => {
+                self.doc = TERMINATED;
+                return;
+            }
+        };
+        self.doc = last_doc + (code >> 1) as DocId;
+        if self.doc >= self.max_doc {
+            self.doc = TERMINATED;
+            return;
+        }
+        self.freq = if code & 1 == 1 {
+            1
+        } else {
+            read_vint(&mut self.reader).unwrap() as u32
+        };
+        self.positions_pending = self.record_option.has_positions();
+    }
+
+    // Decodes the positions of the current doc the first time they are read.
+    fn load_positions(&mut self) {
+        if self.positions_pending {
+            self.positions_pending = false;
+            let mut position = 0;
+            for _ in 0..self.freq {
+                position += read_vint(&mut self.reader).unwrap() as u32;
+                self.positions.push(position);
+                if self.record_option.has_offsets() {
+                    let start = read_vint(&mut self.reader).unwrap() as u32;
+                    let len = read_vint(&mut self.reader).unwrap() as u32;
+                    self.offsets.push((start, start + len));
+                }
+            }
+        }
+    }
+}
+
+impl<'a> PostingsIterator for RealtimePostingsIterator<'a> {
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn next(&mut self) -> DocId {
+        if self.doc != TERMINATED {
+            self.decode_next(self.doc);
+        }
+        self.doc
+    }
+
+    fn freq(&self) -> u32 {
+        self.freq
+    }
+
+    fn positions(&mut self) -> &[u32] {
+        self.load_positions();
+        &self.positions
+    }
+
+    fn offsets(&mut self) -> &[(u32, u32)] {
+        self.load_positions();
+        &self.offsets
+    }
+
+    // Field lengths are not known here, so they are only bounded by zero.
+    fn max_impact(&self) -> Impact {
+        Impact {
+            freq: self.max_freq,
+            field_length: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use crate::{postings::PostingsIterator, schema::IndexRecordOption, DocId, TERMINATED};
+
+    use super::PostingWriter;
+
+    #[test]
+    fn test_simple() {
+        let writer = PostingWriter::new(IndexRecordOption::Basic);
+        assert_eq!(writer.doc_freq(), 0);
+        assert_eq!(writer.postings(TERMINATED).doc(), TERMINATED);
+
+        let count = 100;
+        for i in 0..count {
+            let positions: Vec<_> = (0..i + 1).collect();
+            writer.add_doc(i * 2, &positions, &[]);
+        }
+        assert_eq!(writer.doc_freq(), count as usize);
+
+        let mut postings = writer.postings(TERMINATED);
+        for i in 0..count {
+            assert_eq!(postings.doc(), i * 2);
+            assert_eq!(postings.freq(), i + 1);
+            assert!(postings.positions().is_empty());
+            assert!(postings.offsets().is_empty());
+            postings.next();
+        }
+        assert_eq!(postings.doc(), TERMINATED);
+        assert_eq!(postings.next(), TERMINATED);
+
+        let mut postings = writer.postings(10);
+        let mut docs = vec![];
+        while postings.doc() != TERMINATED {
+            docs.push(postings.doc());
+            postings.next();
+        }
+        assert_eq!(docs, vec![0, 2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn test_positions_and_offsets() {
+        let writer = PostingWriter::new(IndexRecordOption::WithPositions);
+        writer.add_doc(0, &[3], &[(10, 15)]);
+        writer.add_doc(5, &[0, 2, 200], &[(0, 1), (4, 6), (900, 901)]);
+
+        let mut postings = writer.postings(TERMINATED);
+        assert_eq!(postings.doc(), 0);
+        assert_eq!(postings.positions(), &[3]);
+        assert!(postings.offsets().is_empty());
+        assert_eq!(postings.next(), 5);
+        assert_eq!(postings.freq(), 3);
+        assert_eq!(postings.positions(), &[0, 2, 200]);
+        assert_eq!(postings.next(), TERMINATED);
+
+        let writer = PostingWriter::new(IndexRecordOption::WithPositionsAndOffsets);
+        writer.add_doc(0, &[3], &[(10, 15)]);
+        writer.add_doc(5, &[0, 2, 200], &[(0, 1), (4, 6), (900, 901)]);
+        writer.add_doc(1000, &[100000, 100001], &[(7, 9), (3, 4)]);
+
+        let mut postings = writer.postings(TERMINATED);
+        assert_eq!(postings.doc(), 0);
+        assert_eq!(postings.positions(), &[3]);
+        assert_eq!(postings.offsets(), &[(10, 15)]);
+        assert_eq!(postings.next(), 5);
+        assert_eq!(postings.positions(), &[0, 2, 200]);
+        assert_eq!(postings.offsets(), &[(0, 1), (4, 6), (900, 901)]);
+        assert_eq!(postings.next(), 1000);
+        assert_eq!(postings.positions(), &[100000, 100001]);
+        assert_eq!(postings.offsets(), &[(7, 9), (3, 4)]);
+        assert_eq!(postings.next(), TERMINATED);
+        assert!(postings.positions().is_empty());
+
+        let mut postings = writer.postings(1000);
+        assert_eq!(postings.doc(), 0);
+        assert_eq!(postings.next(), 5);
+        assert_eq!(postings.next(), TERMINATED);
+        assert_eq!(postings.next(), TERMINATED);
+    }
+
+    #[test]
+    fn test_reversed_offsets() {
+        let writer = PostingWriter::new(IndexRecordOption::WithPositionsAndOffsets);
+        writer.add_doc(0, &[0, 1], &[(5, 3), (6, 8)]);
+        let mut postings = writer.postings(TERMINATED);
+        assert_eq!(postings.offsets(), &[(5, 5), (6, 8)]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Positions must be sorted")]
+    fn test_unsorted_positions() {
+        let writer = PostingWriter::new(IndexRecordOption::WithPositions);
+        writer.add_doc(0, &[2, 1], &[]);
+    }
+
+    #[test]
+    fn test_doc_freq_below() {
+        let writer = PostingWriter::new(IndexRecordOption::WithPositionsAndOffsets);
+        assert_eq!(writer.doc_freq_below(10), 0);
+        // Past a few checkpoints, with docs spread over many byte slices.
+        let count = 1000;
+        for i in 0..count {
+            let positions: Vec<_> = (0..i % 5 + 1).collect();
+            let offsets: Vec<_> = positions.iter().map(|&p| (p, p + 1)).collect();
+            writer.add_doc(i * 3, &positions, &offsets);
+        }
+        for max_doc in (0..count * 3 + 2).chain([TERMINATED]) {
+            let mut postings = writer.postings(max_doc);
+            let mut doc_freq = 0;
+            while postings.doc() != TERMINATED {
+                doc_freq += 1;
+                postings.next();
+            }
+            assert_eq!(writer.doc_freq_below(max_doc), doc_freq, "{}", max_doc);
+        }
+    }
+
+    #[test]
+    fn test_multithreads() {
+        let writer = PostingWriter::new(IndexRecordOption::WithPositions);
+        let count = 1024;
+        thread::scope(|scope| {
+            let t = scope.spawn(|| loop {
+                let mut len = 0;
+                let mut postings = writer.postings(TERMINATED);
+                while postings.doc() != TERMINATED {
+                    assert_eq!(postings.doc(), len * 2);
+                    assert_eq!(postings.freq(), len % 4 + 1);
+                    let positions: Vec<_> = (0..postings.freq()).map(|j| len + j).collect();
+                    assert_eq!(postings.positions(), positions);
+                    len += 1;
+                    postings.next();
+                }
+                if len == count {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(1));
+            });
+
+            for i in 0..count {
+                let positions: Vec<_> = (0..i % 4 + 1).map(|j| i + j).collect();
+                writer.add_doc(i * 2 as DocId, &positions, &[]);
+            }
+
+            t.join().unwrap();
+        });
+    }
+}
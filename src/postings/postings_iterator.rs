@@ -0,0 +1,72 @@
+use crate::{DocId, TERMINATED};
+
+// The highest freq and the lowest field length among a set of docs, which
+// bound the scores of these docs since scores grow with the freq and shrink
+// with the field length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Impact {
+    pub freq: u32,
+    pub field_length: u32,
+}
+
+impl Impact {
+    // Bounds any doc.
+    pub const MAX: Impact = Impact {
+        freq: u32::MAX,
+        field_length: 0,
+    };
+}
+
+// Iterates the docs of a posting list in increasing order.
+//
+// An iterator is positioned on its first doc as soon as it is created, and
+// returns `TERMINATED` once exhausted.
+pub trait PostingsIterator {
+    fn doc(&self) -> DocId;
+
+    fn next(&mut self) -> DocId;
+
+    // Moves to the first doc at or after `target`, staying on the current doc
+    // if it is not before the target.
+    fn advance(&mut self, target: DocId) -> DocId {
+        while self.doc() < target {
+            self.next();
+        }
+        self.doc()
+    }
+
+    fn freq(&self) -> u32;
+
+    // The positions of the current doc, empty if they were not recorded. They
+    // are only decoded for the docs they are asked for.
+    fn positions(&mut self) -> &[u32];
+
+    // The start and end byte offsets of each position of the current doc,
+    // empty if they were not recorded.
+    fn offsets(&mut self) -> &[(u32, u32)];
+
+    // Bounds the impacts of all the docs.
+    fn max_impact(&self) -> Impact {
+        Impact::MAX
+    }
+
+    // The last doc of the block containing `target`, which must not be before
+    // the current doc, and the bound of the impacts of the docs from the
+    // current one to it, without decoding the block. Without blocks, all the
+    // docs are in one.
+    fn block_max_impact(&self, _target: DocId) -> (DocId, Impact) {
+        (TERMINATED, self.max_impact())
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn collect_postings<P: PostingsIterator + ?Sized>(
+    postings: &mut P,
+) -> Vec<(DocId, u32)> {
+    let mut docs = vec![];
+    while postings.doc() != crate::TERMINATED {
+        docs.push((postings.doc(), postings.freq()));
+        postings.next();
+    }
+    docs
+}
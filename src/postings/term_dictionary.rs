@@ -0,0 +1,253 @@
+use std::cmp::Ordering;
+
+use crate::automata::Automaton;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TermInfo {
+    pub doc_freq: u32,
+    pub postings_offset: usize,
+    pub positions_offset: usize,
+}
+
+// The sorted term dictionary of a field in an immutable segment. Terms are
+// concatenated into a single buffer and looked up by binary search.
+pub struct TermDictionary {
+    terms: Vec<u8>,
+    term_offsets: Vec<usize>,
+    term_infos: Vec<TermInfo>,
+}
+
+impl TermDictionary {
+    pub fn len(&self) -> usize {
+        self.term_infos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.term_infos.is_empty()
+    }
+
+    pub fn term(&self, term_ord: usize) -> &str {
+        let bytes = &self.terms[self.term_offsets[term_ord]..self.term_offsets[term_ord + 1]];
+        unsafe { std::str::from_utf8_unchecked(bytes) }
+    }
+
+    pub fn term_info(&self, term_ord: usize) -> &TermInfo {
+        &self.term_infos[term_ord]
+    }
+
+    pub fn term_ord(&self, term: &str) -> Option<usize> {
+        let mut low = 0;
+        let mut high = self.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.term(mid).cmp(term) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Some(mid),
+            }
+        }
+        None
+    }
+
+    pub fn get(&self, term: &str) -> Option<&TermInfo> {
+        self.term_ord(term).map(|term_ord| self.term_info(term_ord))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &TermInfo)> {
+        (0..self.len()).map(|term_ord| (self.term(term_ord), self.term_info(term_ord)))
+    }
+
+    // The ords of the terms matched by the automaton, in order.
+    //
+    // Terms are walked in order, reusing the states of the prefix shared with
+    // the previous term. Once a prefix can't match, all the terms starting
+    // with it, which are contiguous, are skipped by binary search.
+    pub fn search<A: Automaton>(&self, automaton: &A) -> Vec<usize> {
+        let mut term_ords = Vec::new();
+        // The chars of the last walked prefix, and the states after each of
+        // them, starting with the start state.
+        let mut chars: Vec<char> = Vec::new();
+        let mut states = vec![automaton.start()];
+        let mut term_ord = 0;
+        while term_ord < self.len() {
+            let term = self.term(term_ord);
+            let common_len = chars
+                .iter()
+                .zip(term.chars())
+                .take_while(|&(&a, b)| a == b)
+                .count();
+            chars.truncate(common_len);
+            states.truncate(common_len + 1);
+
+            let mut can_match = true;
+            for c in term.chars().skip(common_len) {
+                let state = automaton.accept(states.last().unwrap(), c);
+                chars.push(c);
+                if !automaton.can_match(&state) {
+                    can_match = false;
+                    break;
+                }
+                states.push(state);
+            }
+            if can_match {
+                if automaton.is_match(states.last().unwrap()) {
+                    term_ords.push(term_ord);
+                }
+                term_ord += 1;
+            } else {
+                let prefix: String = chars.iter().collect();
+                term_ord = self.prefix_end(term_ord, &prefix);
+                chars.pop();
+            }
+        }
+        term_ords
+    }
+
+    // The ord of the first term from `term_ord` that doesn't start with the
+    // prefix, given that the term at `term_ord` does.
+    fn prefix_end(&self, term_ord: usize, prefix: &str) -> usize {
+        let mut low = term_ord + 1;
+        let mut high = self.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.term(mid).starts_with(prefix) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+}
+
+pub struct TermDictionaryBuilder {
+    terms: Vec<u8>,
+    term_offsets: Vec<usize>,
+    term_infos: Vec<TermInfo>,
+}
+
+impl Default for TermDictionaryBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TermDictionaryBuilder {
+    pub fn new() -> Self {
+        Self {
+            terms: Vec::new(),
+            term_offsets: vec![0],
+            term_infos: Vec::new(),
+        }
+    }
+
+    // Terms must be inserted in strictly increasing order.
+    pub fn insert(&mut self, term: &str, term_info: TermInfo) {
+        debug_assert!(self.last_term().is_none_or(|last_term| last_term < term));
+        self.terms.extend_from_slice(term.as_bytes());
+        self.term_offsets.push(self.terms.len());
+        self.term_infos.push(term_info);
+    }
+
+    pub fn build(self) -> TermDictionary {
+        TermDictionary {
+            terms: self.terms,
+            term_offsets: self.term_offsets,
+            term_infos: self.term_infos,
+        }
+    }
+
+    fn last_term(&self) -> Option<&str> {
+        let len = self.term_infos.len();
+        if len > 0 {
+            let bytes = &self.terms[self.term_offsets[len - 1]..self.term_offsets[len]];
+            Some(unsafe { std::str::from_utf8_unchecked(bytes) })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::automata::{LevenshteinAutomaton, PrefixAutomaton, RegexAutomaton};
+
+    use super::{TermDictionaryBuilder, TermInfo};
+
+    fn term_info(doc_freq: u32) -> TermInfo {
+        TermInfo {
+            doc_freq,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_simple() {
+        let builder = TermDictionaryBuilder::new();
+        let dictionary = builder.build();
+        assert!(dictionary.is_empty());
+        assert!(dictionary.get("hello").is_none());
+
+        let terms = ["", "a", "ab", "abc", "b", "hello", "world", "世界"];
+        let mut builder = TermDictionaryBuilder::new();
+        for (i, term) in terms.iter().enumerate() {
+            builder.insert(term, term_info(i as u32));
+        }
+        let dictionary = builder.build();
+        assert_eq!(dictionary.len(), terms.len());
+        for (i, term) in terms.iter().enumerate() {
+            assert_eq!(dictionary.term_ord(term), Some(i));
+            assert_eq!(dictionary.term(i), *term);
+            assert_eq!(dictionary.get(term), Some(&term_info(i as u32)));
+        }
+        assert!(dictionary.get("aa").is_none());
+        assert!(dictionary.get("zzz").is_none());
+
+        let iterated: Vec<_> = dictionary.iter().map(|(term, _)| term).collect();
+        assert_eq!(iterated, terms);
+    }
+
+    #[test]
+    fn test_search() {
+        let terms = [
+            "", "a", "ab", "abc", "abd", "b", "ba", "hello", "help", "world", "世界",
+        ];
+        let mut builder = TermDictionaryBuilder::new();
+        for term in terms {
+            builder.insert(term, term_info(0));
+        }
+        let dictionary = builder.build();
+        let search = |term_ords: Vec<usize>| -> Vec<&str> {
+            term_ords
+                .into_iter()
+                .map(|term_ord| dictionary.term(term_ord))
+                .collect()
+        };
+
+        assert_eq!(
+            search(dictionary.search(&PrefixAutomaton::new("ab"))),
+            ["ab", "abc", "abd"]
+        );
+        assert_eq!(
+            search(dictionary.search(&PrefixAutomaton::new("x"))),
+            [""; 0]
+        );
+        assert_eq!(search(dictionary.search(&PrefixAutomaton::new(""))), terms);
+        assert_eq!(
+            search(dictionary.search(&RegexAutomaton::new("(a|b).?").unwrap())),
+            ["a", "ab", "b", "ba"]
+        );
+        assert_eq!(
+            search(dictionary.search(&RegexAutomaton::wildcard("*l?"))),
+            ["hello", "help", "world"]
+        );
+        assert_eq!(
+            search(dictionary.search(&LevenshteinAutomaton::new("hellp", 1, true))),
+            ["hello", "help"]
+        );
+        assert_eq!(
+            search(dictionary.search(&LevenshteinAutomaton::new("界世", 1, true))),
+            ["世界"]
+        );
+    }
+}
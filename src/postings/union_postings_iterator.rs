@@ -0,0 +1,159 @@
+use crate::{DocId, TERMINATED};
+
+use super::PostingsIterator;
+
+// Iterates the docs of any of several posting lists, merging their positions
+// as if they were the positions of a single term, e.g. to match alternative
+// terms at the same place of a phrase.
+pub struct UnionPostingsIterator<'a> {
+    postings: Vec<Box<dyn PostingsIterator + 'a>>,
+    doc: DocId,
+    freq: u32,
+    positions: Vec<u32>,
+    offsets: Vec<(u32, u32)>,
+}
+
+impl<'a> UnionPostingsIterator<'a> {
+    pub fn new(postings: Vec<Box<dyn PostingsIterator + 'a>>) -> Self {
+        let mut iterator = Self {
+            postings,
+            doc: TERMINATED,
+            freq: 0,
+            positions: Vec::new(),
+            offsets: Vec::new(),
+        };
+        iterator.load_doc();
+        iterator
+    }
+
+    fn load_doc(&mut self) {
+        self.doc = self
+            .postings
+            .iter()
+            .map(|postings| postings.doc())
+            .min()
+            .unwrap_or(TERMINATED);
+        self.freq = 0;
+        self.positions.clear();
+        self.offsets.clear();
+        if self.doc == TERMINATED {
+            return;
+        }
+
+        let mut occurrences = Vec::new();
+        let mut has_offsets = true;
+        let doc = self.doc;
+        for postings in self.postings.iter_mut().filter(|p| p.doc() == doc) {
+            self.freq += postings.freq();
+            let start = occurrences.len();
+            occurrences.extend(
+                postings
+                    .positions()
+                    .iter()
+                    .map(|&position| (position, (0, 0))),
+            );
+            let offsets = postings.offsets();
+            has_offsets &= offsets.len() == occurrences.len() - start;
+            for (occurrence, &offsets) in occurrences[start..].iter_mut().zip(offsets) {
+                occurrence.1 = offsets;
+            }
+        }
+        if occurrences.is_empty() {
+            return;
+        }
+        // Terms at the same position, like synonyms, count once.
+        occurrences.sort_unstable();
+        occurrences.dedup_by_key(|&mut (position, _)| position);
+        self.freq = occurrences.len() as u32;
+        self.positions
+            .extend(occurrences.iter().map(|&(position, _)| position));
+        if has_offsets {
+            self.offsets
+                .extend(occurrences.iter().map(|&(_, offsets)| offsets));
+        }
+    }
+}
+
+impl<'a> PostingsIterator for UnionPostingsIterator<'a> {
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn next(&mut self) -> DocId {
+        if self.doc == TERMINATED {
+            return TERMINATED;
+        }
+        let doc = self.doc;
+        for postings in self.postings.iter_mut().filter(|p| p.doc() == doc) {
+            postings.next();
+        }
+        self.load_doc();
+        self.doc
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        if self.doc >= target {
+            return self.doc;
+        }
+        for postings in self.postings.iter_mut() {
+            postings.advance(target);
+        }
+        self.load_doc();
+        self.doc
+    }
+
+    fn freq(&self) -> u32 {
+        self.freq
+    }
+
+    fn positions(&mut self) -> &[u32] {
+        &self.positions
+    }
+
+    fn offsets(&mut self) -> &[(u32, u32)] {
+        &self.offsets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        postings::{collect_postings, PostingWriter, PostingsIterator},
+        schema::IndexRecordOption,
+        TERMINATED,
+    };
+
+    use super::UnionPostingsIterator;
+
+    #[test]
+    fn test_simple() {
+        let record_option = IndexRecordOption::WithPositionsAndOffsets;
+        let fox = PostingWriter::new(record_option);
+        fox.add_doc(1, &[2], &[(10, 13)]);
+        fox.add_doc(4, &[0, 5], &[(0, 3), (30, 33)]);
+        let foxes = PostingWriter::new(record_option);
+        foxes.add_doc(3, &[1], &[(4, 9)]);
+        foxes.add_doc(4, &[3, 5], &[(20, 25), (30, 35)]);
+
+        let postings: Vec<Box<dyn PostingsIterator>> = vec![
+            Box::new(fox.postings(TERMINATED)),
+            Box::new(foxes.postings(TERMINATED)),
+        ];
+        let mut union = UnionPostingsIterator::new(postings);
+        assert_eq!(union.doc(), 1);
+        assert_eq!(union.positions(), &[2]);
+        assert_eq!(union.advance(4), 4);
+        assert_eq!(union.freq(), 3);
+        assert_eq!(union.positions(), &[0, 3, 5]);
+        assert_eq!(union.offsets(), &[(0, 3), (20, 25), (30, 33)]);
+        assert_eq!(union.next(), TERMINATED);
+
+        let postings: Vec<Box<dyn PostingsIterator>> = vec![
+            Box::new(fox.postings(TERMINATED)),
+            Box::new(foxes.postings(TERMINATED)),
+        ];
+        let docs = collect_postings(&mut UnionPostingsIterator::new(postings));
+        assert_eq!(docs, vec![(1, 1), (3, 1), (4, 3)]);
+        assert_eq!(UnionPostingsIterator::new(vec![]).doc(), TERMINATED);
+    }
+}
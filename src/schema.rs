@@ -0,0 +1,664 @@
+use std::{collections::HashMap, sync::Arc};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Field(u32);
+
+impl Field {
+    pub fn from_field_id(field_id: u32) -> Self {
+        Self(field_id)
+    }
+
+    pub fn field_id(&self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IndexRecordOption {
+    #[default]
+    Basic,
+    WithPositions,
+    WithPositionsAndOffsets,
+}
+
+impl IndexRecordOption {
+    pub fn has_positions(&self) -> bool {
+        match self {
+            IndexRecordOption::Basic => false,
+            IndexRecordOption::WithPositions | IndexRecordOption::WithPositionsAndOffsets => true,
+        }
+    }
+
+    pub fn has_offsets(&self) -> bool {
+        match self {
+            IndexRecordOption::Basic | IndexRecordOption::WithPositions => false,
+            IndexRecordOption::WithPositionsAndOffsets => true,
+        }
+    }
+}
+
+// Whether doc values keep one value per doc, the first, or all of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cardinality {
+    Single,
+    Multi,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TextOptions {
+    indexed: bool,
+    analyzer: Option<String>,
+    search_analyzer: Option<String>,
+    index_record_option: IndexRecordOption,
+    omit_norms: bool,
+    doc_values: Option<Cardinality>,
+    stored: bool,
+}
+
+impl TextOptions {
+    pub fn set_indexed(mut self) -> Self {
+        self.indexed = true;
+        self
+    }
+
+    // Without an analyzer, the whole value is indexed as a single term.
+    pub fn set_analyzer(mut self, analyzer: &str) -> Self {
+        self.analyzer = Some(analyzer.to_string());
+        self
+    }
+
+    // Queries are analyzed with the search analyzer, which defaults to the
+    // analyzer the values are indexed with.
+    pub fn set_search_analyzer(mut self, analyzer: &str) -> Self {
+        self.search_analyzer = Some(analyzer.to_string());
+        self
+    }
+
+    pub fn set_index_record_option(mut self, index_record_option: IndexRecordOption) -> Self {
+        self.index_record_option = index_record_option;
+        self
+    }
+
+    // Without norms, field lengths are not stored and don't affect scores,
+    // which saves a byte per doc for fields like tags and ids.
+    pub fn set_omit_norms(mut self) -> Self {
+        self.omit_norms = true;
+        self
+    }
+
+    // Stores the whole values, not analyzed, as sorted doc values: ordinals
+    // of the values in a dictionary of the field.
+    pub fn set_doc_values(mut self, cardinality: Cardinality) -> Self {
+        self.doc_values = Some(cardinality);
+        self
+    }
+
+    // Keeps the original values, to be retrieved with the doc.
+    pub fn set_stored(mut self) -> Self {
+        self.stored = true;
+        self
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.indexed
+    }
+
+    pub fn is_stored(&self) -> bool {
+        self.stored
+    }
+
+    pub fn doc_values(&self) -> Option<Cardinality> {
+        self.doc_values
+    }
+
+    pub fn analyzer(&self) -> Option<&str> {
+        self.analyzer.as_deref()
+    }
+
+    pub fn search_analyzer(&self) -> Option<&str> {
+        self.search_analyzer.as_deref().or(self.analyzer())
+    }
+
+    pub fn index_record_option(&self) -> IndexRecordOption {
+        self.index_record_option
+    }
+
+    pub fn has_norms(&self) -> bool {
+        self.indexed && !self.omit_norms
+    }
+}
+
+pub const MAX_DIMENSIONS: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct NumericOptions {
+    indexed: bool,
+    dimensions: usize,
+    doc_values: Option<Cardinality>,
+    updatable: bool,
+    stored: bool,
+}
+
+impl Default for NumericOptions {
+    fn default() -> Self {
+        Self {
+            indexed: false,
+            dimensions: 1,
+            doc_values: None,
+            updatable: false,
+            stored: false,
+        }
+    }
+}
+
+impl NumericOptions {
+    // Indexes the values as points, for range queries.
+    pub fn set_indexed(mut self) -> Self {
+        self.indexed = true;
+        self
+    }
+
+    // Each value of a field with several dimensions is a point with a number
+    // for each of them, e.g. a latitude and a longitude.
+    pub fn set_dimensions(mut self, dimensions: usize) -> Self {
+        assert!(
+            (1..=MAX_DIMENSIONS).contains(&dimensions),
+            "Dimensions must be between 1 and {}",
+            MAX_DIMENSIONS
+        );
+        self.dimensions = dimensions;
+        self
+    }
+
+    // Stores the values in columns, for sorting and aggregations. Only values
+    // of a single number are stored, not points.
+    pub fn set_doc_values(mut self, cardinality: Cardinality) -> Self {
+        assert!(
+            !self.updatable || cardinality == Cardinality::Single,
+            "Updatable fields must have a single value"
+        );
+        self.doc_values = Some(cardinality);
+        self
+    }
+
+    // Lets the doc values be overwritten after indexing, without reindexing
+    // the doc. Implies single value doc values. Indexed points keep the value
+    // the doc was indexed with.
+    pub fn set_updatable(mut self) -> Self {
+        assert!(
+            self.doc_values != Some(Cardinality::Multi),
+            "Updatable fields must have a single value"
+        );
+        self.doc_values = Some(Cardinality::Single);
+        self.updatable = true;
+        self
+    }
+
+    // Keeps the original values, to be retrieved with the doc. Updates of
+    // doc values don't change them.
+    pub fn set_stored(mut self) -> Self {
+        self.stored = true;
+        self
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.indexed
+    }
+
+    pub fn is_stored(&self) -> bool {
+        self.stored
+    }
+
+    pub fn is_updatable(&self) -> bool {
+        self.updatable
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    pub fn doc_values(&self) -> Option<Cardinality> {
+        self.doc_values
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BytesOptions {
+    doc_values: bool,
+    stored: bool,
+}
+
+impl BytesOptions {
+    // Stores the first value of each doc as binary doc values.
+    pub fn set_doc_values(mut self) -> Self {
+        self.doc_values = true;
+        self
+    }
+
+    // Keeps the original values, to be retrieved with the doc.
+    pub fn set_stored(mut self) -> Self {
+        self.stored = true;
+        self
+    }
+
+    pub fn has_doc_values(&self) -> bool {
+        self.doc_values
+    }
+
+    pub fn is_stored(&self) -> bool {
+        self.stored
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum FieldType {
+    Text(TextOptions),
+    I64(NumericOptions),
+    U64(NumericOptions),
+    F64(NumericOptions),
+    Date(NumericOptions),
+    Bytes(BytesOptions),
+}
+
+impl FieldType {
+    pub fn is_indexed(&self) -> bool {
+        match self {
+            FieldType::Text(options) => options.is_indexed(),
+            FieldType::Bytes(_) => false,
+            _ => self.numeric_options().unwrap().is_indexed(),
+        }
+    }
+
+    pub fn has_norms(&self) -> bool {
+        match self {
+            FieldType::Text(options) => options.has_norms(),
+            _ => false,
+        }
+    }
+
+    // Only indexed text fields have terms.
+    pub fn index_record_option(&self) -> Option<IndexRecordOption> {
+        match self {
+            FieldType::Text(options) if options.is_indexed() => Some(options.index_record_option()),
+            _ => None,
+        }
+    }
+
+    pub fn numeric_options(&self) -> Option<&NumericOptions> {
+        match self {
+            FieldType::Text(_) | FieldType::Bytes(_) => None,
+            FieldType::I64(options)
+            | FieldType::U64(options)
+            | FieldType::F64(options)
+            | FieldType::Date(options) => Some(options),
+        }
+    }
+
+    // The dimensions of the points of indexed numeric fields.
+    pub fn point_dimensions(&self) -> Option<usize> {
+        self.numeric_options()
+            .filter(|options| options.is_indexed())
+            .map(|options| options.dimensions())
+    }
+
+    pub fn doc_values(&self) -> Option<Cardinality> {
+        match self {
+            FieldType::Text(options) => options.doc_values(),
+            FieldType::Bytes(options) => options.has_doc_values().then_some(Cardinality::Single),
+            _ => self.numeric_options().unwrap().doc_values(),
+        }
+    }
+
+    pub fn is_updatable(&self) -> bool {
+        self.numeric_options()
+            .is_some_and(|options| options.is_updatable())
+    }
+
+    pub fn is_stored(&self) -> bool {
+        match self {
+            FieldType::Text(options) => options.is_stored(),
+            FieldType::Bytes(options) => options.is_stored(),
+            _ => self.numeric_options().unwrap().is_stored(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FieldEntry {
+    name: String,
+    field_type: FieldType,
+}
+
+impl FieldEntry {
+    pub fn new(name: String, field_type: FieldType) -> Self {
+        Self { name, field_type }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn field_type(&self) -> &FieldType {
+        &self.field_type
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.field_type.is_indexed()
+    }
+
+    pub fn has_norms(&self) -> bool {
+        self.field_type.has_norms()
+    }
+
+    pub fn is_stored(&self) -> bool {
+        self.field_type.is_stored()
+    }
+}
+
+#[derive(Default)]
+pub struct SchemaBuilder {
+    fields: Vec<FieldEntry>,
+    fields_map: HashMap<String, Field>,
+    copy_to: Vec<(Field, Field)>,
+}
+
+impl SchemaBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_text_field(&mut self, name: &str, options: TextOptions) -> Field {
+        self.add_field(FieldEntry::new(name.to_string(), FieldType::Text(options)))
+    }
+
+    pub fn add_i64_field(&mut self, name: &str, options: NumericOptions) -> Field {
+        self.add_field(FieldEntry::new(name.to_string(), FieldType::I64(options)))
+    }
+
+    pub fn add_u64_field(&mut self, name: &str, options: NumericOptions) -> Field {
+        self.add_field(FieldEntry::new(name.to_string(), FieldType::U64(options)))
+    }
+
+    pub fn add_f64_field(&mut self, name: &str, options: NumericOptions) -> Field {
+        self.add_field(FieldEntry::new(name.to_string(), FieldType::F64(options)))
+    }
+
+    pub fn add_date_field(&mut self, name: &str, options: NumericOptions) -> Field {
+        self.add_field(FieldEntry::new(name.to_string(), FieldType::Date(options)))
+    }
+
+    pub fn add_bytes_field(&mut self, name: &str, options: BytesOptions) -> Field {
+        self.add_field(FieldEntry::new(name.to_string(), FieldType::Bytes(options)))
+    }
+
+    pub fn add_field(&mut self, field_entry: FieldEntry) -> Field {
+        assert!(
+            !self.fields_map.contains_key(field_entry.name()),
+            "Field `{}` already exists",
+            field_entry.name()
+        );
+        let field = Field::from_field_id(self.fields.len() as u32);
+        self.fields_map
+            .insert(field_entry.name().to_string(), field);
+        self.fields.push(field_entry);
+        field
+    }
+
+    // Indexes the values of `parent` again as the field `<parent>.<name>`,
+    // e.g. `title.keyword` with another analyzer than `title`.
+    pub fn add_sub_field(&mut self, parent: Field, name: &str, options: TextOptions) -> Field {
+        let name = format!(
+            "{}.{}",
+            self.fields[parent.field_id() as usize].name(),
+            name
+        );
+        let field = self.add_text_field(&name, options);
+        self.add_copy_to(parent, field);
+        field
+    }
+
+    // Indexes the values of `source` into `target` as well, e.g. to search
+    // several fields at once in a catch-all field. Values are copied on to the
+    // targets of `target` too.
+    pub fn add_copy_to(&mut self, source: Field, target: Field) {
+        assert!(
+            (source.field_id() as usize) < self.fields.len()
+                && (target.field_id() as usize) < self.fields.len(),
+            "Unknown field"
+        );
+        assert_ne!(source, target, "Field can't be copied to itself");
+        if !self.copy_to.contains(&(source, target)) {
+            self.copy_to.push((source, target));
+        }
+    }
+
+    pub fn build(self) -> Schema {
+        let copy_targets = (0..self.fields.len())
+            .map(|field_id| {
+                let source = Field::from_field_id(field_id as u32);
+                let mut targets = Vec::new();
+                let mut pending = vec![source];
+                while let Some(field) = pending.pop() {
+                    for &(_, target) in self.copy_to.iter().filter(|&&(from, _)| from == field) {
+                        if target != source && !targets.contains(&target) {
+                            targets.push(target);
+                            pending.push(target);
+                        }
+                    }
+                }
+                targets
+            })
+            .collect();
+        Schema(Arc::new(InnerSchema {
+            fields: self.fields,
+            fields_map: self.fields_map,
+            copy_targets,
+        }))
+    }
+}
+
+struct InnerSchema {
+    fields: Vec<FieldEntry>,
+    fields_map: HashMap<String, Field>,
+    copy_targets: Vec<Vec<Field>>,
+}
+
+#[derive(Clone)]
+pub struct Schema(Arc<InnerSchema>);
+
+impl Schema {
+    pub fn builder() -> SchemaBuilder {
+        SchemaBuilder::new()
+    }
+
+    pub fn get_field(&self, name: &str) -> Option<Field> {
+        self.0.fields_map.get(name).copied()
+    }
+
+    pub fn field_entry(&self, field: Field) -> &FieldEntry {
+        &self.0.fields[field.field_id() as usize]
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = (Field, &FieldEntry)> {
+        self.0
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(field_id, entry)| (Field::from_field_id(field_id as u32), entry))
+    }
+
+    pub fn num_fields(&self) -> usize {
+        self.0.fields.len()
+    }
+
+    // The fields the values of `field` are indexed into as well, from its
+    // sub-fields and copies.
+    pub fn copy_targets(&self, field: Field) -> &[Field] {
+        &self.0.copy_targets[field.field_id() as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BytesOptions, Cardinality, Field, FieldType, NumericOptions, Schema, TextOptions};
+
+    #[test]
+    fn test_simple() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field("title", TextOptions::default().set_indexed());
+        let body = builder.add_text_field(
+            "body",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let schema = builder.build();
+
+        assert_eq!(schema.num_fields(), 2);
+        assert_eq!(title, Field::from_field_id(0));
+        assert_eq!(body, Field::from_field_id(1));
+        assert_eq!(schema.get_field("title"), Some(title));
+        assert_eq!(schema.get_field("body"), Some(body));
+        assert!(schema.get_field("unknown").is_none());
+        assert_eq!(schema.field_entry(body).name(), "body");
+        assert!(schema.field_entry(title).is_indexed());
+    }
+
+    #[test]
+    fn test_copy_to() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard")
+                .set_search_analyzer("whitespace"),
+        );
+        let body = builder.add_text_field("body", TextOptions::default().set_indexed());
+        let all = builder.add_text_field("all", TextOptions::default().set_indexed());
+        let title_keyword =
+            builder.add_sub_field(title, "keyword", TextOptions::default().set_indexed());
+        let all_ngram = builder.add_sub_field(all, "ngram", TextOptions::default().set_indexed());
+        builder.add_copy_to(title, all);
+        builder.add_copy_to(body, all);
+        builder.add_copy_to(all, title);
+        let schema = builder.build();
+
+        assert_eq!(schema.get_field("title.keyword"), Some(title_keyword));
+        assert_eq!(schema.get_field("all.ngram"), Some(all_ngram));
+        assert_eq!(schema.copy_targets(title), &[title_keyword, all, all_ngram]);
+        assert_eq!(
+            schema.copy_targets(body),
+            &[all, all_ngram, title, title_keyword]
+        );
+        assert!(schema.copy_targets(title_keyword).is_empty());
+
+        match schema.field_entry(title).field_type() {
+            FieldType::Text(options) => {
+                assert_eq!(options.analyzer(), Some("standard"));
+                assert_eq!(options.search_analyzer(), Some("whitespace"));
+            }
+            _ => unreachable!(),
+        }
+        match schema.field_entry(title_keyword).field_type() {
+            FieldType::Text(options) => assert!(options.search_analyzer().is_none()),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_numeric() {
+        let mut builder = Schema::builder();
+        let price = builder.add_f64_field("price", NumericOptions::default().set_indexed());
+        let location = builder.add_i64_field(
+            "location",
+            NumericOptions::default().set_indexed().set_dimensions(2),
+        );
+        let count = builder.add_u64_field("count", NumericOptions::default());
+        let schema = builder.build();
+
+        let field_type = schema.field_entry(price).field_type();
+        assert!(field_type.is_indexed());
+        assert!(!field_type.has_norms());
+        assert!(field_type.index_record_option().is_none());
+        assert_eq!(field_type.point_dimensions(), Some(1));
+        let field_type = schema.field_entry(location).field_type();
+        assert_eq!(field_type.point_dimensions(), Some(2));
+        let field_type = schema.field_entry(count).field_type();
+        assert!(!field_type.is_indexed());
+        assert!(field_type.point_dimensions().is_none());
+        assert!(field_type.doc_values().is_none());
+    }
+
+    #[test]
+    fn test_doc_values() {
+        let mut builder = Schema::builder();
+        let price = builder.add_f64_field(
+            "price",
+            NumericOptions::default().set_doc_values(Cardinality::Single),
+        );
+        let tags = builder.add_text_field(
+            "tags",
+            TextOptions::default()
+                .set_indexed()
+                .set_doc_values(Cardinality::Multi),
+        );
+        let thumbnail =
+            builder.add_bytes_field("thumbnail", BytesOptions::default().set_doc_values());
+        let schema = builder.build();
+
+        let field_type = schema.field_entry(price).field_type();
+        assert!(!field_type.is_indexed());
+        assert_eq!(field_type.doc_values(), Some(Cardinality::Single));
+        let field_type = schema.field_entry(tags).field_type();
+        assert!(field_type.is_indexed());
+        assert_eq!(field_type.doc_values(), Some(Cardinality::Multi));
+        let field_type = schema.field_entry(thumbnail).field_type();
+        assert!(!field_type.is_indexed());
+        assert!(field_type.numeric_options().is_none());
+        assert_eq!(field_type.doc_values(), Some(Cardinality::Single));
+        assert!(!field_type.is_updatable());
+    }
+
+    #[test]
+    fn test_stored() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field("title", TextOptions::default().set_stored());
+        let price = builder.add_f64_field("price", NumericOptions::default().set_stored());
+        let thumbnail = builder.add_bytes_field("thumbnail", BytesOptions::default().set_stored());
+        let id = builder.add_u64_field("id", NumericOptions::default().set_indexed());
+        let schema = builder.build();
+
+        for field in [title, price, thumbnail] {
+            let field_type = schema.field_entry(field).field_type();
+            assert!(field_type.is_stored());
+            assert!(!field_type.is_indexed());
+        }
+        assert!(!schema.field_entry(id).field_type().is_stored());
+    }
+
+    #[test]
+    fn test_updatable() {
+        let options = NumericOptions::default().set_updatable();
+        assert!(options.is_updatable());
+        assert_eq!(options.doc_values(), Some(Cardinality::Single));
+        assert!(FieldType::U64(options).is_updatable());
+        assert!(!FieldType::U64(NumericOptions::default()).is_updatable());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_updatable_multi() {
+        NumericOptions::default()
+            .set_updatable()
+            .set_doc_values(Cardinality::Multi);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_duplicated_field() {
+        let mut builder = Schema::builder();
+        builder.add_text_field("title", TextOptions::default());
+        builder.add_text_field("title", TextOptions::default());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{DocId, TERMINATED};
+
+use super::{DocSet, Scorer};
+
+// Iterates the docs of a bitset, with a score of one.
+pub struct BitSetScorer {
+    words: Vec<u64>,
+    doc: DocId,
+}
+
+impl DocSet for BitSetScorer {
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn next(&mut self) -> DocId {
+        if self.doc == TERMINATED {
+            return TERMINATED;
+        }
+        self.seek(self.doc + 1)
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        if self.doc >= target {
+            return self.doc;
+        }
+        self.seek(target)
+    }
+}
+
+impl BitSetScorer {
+    // Bit `doc % 64` of word `doc / 64` is set for each doc.
+    pub fn new(words: Vec<u64>) -> Self {
+        let mut scorer = Self { words, doc: 0 };
+        scorer.seek(0);
+        scorer
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        let mut index = target as usize / 64;
+        if index >= self.words.len() {
+            self.doc = TERMINATED;
+            return TERMINATED;
+        }
+        let mut word = self.words[index] & (u64::MAX << (target % 64));
+        while word == 0 {
+            index += 1;
+            if index == self.words.len() {
+                self.doc = TERMINATED;
+                return TERMINATED;
+            }
+            word = self.words[index];
+        }
+        self.doc = (index * 64) as DocId + word.trailing_zeros();
+        self.doc
+    }
+}
+
+impl Scorer for BitSetScorer {
+    fn score(&self) -> f32 {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        search::{collect_scores, DocSet},
+        TERMINATED,
+    };
+
+    use super::BitSetScorer;
+
+    #[test]
+    fn test_simple() {
+        let mut scorer = BitSetScorer::new(vec![1 << 3 | 1 << 63, 0, 1 << 1]);
+        let docs: Vec<_> = collect_scores(&mut scorer)
+            .into_iter()
+            .map(|(doc, _)| doc)
+            .collect();
+        assert_eq!(docs, [3, 63, 129]);
+
+        let mut scorer = BitSetScorer::new(vec![1 << 3 | 1 << 63, 0, 1 << 1]);
+        assert_eq!(scorer.advance(64), 129);
+        assert_eq!(scorer.next(), TERMINATED);
+
+        let scorer = BitSetScorer::new(vec![0, 0]);
+        assert_eq!(scorer.doc(), TERMINATED);
+    }
+}
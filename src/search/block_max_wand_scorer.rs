@@ -0,0 +1,172 @@
+use crate::{DocId, TERMINATED};
+
+use super::{DocSet, Scorer};
+
+// Scores are bounded with float operations in another order than they are
+// computed, so bounds are loosened by this factor to never skip a doc whose
+// actual score reaches the min competitive score.
+const BOUND_MARGIN: f32 = 1.0 + 1e-4;
+
+// Matches the docs matched by any of the scorers, scoring the sum of the
+// scores of the matching ones, like a disjunction.
+//
+// Once a min competitive score is set, it skips the docs that can't reach it
+// with Block-Max WAND: the scorers are sorted by doc, and the pivot is the
+// first doc where the sum of the max scores of the scorers up to it reaches
+// the min score, since no doc before it can. The pivot is then checked
+// against the block max scores of these scorers, skipping to the end of the
+// first block when they can't reach the min score either.
+pub struct BlockMaxWandScorer<'a> {
+    // Each scorer with its max score.
+    scorers: Vec<(Box<dyn Scorer + 'a>, f32)>,
+    // The indexes of the scorers sorted by doc, kept from one call to the
+    // next so that only the scorers that moved need sorting again.
+    order: Vec<usize>,
+    min_competitive_score: f32,
+    doc: DocId,
+}
+
+// Insertion sort, close to linear when few scorers moved since the last sort.
+fn sort_by_doc(order: &mut [usize], scorers: &[(Box<dyn Scorer + '_>, f32)]) {
+    for i in 1..order.len() {
+        let mut j = i;
+        while j > 0 && scorers[order[j - 1]].0.doc() > scorers[order[j]].0.doc() {
+            order.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+impl<'a> BlockMaxWandScorer<'a> {
+    pub fn new(scorers: Vec<Box<dyn Scorer + 'a>>) -> Self {
+        let scorers: Vec<_> = scorers
+            .into_iter()
+            .map(|scorer| {
+                let max_score = scorer.max_score();
+                (scorer, max_score)
+            })
+            .collect();
+        let order = (0..scorers.len()).collect();
+        let mut scorer = Self {
+            scorers,
+            order,
+            min_competitive_score: 0.0,
+            doc: 0,
+        };
+        scorer.find_match();
+        scorer
+    }
+
+    // Moves to the smallest doc that may reach the min competitive score,
+    // starting from the current docs of the scorers.
+    fn find_match(&mut self) -> DocId {
+        loop {
+            let len = self.scorers.len();
+            self.scorers
+                .retain(|(scorer, _)| scorer.doc() != TERMINATED);
+            if self.scorers.is_empty() {
+                self.doc = TERMINATED;
+                return TERMINATED;
+            }
+            if self.scorers.len() < len {
+                self.order.clear();
+                self.order.extend(0..self.scorers.len());
+            }
+
+            sort_by_doc(&mut self.order, &self.scorers);
+            let mut max_score = 0.0;
+            let mut pivot = None;
+            for &i in &self.order {
+                max_score += self.scorers[i].1;
+                if max_score * BOUND_MARGIN >= self.min_competitive_score {
+                    pivot = Some(self.scorers[i].0.doc());
+                    break;
+                }
+            }
+            let Some(pivot) = pivot else {
+                // Even all the scorers together can't reach the min score.
+                self.doc = TERMINATED;
+                return TERMINATED;
+            };
+
+            let mut block_max_score = 0.0;
+            let mut block_end = TERMINATED;
+            let mut next_doc = TERMINATED;
+            for (scorer, _) in &self.scorers {
+                if scorer.doc() <= pivot {
+                    let (last_doc, score) = scorer.block_max_score(pivot);
+                    block_max_score += score;
+                    block_end = block_end.min(last_doc);
+                } else {
+                    next_doc = next_doc.min(scorer.doc());
+                }
+            }
+            if block_max_score * BOUND_MARGIN < self.min_competitive_score {
+                // No doc can compete until a block ends or another scorer
+                // starts contributing.
+                let target = block_end.saturating_add(1).min(next_doc);
+                for (scorer, _) in self.scorers.iter_mut() {
+                    scorer.advance(target);
+                }
+                continue;
+            }
+
+            if self.scorers[self.order[0]].0.doc() == pivot {
+                self.doc = pivot;
+                return pivot;
+            }
+            for (scorer, _) in self.scorers.iter_mut() {
+                scorer.advance(pivot);
+            }
+        }
+    }
+}
+
+impl<'a> DocSet for BlockMaxWandScorer<'a> {
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn next(&mut self) -> DocId {
+        if self.doc == TERMINATED {
+            return TERMINATED;
+        }
+        let doc = self.doc;
+        for (scorer, _) in self.scorers.iter_mut() {
+            if scorer.doc() == doc {
+                scorer.next();
+            }
+        }
+        self.find_match()
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        if self.doc >= target {
+            return self.doc;
+        }
+        for (scorer, _) in self.scorers.iter_mut() {
+            scorer.advance(target);
+        }
+        self.find_match()
+    }
+}
+
+impl<'a> Scorer for BlockMaxWandScorer<'a> {
+    // Summed in the order of the scorers, like a disjunction, so that scores
+    // are identical.
+    fn score(&self) -> f32 {
+        self.scorers
+            .iter()
+            .filter(|(scorer, _)| scorer.doc() == self.doc)
+            .map(|(scorer, _)| scorer.score())
+            .sum()
+    }
+
+    fn max_score(&self) -> f32 {
+        self.scorers.iter().map(|(_, max_score)| max_score).sum()
+    }
+
+    fn set_min_competitive_score(&mut self, min_score: f32) {
+        self.min_competitive_score = self.min_competitive_score.max(min_score);
+    }
+}
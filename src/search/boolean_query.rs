@@ -0,0 +1,273 @@
+use crate::{index::Searcher, segment::SegmentReader};
+
+use super::{
+    BlockMaxWandScorer, ConjunctionScorer, ConstantScorer, DisjunctionScorer, ExclusionScorer,
+    Query, ReqOptScorer, Scorer, Weight,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Occur {
+    // Must match, and contributes to the score.
+    Must,
+    // May match, and contributes to the score when it does.
+    Should,
+    // Must not match.
+    MustNot,
+    // Must match, without contributing to the score.
+    Filter,
+}
+
+// Combines queries by how they must occur in the docs.
+//
+// Without any must or filter clause, at least one should clause has to match.
+// Otherwise should clauses only add to the score, unless a minimum number of
+// them is required to match.
+pub struct BooleanQuery {
+    clauses: Vec<(Occur, Box<dyn Query>)>,
+    minimum_should_match: usize,
+}
+
+impl BooleanQuery {
+    pub fn new(clauses: Vec<(Occur, Box<dyn Query>)>) -> Self {
+        Self {
+            clauses,
+            minimum_should_match: 0,
+        }
+    }
+
+    pub fn set_minimum_should_match(mut self, minimum_should_match: usize) -> Self {
+        self.minimum_should_match = minimum_should_match;
+        self
+    }
+
+    pub fn clauses(&self) -> &[(Occur, Box<dyn Query>)] {
+        &self.clauses
+    }
+
+    pub fn minimum_should_match(&self) -> usize {
+        self.minimum_should_match
+    }
+}
+
+impl Query for BooleanQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        Box::new(BooleanWeight {
+            clauses: self
+                .clauses
+                .iter()
+                .map(|(occur, query)| (*occur, query.weight(searcher)))
+                .collect(),
+            minimum_should_match: self.minimum_should_match,
+        })
+    }
+}
+
+struct BooleanWeight {
+    clauses: Vec<(Occur, Box<dyn Weight>)>,
+    minimum_should_match: usize,
+}
+
+impl Weight for BooleanWeight {
+    fn scorer<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Scorer + 'a>> {
+        let mut required = Vec::new();
+        let mut should = Vec::new();
+        let mut excluded = Vec::new();
+        for (occur, weight) in &self.clauses {
+            let scorer = weight.scorer(segment_reader);
+            match occur {
+                Occur::Must => required.push(scorer?),
+                Occur::Filter => required.push(Box::new(ConstantScorer::new(scorer?, 0.0))),
+                Occur::Should => should.extend(scorer),
+                Occur::MustNot => excluded.extend(scorer),
+            }
+        }
+
+        if self.minimum_should_match > 0 {
+            if should.len() < self.minimum_should_match {
+                return None;
+            }
+            required.push(Box::new(DisjunctionScorer::new(
+                should,
+                self.minimum_should_match,
+            )));
+            should = Vec::new();
+        }
+
+        let mut scorer: Box<dyn Scorer + 'a> = match (required.len(), should.is_empty()) {
+            (0, true) => return None,
+            (0, false) => Box::new(BlockMaxWandScorer::new(should)),
+            (_, should_is_empty) => {
+                let required = if required.len() == 1 {
+                    required.pop().unwrap()
+                } else {
+                    Box::new(ConjunctionScorer::new(required))
+                };
+                if should_is_empty {
+                    required
+                } else {
+                    Box::new(ReqOptScorer::new(
+                        required,
+                        Box::new(DisjunctionScorer::new(should, 1)),
+                    ))
+                }
+            }
+        };
+        if !excluded.is_empty() {
+            let excluded: Box<dyn Scorer + 'a> = if excluded.len() == 1 {
+                excluded.pop().unwrap()
+            } else {
+                Box::new(DisjunctionScorer::new(excluded, 1))
+            };
+            scorer = Box::new(ExclusionScorer::new(scorer, excluded));
+        }
+        Some(scorer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        index::Searcher,
+        schema::{Field, IndexRecordOption},
+        search::{search_all, text_searcher, MatchAllQuery, Query, TermQuery},
+        DocId,
+    };
+
+    use super::{BooleanQuery, Occur};
+
+    fn searcher() -> (Searcher, Field) {
+        let texts: Vec<_> = (0..300u32)
+            .map(|i| {
+                let mut text = vec!["all"];
+                for (divisor, term) in [(2, "two"), (3, "three"), (5, "five")] {
+                    if i.is_multiple_of(divisor) {
+                        text.push(term);
+                    }
+                }
+                text.join(" ")
+            })
+            .collect();
+        text_searcher("standard", IndexRecordOption::Basic, &[&texts])
+    }
+
+    fn term(field: Field, term: &str) -> Box<dyn Query> {
+        Box::new(TermQuery::new(field, term))
+    }
+
+    fn docs(searcher: &Searcher, query: &dyn Query) -> Vec<DocId> {
+        search_all(searcher, query)
+            .into_iter()
+            .map(|(_, doc, _)| doc)
+            .collect()
+    }
+
+    fn expect(filter: impl Fn(DocId) -> bool) -> Vec<DocId> {
+        (0..300).filter(|&i| filter(i)).collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        let (searcher, title) = searcher();
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, term(title, "two")),
+            (Occur::Must, term(title, "three")),
+        ]);
+        assert_eq!(docs(&searcher, &query), expect(|i| i.is_multiple_of(6)));
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Should, term(title, "two")),
+            (Occur::Should, term(title, "three")),
+            (Occur::Should, term(title, "missing")),
+        ]);
+        assert_eq!(
+            docs(&searcher, &query),
+            expect(|i| i.is_multiple_of(2) || i.is_multiple_of(3))
+        );
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, term(title, "two")),
+            (Occur::MustNot, term(title, "three")),
+            (Occur::MustNot, term(title, "five")),
+        ]);
+        assert_eq!(
+            docs(&searcher, &query),
+            expect(|i| i.is_multiple_of(2) && !i.is_multiple_of(3) && !i.is_multiple_of(5))
+        );
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Should, term(title, "two")),
+            (Occur::Should, term(title, "three")),
+            (Occur::Should, term(title, "five")),
+        ])
+        .set_minimum_should_match(2);
+        let matches = |i: DocId| [2, 3, 5].iter().filter(|&&d| i.is_multiple_of(d)).count();
+        assert_eq!(docs(&searcher, &query), expect(|i| matches(i) >= 2));
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, term(title, "missing")),
+            (Occur::Should, term(title, "two")),
+        ]);
+        assert!(docs(&searcher, &query).is_empty());
+
+        let query = BooleanQuery::new(vec![(Occur::MustNot, term(title, "two"))]);
+        assert!(docs(&searcher, &query).is_empty());
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, Box::new(MatchAllQuery)),
+            (Occur::MustNot, term(title, "two")),
+        ]);
+        assert_eq!(docs(&searcher, &query), expect(|i| !i.is_multiple_of(2)));
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Should, term(title, "two")),
+            (Occur::Should, term(title, "three")),
+        ])
+        .set_minimum_should_match(3);
+        assert!(docs(&searcher, &query).is_empty());
+    }
+
+    #[test]
+    fn test_scores() {
+        let (searcher, title) = searcher();
+        let scores = |query: &dyn Query| -> Vec<(DocId, f32)> {
+            search_all(&searcher, query)
+                .into_iter()
+                .map(|(_, doc, score)| (doc, score))
+                .collect()
+        };
+        let two = scores(&TermQuery::new(title, "two"));
+        let three = scores(&TermQuery::new(title, "three"));
+        let score = |docs: &[(DocId, f32)], doc: DocId| {
+            docs.iter()
+                .find(|&&(d, _)| d == doc)
+                .map_or(0.0, |&(_, score)| score)
+        };
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, term(title, "two")),
+            (Occur::Should, term(title, "three")),
+        ]);
+        let docs = scores(&query);
+        assert_eq!(docs.len(), two.len());
+        for &(doc, s) in &docs {
+            assert_eq!(s, score(&two, doc) + score(&three, doc));
+        }
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Filter, term(title, "two")),
+            (Occur::Must, term(title, "three")),
+        ]);
+        let docs = scores(&query);
+        assert_eq!(
+            docs.iter().map(|&(doc, _)| doc).collect::<Vec<_>>(),
+            expect(|i| i.is_multiple_of(6))
+        );
+        for &(doc, s) in &docs {
+            assert_eq!(s, score(&three, doc));
+        }
+
+        let query = BooleanQuery::new(vec![(Occur::Filter, term(title, "two"))]);
+        assert!(scores(&query).iter().all(|&(_, s)| s == 0.0));
+    }
+}
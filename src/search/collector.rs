@@ -0,0 +1,85 @@
+use crate::{segment::SegmentReader, DocId};
+
+// Collects the matches of a search, one segment at a time, and merges what
+// was collected in each segment.
+//
+// Tuples of collectors collect in the same pass, e.g. `(Count::new(),
+// TopDocs::new(10))`.
+pub trait Collector {
+    type Fruit;
+    type SegmentFruit;
+    // May borrow the segment reader, e.g. to read doc values while collecting.
+    type Child<'a>: SegmentCollector<Fruit = Self::SegmentFruit>
+    where
+        Self: 'a;
+
+    fn for_segment<'a>(
+        &'a self,
+        segment_ord: usize,
+        segment_reader: &'a SegmentReader,
+    ) -> Self::Child<'a>;
+
+    // Fruits are in segment order.
+    fn merge_fruits(&self, fruits: Vec<Self::SegmentFruit>) -> Self::Fruit;
+}
+
+pub trait SegmentCollector {
+    type Fruit;
+
+    fn collect(&mut self, doc: DocId, score: f32);
+
+    // Docs scoring below it may be skipped, in this segment and the following
+    // ones. Every doc is collected by default.
+    fn min_competitive_score(&self) -> f32 {
+        0.0
+    }
+
+    fn harvest(self) -> Self::Fruit;
+}
+
+macro_rules! impl_tuple_collector {
+    ($($name:ident $index:tt),+) => {
+        impl<$($name: Collector),+> Collector for ($($name,)+) {
+            type Fruit = ($($name::Fruit,)+);
+            type SegmentFruit = ($($name::SegmentFruit,)+);
+            type Child<'a> = ($($name::Child<'a>,)+) where Self: 'a;
+
+            fn for_segment<'a>(
+                &'a self,
+                segment_ord: usize,
+                segment_reader: &'a SegmentReader,
+            ) -> Self::Child<'a> {
+                ($(self.$index.for_segment(segment_ord, segment_reader),)+)
+            }
+
+            fn merge_fruits(&self, fruits: Vec<Self::SegmentFruit>) -> Self::Fruit {
+                let mut split_fruits = ($(Vec::<$name::SegmentFruit>::with_capacity(fruits.len()),)+);
+                for fruit in fruits {
+                    $(split_fruits.$index.push(fruit.$index);)+
+                }
+                ($(self.$index.merge_fruits(split_fruits.$index),)+)
+            }
+        }
+
+        impl<$($name: SegmentCollector),+> SegmentCollector for ($($name,)+) {
+            type Fruit = ($($name::Fruit,)+);
+
+            fn collect(&mut self, doc: DocId, score: f32) {
+                $(self.$index.collect(doc, score);)+
+            }
+
+            // Only the docs that none of the collectors needs can be skipped.
+            fn min_competitive_score(&self) -> f32 {
+                f32::INFINITY$(.min(self.$index.min_competitive_score()))+
+            }
+
+            fn harvest(self) -> Self::Fruit {
+                ($(self.$index.harvest(),)+)
+            }
+        }
+    };
+}
+
+impl_tuple_collector!(A 0, B 1);
+impl_tuple_collector!(A 0, B 1, C 2);
+impl_tuple_collector!(A 0, B 1, C 2, D 3);
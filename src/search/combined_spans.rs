@@ -0,0 +1,124 @@
+use crate::{DocId, TERMINATED};
+
+use super::{DocSet, Span, Spans};
+
+pub(super) type CombineSpans<'a> = Box<dyn Fn(&[&[Span]]) -> Vec<Span> + 'a>;
+
+// Combines the spans of several clauses in each doc. Docs are matched by all
+// the required clauses, or by any clause if none is required, and the spans
+// of the clauses matching the doc are then combined, with no spans given for
+// the others. Docs where the combination is empty are skipped.
+pub(super) struct CombinedSpans<'a> {
+    clauses: Vec<(Box<dyn Spans + 'a>, bool)>,
+    combine: CombineSpans<'a>,
+    doc: DocId,
+    spans: Vec<Span>,
+}
+
+impl<'a> CombinedSpans<'a> {
+    pub(super) fn new(
+        clauses: Vec<(Box<dyn Spans + 'a>, bool)>,
+        combine: CombineSpans<'a>,
+    ) -> Self {
+        let mut spans = Self {
+            clauses,
+            combine,
+            doc: 0,
+            spans: Vec::new(),
+        };
+        spans.find_match(0);
+        spans
+    }
+
+    fn find_match(&mut self, mut target: DocId) -> DocId {
+        loop {
+            for (clause, _) in self.clauses.iter_mut() {
+                clause.advance(target);
+            }
+            let doc = self.align();
+            if doc == TERMINATED {
+                self.doc = TERMINATED;
+                self.spans.clear();
+                return TERMINATED;
+            }
+            for (clause, _) in self.clauses.iter_mut() {
+                clause.advance(doc);
+            }
+            let clause_spans: Vec<&[Span]> = self
+                .clauses
+                .iter()
+                .map(|(clause, _)| {
+                    if clause.doc() == doc {
+                        clause.spans()
+                    } else {
+                        &[]
+                    }
+                })
+                .collect();
+            let spans = (self.combine)(&clause_spans);
+            if !spans.is_empty() {
+                self.doc = doc;
+                self.spans = spans;
+                return doc;
+            }
+            target = doc + 1;
+        }
+    }
+
+    // Leapfrogs the required clauses to a doc they all match, or finds the
+    // smallest doc of any clause if none is required.
+    fn align(&mut self) -> DocId {
+        let mut required = self
+            .clauses
+            .iter_mut()
+            .filter(|(_, required)| *required)
+            .map(|(clause, _)| clause)
+            .peekable();
+        if required.peek().is_none() {
+            return self
+                .clauses
+                .iter()
+                .map(|(clause, _)| clause.doc())
+                .min()
+                .unwrap_or(TERMINATED);
+        }
+        let mut required: Vec<_> = required.collect();
+        let mut target = required.iter().map(|clause| clause.doc()).max().unwrap();
+        'outer: loop {
+            for clause in required.iter_mut() {
+                let doc = clause.advance(target);
+                if doc > target {
+                    target = doc;
+                    continue 'outer;
+                }
+            }
+            return target;
+        }
+    }
+}
+
+impl<'a> DocSet for CombinedSpans<'a> {
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn next(&mut self) -> DocId {
+        if self.doc == TERMINATED {
+            return TERMINATED;
+        }
+        self.find_match(self.doc + 1)
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        if self.doc >= target {
+            return self.doc;
+        }
+        self.find_match(target)
+    }
+}
+
+impl<'a> Spans for CombinedSpans<'a> {
+    fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{DocId, TERMINATED};
+
+use super::{DocSet, Scorer};
+
+// Matches the docs matched by all of the scorers, scoring the sum of their
+// scores.
+pub struct ConjunctionScorer<'a> {
+    scorers: Vec<Box<dyn Scorer + 'a>>,
+    doc: DocId,
+}
+
+impl<'a> ConjunctionScorer<'a> {
+    pub fn new(scorers: Vec<Box<dyn Scorer + 'a>>) -> Self {
+        assert!(!scorers.is_empty(), "Conjunction of no scorers");
+        let target = scorers.iter().map(|scorer| scorer.doc()).max().unwrap();
+        let mut scorer = Self { scorers, doc: 0 };
+        scorer.align(target);
+        scorer
+    }
+
+    // Leapfrogs the scorers to the first doc at or after the target that all
+    // of them match.
+    fn align(&mut self, mut target: DocId) -> DocId {
+        'outer: loop {
+            for scorer in self.scorers.iter_mut() {
+                let doc = scorer.advance(target);
+                if doc > target {
+                    target = doc;
+                    continue 'outer;
+                }
+            }
+            self.doc = target;
+            return target;
+        }
+    }
+}
+
+impl<'a> DocSet for ConjunctionScorer<'a> {
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn next(&mut self) -> DocId {
+        if self.doc == TERMINATED {
+            return TERMINATED;
+        }
+        self.align(self.doc + 1)
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        if self.doc >= target {
+            return self.doc;
+        }
+        self.align(target)
+    }
+}
+
+impl<'a> Scorer for ConjunctionScorer<'a> {
+    fn score(&self) -> f32 {
+        self.scorers.iter().map(|scorer| scorer.score()).sum()
+    }
+}
@@ -0,0 +1,36 @@
+use crate::DocId;
+
+use super::{DocSet, Scorer};
+
+// Gives the same score to every doc of the wrapped scorer, e.g. zero for
+// filters that must match without affecting scores.
+pub struct ConstantScorer<'a> {
+    scorer: Box<dyn Scorer + 'a>,
+    score: f32,
+}
+
+impl<'a> ConstantScorer<'a> {
+    pub fn new(scorer: Box<dyn Scorer + 'a>, score: f32) -> Self {
+        Self { scorer, score }
+    }
+}
+
+impl<'a> DocSet for ConstantScorer<'a> {
+    fn doc(&self) -> DocId {
+        self.scorer.doc()
+    }
+
+    fn next(&mut self) -> DocId {
+        self.scorer.next()
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        self.scorer.advance(target)
+    }
+}
+
+impl<'a> Scorer for ConstantScorer<'a> {
+    fn score(&self) -> f32 {
+        self.score
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{segment::SegmentReader, DocId};
+
+use super::{Collector, SegmentCollector};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TotalHits {
+    pub count: usize,
+    // Whether matches may have been skipped once the threshold was reached,
+    // in which case there are at least `count` of them.
+    pub is_lower_bound: bool,
+}
+
+// Counts the matches, exactly by default.
+//
+// With a threshold, matches are only counted exactly up to it, which lets
+// other collectors skip the docs they don't need past it.
+#[derive(Clone, Debug, Default)]
+pub struct Count {
+    threshold: Option<usize>,
+}
+
+impl Count {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_threshold(mut self, threshold: usize) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+}
+
+impl Collector for Count {
+    type Fruit = TotalHits;
+    type SegmentFruit = usize;
+    type Child<'a> = SegmentCount;
+
+    fn for_segment(&self, _segment_ord: usize, _segment_reader: &SegmentReader) -> SegmentCount {
+        SegmentCount {
+            threshold: self.threshold,
+            count: 0,
+        }
+    }
+
+    fn merge_fruits(&self, fruits: Vec<usize>) -> TotalHits {
+        let count = fruits.into_iter().sum();
+        TotalHits {
+            count,
+            is_lower_bound: self.threshold.is_some_and(|threshold| count >= threshold),
+        }
+    }
+}
+
+pub struct SegmentCount {
+    threshold: Option<usize>,
+    count: usize,
+}
+
+impl SegmentCollector for SegmentCount {
+    type Fruit = usize;
+
+    fn collect(&mut self, _doc: DocId, _score: f32) {
+        self.count += 1;
+    }
+
+    fn min_competitive_score(&self) -> f32 {
+        match self.threshold {
+            Some(threshold) if self.count >= threshold => f32::INFINITY,
+            _ => 0.0,
+        }
+    }
+
+    fn harvest(self) -> usize {
+        self.count
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{DocId, TERMINATED};
+
+use super::{DocSet, Scorer};
+
+// Matches the docs matched by at least `minimum_match` of the scorers,
+// scoring the sum of the scores of the matching ones.
+pub struct DisjunctionScorer<'a> {
+    scorers: Vec<Box<dyn Scorer + 'a>>,
+    minimum_match: usize,
+    doc: DocId,
+}
+
+impl<'a> DisjunctionScorer<'a> {
+    pub fn new(scorers: Vec<Box<dyn Scorer + 'a>>, minimum_match: usize) -> Self {
+        assert!(minimum_match >= 1, "Minimum match must be at least one");
+        let mut scorer = Self {
+            scorers,
+            minimum_match,
+            doc: 0,
+        };
+        scorer.find_match();
+        scorer
+    }
+
+    // Moves to the smallest doc matched by enough scorers, starting from the
+    // current docs of the scorers.
+    fn find_match(&mut self) -> DocId {
+        loop {
+            self.scorers.retain(|scorer| scorer.doc() != TERMINATED);
+            if self.scorers.len() < self.minimum_match {
+                self.doc = TERMINATED;
+                return TERMINATED;
+            }
+            let doc = self
+                .scorers
+                .iter()
+                .map(|scorer| scorer.doc())
+                .min()
+                .unwrap();
+            let matching = self
+                .scorers
+                .iter()
+                .filter(|scorer| scorer.doc() == doc)
+                .count();
+            if matching >= self.minimum_match {
+                self.doc = doc;
+                return doc;
+            }
+            for scorer in self.scorers.iter_mut().filter(|scorer| scorer.doc() == doc) {
+                scorer.next();
+            }
+        }
+    }
+}
+
+impl<'a> DocSet for DisjunctionScorer<'a> {
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn next(&mut self) -> DocId {
+        if self.doc == TERMINATED {
+            return TERMINATED;
+        }
+        let doc = self.doc;
+        for scorer in self.scorers.iter_mut().filter(|scorer| scorer.doc() == doc) {
+            scorer.next();
+        }
+        self.find_match()
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        if self.doc >= target {
+            return self.doc;
+        }
+        for scorer in self.scorers.iter_mut() {
+            scorer.advance(target);
+        }
+        self.find_match()
+    }
+}
+
+impl<'a> Scorer for DisjunctionScorer<'a> {
+    fn score(&self) -> f32 {
+        self.scorers
+            .iter()
+            .filter(|scorer| scorer.doc() == self.doc)
+            .map(|scorer| scorer.score())
+            .sum()
+    }
+}
@@ -0,0 +1,20 @@
+use crate::DocId;
+
+// Iterates docs in increasing order.
+//
+// A doc set is positioned on its first doc as soon as it is created, and
+// returns `TERMINATED` once exhausted.
+pub trait DocSet {
+    fn doc(&self) -> DocId;
+
+    fn next(&mut self) -> DocId;
+
+    // Moves to the first doc at or after `target`, staying on the current doc
+    // if it is not before the target.
+    fn advance(&mut self, target: DocId) -> DocId {
+        while self.doc() < target {
+            self.next();
+        }
+        self.doc()
+    }
+}
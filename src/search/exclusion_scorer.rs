@@ -0,0 +1,49 @@
+use crate::{DocId, TERMINATED};
+
+use super::{DocSet, Scorer};
+
+// Matches the docs of the scorer that the excluded doc set doesn't match.
+pub struct ExclusionScorer<'a> {
+    scorer: Box<dyn Scorer + 'a>,
+    excluded: Box<dyn Scorer + 'a>,
+}
+
+impl<'a> ExclusionScorer<'a> {
+    pub fn new(scorer: Box<dyn Scorer + 'a>, excluded: Box<dyn Scorer + 'a>) -> Self {
+        let mut scorer = Self { scorer, excluded };
+        scorer.skip_excluded();
+        scorer
+    }
+
+    fn skip_excluded(&mut self) -> DocId {
+        loop {
+            let doc = self.scorer.doc();
+            if doc == TERMINATED || self.excluded.advance(doc) != doc {
+                return doc;
+            }
+            self.scorer.next();
+        }
+    }
+}
+
+impl<'a> DocSet for ExclusionScorer<'a> {
+    fn doc(&self) -> DocId {
+        self.scorer.doc()
+    }
+
+    fn next(&mut self) -> DocId {
+        self.scorer.next();
+        self.skip_excluded()
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        self.scorer.advance(target);
+        self.skip_excluded()
+    }
+}
+
+impl<'a> Scorer for ExclusionScorer<'a> {
+    fn score(&self) -> f32 {
+        self.scorer.score()
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{
+    automata::{Automaton, LevenshteinAutomaton},
+    index::Searcher,
+    schema::Field,
+};
+
+use super::{
+    multi_term_query::{expand_terms, rewrite},
+    Query, RewriteMethod, Weight,
+};
+
+const MAX_DISTANCE: u32 = 2;
+const DEFAULT_MAX_EXPANSIONS: usize = 50;
+
+// Matches the docs containing a term within `max_distance` edits of a term,
+// counting the swap of two adjacent chars as a single edit unless
+// transpositions are disabled.
+//
+// Only the `max_expansions` closest matching terms are searched, with ties
+// broken in term order.
+pub struct FuzzyQuery {
+    field: Field,
+    term: String,
+    max_distance: u32,
+    transpositions: bool,
+    max_expansions: usize,
+    rewrite_method: RewriteMethod,
+}
+
+impl FuzzyQuery {
+    pub fn new(field: Field, term: &str, max_distance: u32) -> Self {
+        assert!(
+            max_distance <= MAX_DISTANCE,
+            "Max distance must be at most {}",
+            MAX_DISTANCE
+        );
+        Self {
+            field,
+            term: term.to_string(),
+            max_distance,
+            transpositions: true,
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+            rewrite_method: RewriteMethod::default(),
+        }
+    }
+
+    pub fn set_transpositions(mut self, transpositions: bool) -> Self {
+        self.transpositions = transpositions;
+        self
+    }
+
+    pub fn set_max_expansions(mut self, max_expansions: usize) -> Self {
+        self.max_expansions = max_expansions;
+        self
+    }
+
+    pub fn set_rewrite_method(mut self, rewrite_method: RewriteMethod) -> Self {
+        self.rewrite_method = rewrite_method;
+        self
+    }
+}
+
+impl Query for FuzzyQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        let automaton =
+            LevenshteinAutomaton::new(&self.term, self.max_distance, self.transpositions);
+        let mut terms: Vec<_> = expand_terms(searcher, self.field, &automaton)
+            .into_iter()
+            .map(|term| {
+                let state = term
+                    .chars()
+                    .fold(automaton.start(), |state, c| automaton.accept(&state, c));
+                (automaton.distance(&state).unwrap(), term)
+            })
+            .collect();
+        terms.sort_unstable();
+        terms.truncate(self.max_expansions);
+        let terms = terms.into_iter().map(|(_, term)| term).collect();
+        rewrite(searcher, self.field, terms, self.rewrite_method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{multi_term_query::search_docs, text_searcher},
+    };
+
+    use super::FuzzyQuery;
+
+    #[test]
+    fn test_simple() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::Basic,
+            &[
+                &["lucene", "lucent", "ulcene"],
+                &["lcne", "search", "lucenes"],
+            ],
+        );
+
+        let query = FuzzyQuery::new(body, "lucene", 1);
+        assert_eq!(
+            search_docs(&searcher, &query),
+            [(0, 0), (0, 1), (0, 2), (1, 2)]
+        );
+        let query = FuzzyQuery::new(body, "lucene", 1).set_transpositions(false);
+        assert_eq!(search_docs(&searcher, &query), [(0, 0), (0, 1), (1, 2)]);
+        let query = FuzzyQuery::new(body, "lucene", 2);
+        assert_eq!(
+            search_docs(&searcher, &query),
+            [(0, 0), (0, 1), (0, 2), (1, 0), (1, 2)]
+        );
+
+        // The exact term is closest, then "lucenes" and "lucent" in order.
+        let query = FuzzyQuery::new(body, "lucene", 2).set_max_expansions(2);
+        assert_eq!(search_docs(&searcher, &query), [(0, 0), (1, 2)]);
+    }
+
+    #[test]
+    fn test_scores() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::Basic,
+            &[&["lucene", "lucent lucene"]],
+        );
+        let docs = crate::search::search_all(&searcher, &FuzzyQuery::new(body, "lucene", 1));
+        assert_eq!(docs.len(), 2);
+        assert!(docs[1].2 > docs[0].2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_max_distance() {
+        let (_, body) = text_searcher::<&str>("whitespace", IndexRecordOption::Basic, &[]);
+        FuzzyQuery::new(body, "lucene", 3);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{index::Searcher, segment::SegmentReader, DocId, TERMINATED};
+
+use super::{DocSet, Query, Scorer, Weight};
+
+// Matches every doc with a score of one.
+pub struct MatchAllQuery;
+
+impl Query for MatchAllQuery {
+    fn weight(&self, _searcher: &Searcher) -> Box<dyn Weight> {
+        Box::new(MatchAllWeight)
+    }
+}
+
+struct MatchAllWeight;
+
+impl Weight for MatchAllWeight {
+    fn scorer<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Scorer + 'a>> {
+        let max_doc = segment_reader.max_doc();
+        if max_doc == 0 {
+            return None;
+        }
+        Some(Box::new(MatchAllScorer { doc: 0, max_doc }))
+    }
+}
+
+struct MatchAllScorer {
+    doc: DocId,
+    max_doc: DocId,
+}
+
+impl DocSet for MatchAllScorer {
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn next(&mut self) -> DocId {
+        self.advance(self.doc.saturating_add(1))
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        if target >= self.max_doc {
+            self.doc = TERMINATED;
+        } else if target > self.doc {
+            self.doc = target;
+        }
+        self.doc
+    }
+}
+
+impl Scorer for MatchAllScorer {
+    fn score(&self) -> f32 {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        document::Document,
+        index::IndexWriter,
+        schema::{Schema, TextOptions},
+        search::search_all,
+    };
+
+    use super::{MatchAllQuery, Query};
+
+    #[test]
+    fn test_simple() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field("title", TextOptions::default().set_indexed());
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        let searcher = writer.reader().searcher();
+        assert!(search_all(&searcher, &MatchAllQuery).is_empty());
+
+        for _ in 0..3 {
+            writer.add_document(&Document::new());
+        }
+        writer.flush();
+        let mut doc = Document::new();
+        doc.add_text(title, "hello");
+        writer.add_document(&doc);
+        let searcher = writer.reader().searcher();
+        assert_eq!(
+            search_all(&searcher, &MatchAllQuery),
+            vec![(0, 0, 1.0), (0, 1, 1.0), (0, 2, 1.0), (1, 0, 1.0)]
+        );
+
+        let weight = MatchAllQuery.weight(&searcher);
+        let mut scorer = weight.scorer(searcher.segment_reader(0)).unwrap();
+        assert_eq!(scorer.advance(2), 2);
+        assert_eq!(scorer.advance(1), 2);
+        assert_eq!(scorer.advance(3), crate::TERMINATED);
+        assert_eq!(scorer.next(), crate::TERMINATED);
+    }
+}
@@ -0,0 +1,80 @@
+mod bit_set_scorer;
+mod block_max_wand_scorer;
+mod boolean_query;
+mod collector;
+mod combined_spans;
+mod conjunction_scorer;
+mod constant_scorer;
+mod count_collector;
+mod disjunction_scorer;
+mod doc_set;
+mod exclusion_scorer;
+mod fuzzy_query;
+mod match_all_query;
+mod multi_phrase_query;
+mod multi_term_query;
+mod phrase_query;
+mod phrase_scorer;
+mod prefix_query;
+mod query;
+mod range_query;
+mod regexp_query;
+mod req_opt_scorer;
+mod scorer;
+mod span_containing_query;
+mod span_first_query;
+mod span_near_query;
+mod span_not_query;
+mod span_or_query;
+mod span_query;
+mod span_term_query;
+mod span_within_query;
+mod spans;
+mod term_query;
+mod top_docs;
+mod top_field_docs;
+mod weight;
+mod wildcard_query;
+
+pub use bit_set_scorer::BitSetScorer;
+pub use block_max_wand_scorer::BlockMaxWandScorer;
+pub use boolean_query::{BooleanQuery, Occur};
+pub use collector::{Collector, SegmentCollector};
+pub use conjunction_scorer::ConjunctionScorer;
+pub use constant_scorer::ConstantScorer;
+pub use count_collector::{Count, SegmentCount, TotalHits};
+pub use disjunction_scorer::DisjunctionScorer;
+pub use doc_set::DocSet;
+pub use exclusion_scorer::ExclusionScorer;
+pub use fuzzy_query::FuzzyQuery;
+pub use match_all_query::MatchAllQuery;
+pub use multi_phrase_query::MultiPhraseQuery;
+pub use multi_term_query::RewriteMethod;
+pub use phrase_query::{graph_phrase_query, PhraseQuery};
+pub use phrase_scorer::PhraseScorer;
+pub use prefix_query::PrefixQuery;
+pub use query::Query;
+#[cfg(test)]
+pub(crate) use query::{search_all, segmented_searcher, text_searcher};
+pub use range_query::RangeQuery;
+pub use regexp_query::RegexpQuery;
+pub use req_opt_scorer::ReqOptScorer;
+#[cfg(test)]
+pub(crate) use scorer::collect_scores;
+pub use scorer::Scorer;
+pub use span_containing_query::SpanContainingQuery;
+pub use span_first_query::SpanFirstQuery;
+pub use span_near_query::SpanNearQuery;
+pub use span_not_query::SpanNotQuery;
+pub use span_or_query::SpanOrQuery;
+pub use span_query::{SpanQuery, SpanScorer};
+pub use span_term_query::SpanTermQuery;
+pub use span_within_query::SpanWithinQuery;
+pub use spans::{Span, Spans};
+pub use term_query::{TermQuery, TermScorer};
+pub use top_docs::{ScoredDoc, SegmentTopDocs, TopDocs};
+pub use top_field_docs::{
+    FieldDoc, Order, SegmentTopFieldDocs, SortField, SortValue, TopFieldDocs,
+};
+pub use weight::Weight;
+pub use wildcard_query::WildcardQuery;
@@ -0,0 +1,137 @@
+use crate::{index::Searcher, schema::Field};
+
+use super::{phrase_scorer::PhraseWeight, Query, Weight};
+
+// A phrase query where each position accepts any of several terms, e.g. the
+// synonyms of a word, or the terms starting with the last word of a phrase
+// typed so far. Like phrase queries, it panics on fields indexed without
+// positions.
+pub struct MultiPhraseQuery {
+    field: Field,
+    terms: Vec<(u32, Vec<String>)>,
+    slop: u32,
+}
+
+impl MultiPhraseQuery {
+    pub fn new(field: Field, terms: &[&[&str]]) -> Self {
+        let terms: Vec<_> = terms
+            .iter()
+            .enumerate()
+            .map(|(position, &alternatives)| (position as u32, alternatives))
+            .collect();
+        Self::with_positions(field, &terms)
+    }
+
+    pub fn with_positions(field: Field, terms: &[(u32, &[&str])]) -> Self {
+        assert!(!terms.is_empty(), "Phrase without terms");
+        assert!(
+            terms
+                .iter()
+                .all(|(_, alternatives)| !alternatives.is_empty()),
+            "Phrase position without terms"
+        );
+        assert!(
+            terms.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "Phrase positions must be increasing"
+        );
+        Self {
+            field,
+            terms: terms
+                .iter()
+                .map(|&(position, alternatives)| {
+                    let alternatives = alternatives.iter().map(|term| term.to_string());
+                    (position, alternatives.collect())
+                })
+                .collect(),
+            slop: 0,
+        }
+    }
+
+    pub fn set_slop(mut self, slop: u32) -> Self {
+        self.slop = slop;
+        self
+    }
+
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    pub fn terms(&self) -> &[(u32, Vec<String>)] {
+        &self.terms
+    }
+
+    pub fn slop(&self) -> u32 {
+        self.slop
+    }
+}
+
+impl Query for MultiPhraseQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        Box::new(PhraseWeight::new(
+            searcher,
+            self.field,
+            self.terms.clone(),
+            self.slop,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{search_all, text_searcher},
+    };
+
+    use super::MultiPhraseQuery;
+
+    #[test]
+    fn test_simple() {
+        let (searcher, title) = text_searcher(
+            "standard",
+            IndexRecordOption::WithPositions,
+            &[&[
+                "new york city",
+                "big apple city",
+                "new yorker magazine",
+                "new jersey city",
+                "york new",
+            ]],
+        );
+        let docs = |query: &MultiPhraseQuery| -> Vec<_> {
+            search_all(&searcher, query)
+                .into_iter()
+                .map(|(_, doc, _)| doc)
+                .collect()
+        };
+
+        // Autocomplete of "new yo".
+        let query = MultiPhraseQuery::new(title, &[&["new"], &["york", "yorker"]]);
+        assert_eq!(docs(&query), [0, 2]);
+
+        let query = MultiPhraseQuery::with_positions(
+            title,
+            &[
+                (0, &["new", "big"]),
+                (1, &["york", "apple"]),
+                (2, &["city"]),
+            ],
+        );
+        assert_eq!(docs(&query), [0, 1]);
+
+        let query = MultiPhraseQuery::new(title, &[&["new", "missing"], &["missing"]]);
+        assert!(docs(&query).is_empty());
+
+        let query = MultiPhraseQuery::new(title, &[&["york", "jersey"], &["city"]]).set_slop(1);
+        assert_eq!(docs(&query), [0, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "indexed without positions")]
+    fn test_without_positions() {
+        let (searcher, title) =
+            text_searcher("standard", IndexRecordOption::Basic, &[&["new york city"]]);
+        let query = MultiPhraseQuery::new(title, &[&["york", "jersey"], &["city"]]).set_slop(1);
+        search_all(&searcher, &query);
+    }
+}
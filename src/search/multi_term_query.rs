@@ -0,0 +1,94 @@
+use crate::{
+    automata::Automaton, index::Searcher, schema::Field, segment::SegmentReader, TERMINATED,
+};
+
+use super::{BitSetScorer, BooleanQuery, Occur, Query, Scorer, TermQuery, Weight};
+
+pub(super) const DEFAULT_MAX_EXPANSIONS: usize = 1024;
+
+// How queries matching many terms, e.g. prefix or fuzzy queries, are rewritten
+// once their terms are expanded from the term dictionaries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RewriteMethod {
+    // A boolean query of should term clauses, scoring each term as usual.
+    #[default]
+    ScoringBoolean,
+    // Gives a score of one to every doc containing any of the terms, without
+    // the cost of scoring them.
+    ConstantScore,
+}
+
+// The terms of the field matched by the automaton in any segment, in order.
+pub(super) fn expand_terms<A: Automaton>(
+    searcher: &Searcher,
+    field: Field,
+    automaton: &A,
+) -> Vec<String> {
+    let mut terms: Vec<_> = searcher
+        .segment_readers()
+        .iter()
+        .flat_map(|segment_reader| segment_reader.matching_terms(field, automaton))
+        .collect();
+    terms.sort_unstable();
+    terms.dedup();
+    terms
+}
+
+pub(super) fn rewrite(
+    searcher: &Searcher,
+    field: Field,
+    terms: Vec<String>,
+    rewrite_method: RewriteMethod,
+) -> Box<dyn Weight> {
+    match rewrite_method {
+        RewriteMethod::ScoringBoolean => BooleanQuery::new(
+            terms
+                .iter()
+                .map(|term| {
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(field, term)) as Box<dyn Query>,
+                    )
+                })
+                .collect(),
+        )
+        .weight(searcher),
+        RewriteMethod::ConstantScore => Box::new(ConstantScoreWeight { field, terms }),
+    }
+}
+
+struct ConstantScoreWeight {
+    field: Field,
+    terms: Vec<String>,
+}
+
+impl Weight for ConstantScoreWeight {
+    fn scorer<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Scorer + 'a>> {
+        let mut words = vec![0u64; (segment_reader.max_doc() as usize).div_ceil(64)];
+        let mut is_empty = true;
+        for term in &self.terms {
+            let Some(mut postings) = segment_reader.postings(self.field, term) else {
+                continue;
+            };
+            while postings.doc() != TERMINATED {
+                let doc = postings.doc() as usize;
+                words[doc / 64] |= 1 << (doc % 64);
+                is_empty = false;
+                postings.next();
+            }
+        }
+        if is_empty {
+            return None;
+        }
+        Some(Box::new(BitSetScorer::new(words)))
+    }
+}
+
+// The segment ord and doc of each match.
+#[cfg(test)]
+pub(crate) fn search_docs(searcher: &Searcher, query: &dyn Query) -> Vec<(usize, crate::DocId)> {
+    super::search_all(searcher, query)
+        .into_iter()
+        .map(|(segment_ord, doc, _)| (segment_ord, doc))
+        .collect()
+}
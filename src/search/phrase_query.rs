@@ -0,0 +1,327 @@
+use crate::{analysis::Token, index::Searcher, schema::Field};
+
+use super::{phrase_scorer::PhraseWeight, BooleanQuery, Occur, Query, Weight};
+
+// Matches the docs containing the terms at the given positions relative to
+// each other. The field must be indexed with positions, or searching panics.
+//
+// With a slop, terms may also be that many moves away from their positions,
+// including out of order, and closer matches score higher.
+pub struct PhraseQuery {
+    field: Field,
+    terms: Vec<(u32, String)>,
+    slop: u32,
+}
+
+impl PhraseQuery {
+    pub fn new(field: Field, terms: &[&str]) -> Self {
+        let terms: Vec<_> = terms
+            .iter()
+            .enumerate()
+            .map(|(position, &term)| (position as u32, term))
+            .collect();
+        Self::with_positions(field, &terms)
+    }
+
+    // Positions can leave gaps, e.g. for stop words removed from the text.
+    pub fn with_positions(field: Field, terms: &[(u32, &str)]) -> Self {
+        assert!(!terms.is_empty(), "Phrase without terms");
+        assert!(
+            terms.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "Phrase positions must be increasing"
+        );
+        Self {
+            field,
+            terms: terms
+                .iter()
+                .map(|&(position, term)| (position, term.to_string()))
+                .collect(),
+            slop: 0,
+        }
+    }
+
+    pub fn set_slop(mut self, slop: u32) -> Self {
+        self.slop = slop;
+        self
+    }
+
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    pub fn terms(&self) -> &[(u32, String)] {
+        &self.terms
+    }
+
+    pub fn slop(&self) -> u32 {
+        self.slop
+    }
+}
+
+impl Query for PhraseQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        let terms = self
+            .terms
+            .iter()
+            .map(|(position, term)| (*position, vec![term.clone()]))
+            .collect();
+        Box::new(PhraseWeight::new(searcher, self.field, terms, self.slop))
+    }
+}
+
+// Matches the phrases of every path through a token graph, such as the
+// tokens of a search analyzer with a synonym graph filter, so that a
+// multi-word synonym matches the indexed words of any of its forms. The
+// position length of each token tells where its path goes on.
+pub fn graph_phrase_query(field: Field, tokens: &[Token], slop: u32) -> BooleanQuery {
+    assert!(!tokens.is_empty(), "Phrase without terms");
+    let clauses = graph_paths(tokens)
+        .into_iter()
+        .map(|terms| {
+            let query = PhraseQuery::with_positions(field, &terms).set_slop(slop);
+            (Occur::Should, Box::new(query) as Box<dyn Query>)
+        })
+        .collect();
+    BooleanQuery::new(clauses)
+}
+
+// The terms of each path through the graph, with their phrase positions.
+fn graph_paths(tokens: &[Token]) -> Vec<Vec<(u32, &str)>> {
+    let mut edges = Vec::with_capacity(tokens.len());
+    let mut position = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        // The first token is at position zero, whatever its increment.
+        if i > 0 {
+            position += token.position_increment;
+        }
+        edges.push((position, position + token.position_length.max(1), token));
+    }
+    let end = edges.iter().map(|&(_, end, _)| end).max().unwrap_or(0);
+
+    let mut paths = Vec::new();
+    collect_paths(&edges, end, 0, 0, &mut Vec::new(), &mut paths);
+    paths
+}
+
+// Follows the edges from `node`, where the path has its next term at
+// `position` unless positions are skipped, e.g. for removed stop words.
+fn collect_paths<'a>(
+    edges: &[(u32, u32, &'a Token)],
+    end: u32,
+    node: u32,
+    position: u32,
+    terms: &mut Vec<(u32, &'a str)>,
+    paths: &mut Vec<Vec<(u32, &'a str)>>,
+) {
+    if node == end {
+        paths.push(terms.clone());
+        return;
+    }
+    // Paths that end before the others are dropped.
+    let Some(start) = edges
+        .iter()
+        .map(|&(start, _, _)| start)
+        .filter(|&start| start >= node)
+        .min()
+    else {
+        return;
+    };
+    let position = position + start - node;
+    for &(edge_start, edge_end, token) in edges {
+        if edge_start == start {
+            terms.push((position, token.text.as_str()));
+            collect_paths(edges, end, edge_end, position + 1, terms, paths);
+            terms.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        analysis::{
+            Analyzer, AnalyzerRegistry, SynonymGraphFilter, SynonymMap, Token, WhitespaceTokenizer,
+        },
+        document::Document,
+        index::{IndexWriter, Searcher},
+        schema::{Field, IndexRecordOption, Schema, TextOptions},
+        search::{search_all, segmented_searcher, text_searcher},
+        DocId,
+    };
+
+    use super::{graph_paths, graph_phrase_query, PhraseQuery, Query};
+
+    fn docs(searcher: &Searcher, query: &dyn Query) -> Vec<(usize, DocId)> {
+        search_all(searcher, query)
+            .into_iter()
+            .map(|(segment_ord, doc, _)| (segment_ord, doc))
+            .collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        let (searcher, title) = text_searcher(
+            "english",
+            IndexRecordOption::WithPositions,
+            &[
+                &["quick brown fox", "brown quick fox", "quick fox"],
+                &[
+                    "the quick and the brown fox",
+                    "quick brown and quick brown fox",
+                ],
+            ],
+        );
+
+        let query = PhraseQuery::new(title, &["quick", "brown"]);
+        assert_eq!(docs(&searcher, &query), [(0, 0), (1, 1)]);
+        let query = PhraseQuery::new(title, &["brown", "fox"]);
+        assert_eq!(docs(&searcher, &query), [(0, 0), (1, 0), (1, 1)]);
+        let query = PhraseQuery::new(title, &["quick", "fox"]);
+        assert_eq!(docs(&searcher, &query), [(0, 1), (0, 2)]);
+        let query = PhraseQuery::new(title, &["fox", "quick"]);
+        assert!(docs(&searcher, &query).is_empty());
+        let query = PhraseQuery::new(title, &["quick", "missing"]);
+        assert!(docs(&searcher, &query).is_empty());
+
+        // Stop words leave gaps in positions.
+        let query = PhraseQuery::with_positions(title, &[(0, "quick"), (3, "brown")]);
+        assert_eq!(docs(&searcher, &query), [(1, 0)]);
+
+        let docs = search_all(&searcher, &PhraseQuery::new(title, &["quick", "brown"]));
+        assert!(docs[1].2 > docs[0].2);
+    }
+
+    #[test]
+    fn test_slop() {
+        let (searcher, title) = text_searcher(
+            "english",
+            IndexRecordOption::WithPositions,
+            &[
+                &["quick brown fox", "quick red fox", "quick big red fox"],
+                &["fox quick", "fox is quick"],
+            ],
+        );
+
+        let query = PhraseQuery::new(title, &["quick", "fox"]).set_slop(1);
+        let docs = search_all(&searcher, &query);
+        assert_eq!(
+            docs.iter().map(|d| (d.0, d.1)).collect::<Vec<_>>(),
+            [(0, 0), (0, 1)]
+        );
+
+        let query = PhraseQuery::new(title, &["quick", "fox"]).set_slop(2);
+        let docs = search_all(&searcher, &query);
+        assert_eq!(
+            docs.iter().map(|d| (d.0, d.1)).collect::<Vec<_>>(),
+            [(0, 0), (0, 1), (0, 2), (1, 0)]
+        );
+        // Closer matches score higher.
+        assert!(docs[0].2 > docs[2].2);
+
+        let query = PhraseQuery::new(title, &["quick", "fox"]).set_slop(3);
+        assert_eq!(search_all(&searcher, &query).len(), 5);
+    }
+
+    #[test]
+    fn test_synonyms() {
+        // Synonyms are expanded at search time into a graph, each path of
+        // which is a phrase of the indexed words.
+        let synonym_map = SynonymMap::load("wifi, wireless network\nny, new york".as_bytes());
+        let mut analyzer_registry = AnalyzerRegistry::default();
+        analyzer_registry.register(
+            "synonyms",
+            Analyzer::new(WhitespaceTokenizer)
+                .add_token_filter(SynonymGraphFilter::new(Arc::new(synonym_map.unwrap()))),
+        );
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("whitespace")
+                .set_search_analyzer("synonyms")
+                .set_index_record_option(IndexRecordOption::WithPositions),
+        );
+        let writer = IndexWriter::with_analyzer_registry(builder.build(), analyzer_registry);
+        let reader = writer.reader();
+        let documents: Vec<_> = ["free wifi ok", "visit new york city", "ny city"]
+            .into_iter()
+            .map(|text| {
+                let mut doc = Document::new();
+                doc.add_text(title, text);
+                doc
+            })
+            .collect();
+        let searcher = segmented_searcher(writer, &[&documents]);
+
+        for (text, expected) in [
+            ("wifi ok", &[(0, 0)][..]),
+            ("free wireless network ok", &[(0, 0)]),
+            ("wireless ok", &[]),
+            ("new york city", &[(0, 1), (0, 2)]),
+            ("ny city", &[(0, 1), (0, 2)]),
+            ("visit ny", &[(0, 1)]),
+            ("york city", &[(0, 1)]),
+            ("ny york", &[]),
+        ] {
+            let query = graph_phrase_query(title, &reader.search_tokens(title, text), 0);
+            assert_eq!(docs(&searcher, &query), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_graph_paths() {
+        // Stacked tokens, multi-position ones and gaps.
+        let token = |text: &str, position_increment, position_length| Token {
+            position_increment,
+            position_length,
+            ..Token::new(text.to_string(), 0, 0)
+        };
+        let tokens = [
+            token("ny", 1, 2),
+            token("new", 0, 1),
+            token("york", 1, 1),
+            token("city", 2, 1),
+        ];
+        assert_eq!(
+            graph_paths(&tokens),
+            [
+                vec![(0, "ny"), (2, "city")],
+                vec![(0, "new"), (1, "york"), (3, "city")],
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "indexed without positions")]
+    fn test_exact_without_positions() {
+        let (searcher, title) = text_searcher(
+            "standard",
+            IndexRecordOption::Basic,
+            &[&["quick brown fox"]],
+        );
+        search_all(&searcher, &PhraseQuery::new(title, &["quick", "brown"]));
+    }
+
+    #[test]
+    #[should_panic(expected = "indexed without positions")]
+    fn test_sloppy_without_positions() {
+        let (searcher, title) = text_searcher(
+            "standard",
+            IndexRecordOption::Basic,
+            &[&["quick brown fox"]],
+        );
+        search_all(
+            &searcher,
+            &PhraseQuery::new(title, &["quick", "fox"]).set_slop(1),
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_decreasing_positions() {
+        PhraseQuery::with_positions(Field::from_field_id(0), &[(1, "a"), (0, "b")]);
+    }
+}
@@ -0,0 +1,318 @@
+use crate::{
+    index::Searcher,
+    postings::{PostingsIterator, UnionPostingsIterator},
+    schema::Field,
+    segment::SegmentReader,
+    similarity::SimilarityScorer,
+    DocId, TERMINATED,
+};
+
+use super::{DocSet, Scorer, Weight};
+
+// The weight of phrase queries, where each position of the phrase accepts one
+// or more alternative terms.
+pub(super) struct PhraseWeight {
+    field: Field,
+    terms: Vec<(u32, Vec<String>)>,
+    slop: u32,
+    similarity_scorer: Box<dyn SimilarityScorer>,
+}
+
+impl PhraseWeight {
+    pub(super) fn new(
+        searcher: &Searcher,
+        field: Field,
+        terms: Vec<(u32, Vec<String>)>,
+        slop: u32,
+    ) -> Self {
+        let term_statistics: Vec<_> = terms
+            .iter()
+            .flat_map(|(_, alternatives)| alternatives)
+            .map(|term| searcher.term_statistics(field, term))
+            .collect();
+        let similarity_scorer = searcher.similarity().scorer(
+            1.0,
+            &searcher.collection_statistics(field),
+            &term_statistics,
+        );
+        Self {
+            field,
+            terms,
+            slop,
+            similarity_scorer,
+        }
+    }
+}
+
+impl Weight for PhraseWeight {
+    // Panics if the field is indexed without positions, with or without a
+    // slop, rather than matching nothing.
+    fn scorer<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Scorer + 'a>> {
+        let field_entry = segment_reader.schema().field_entry(self.field);
+        if let Some(record_option) = field_entry.field_type().index_record_option() {
+            assert!(
+                record_option.has_positions(),
+                "Field `{}` is indexed without positions",
+                field_entry.name()
+            );
+        }
+        let mut postings = Vec::with_capacity(self.terms.len());
+        for (offset, alternatives) in &self.terms {
+            let mut alternative_postings: Vec<_> = alternatives
+                .iter()
+                .filter_map(|term| segment_reader.postings(self.field, term))
+                .filter(|postings| postings.doc() != TERMINATED)
+                .collect();
+            let position_postings = match alternative_postings.len() {
+                0 => return None,
+                1 => alternative_postings.pop().unwrap(),
+                _ => Box::new(UnionPostingsIterator::new(alternative_postings)),
+            };
+            postings.push((*offset, position_postings));
+        }
+        let has_repeats = self.terms.iter().enumerate().any(|(i, (_, alternatives))| {
+            self.terms[i + 1..]
+                .iter()
+                .any(|(_, others)| others.iter().any(|term| alternatives.contains(term)))
+        });
+        Some(Box::new(PhraseScorer::new(
+            postings,
+            self.slop,
+            has_repeats,
+            segment_reader,
+            self.field,
+            self.similarity_scorer.as_ref(),
+        )))
+    }
+}
+
+// Matches the docs where the terms occur at their offsets relative to each
+// other, or within `slop` moves of them.
+//
+// Exact matches each count for one in the freq. Sloppy matches count for
+// `1 / (1 + distance)`, where the distance is how many moves the terms are
+// from their offsets, so closer matches score higher.
+pub struct PhraseScorer<'a> {
+    postings: Vec<(u32, Box<dyn PostingsIterator + 'a>)>,
+    slop: u32,
+    has_repeats: bool,
+    segment_reader: &'a SegmentReader,
+    field: Field,
+    similarity_scorer: &'a dyn SimilarityScorer,
+    doc: DocId,
+    freq: f32,
+}
+
+impl<'a> PhraseScorer<'a> {
+    fn new(
+        postings: Vec<(u32, Box<dyn PostingsIterator + 'a>)>,
+        slop: u32,
+        has_repeats: bool,
+        segment_reader: &'a SegmentReader,
+        field: Field,
+        similarity_scorer: &'a dyn SimilarityScorer,
+    ) -> Self {
+        let mut scorer = Self {
+            postings,
+            slop,
+            has_repeats,
+            segment_reader,
+            field,
+            similarity_scorer,
+            doc: 0,
+            freq: 0.0,
+        };
+        scorer.find_match(0);
+        scorer
+    }
+
+    // The phrase freq of the current doc.
+    pub fn freq(&self) -> f32 {
+        self.freq
+    }
+
+    fn find_match(&mut self, mut target: DocId) -> DocId {
+        loop {
+            let doc = self.align(target);
+            if doc == TERMINATED {
+                self.doc = TERMINATED;
+                self.freq = 0.0;
+                return TERMINATED;
+            }
+            let freq = self.phrase_freq();
+            if freq > 0.0 {
+                self.doc = doc;
+                self.freq = freq;
+                return doc;
+            }
+            target = doc + 1;
+        }
+    }
+
+    // Leapfrogs the postings to the first doc at or after the target that
+    // contains all the terms.
+    fn align(&mut self, mut target: DocId) -> DocId {
+        'outer: loop {
+            for (_, postings) in self.postings.iter_mut() {
+                let doc = postings.advance(target);
+                if doc > target {
+                    target = doc;
+                    continue 'outer;
+                }
+            }
+            return target;
+        }
+    }
+
+    fn phrase_freq(&mut self) -> f32 {
+        // The positions of each term minus its offset, which are equal for
+        // terms of the same exact match.
+        let starts: Vec<Vec<i64>> = self
+            .postings
+            .iter_mut()
+            .map(|(offset, postings)| {
+                postings
+                    .positions()
+                    .iter()
+                    .map(|&position| position as i64 - *offset as i64)
+                    .collect()
+            })
+            .collect();
+        if self.slop == 0 {
+            exact_freq(&starts) as f32
+        } else {
+            let offsets: Vec<i64> = self
+                .postings
+                .iter()
+                .map(|&(offset, _)| offset as i64)
+                .collect();
+            sloppy_freq(&starts, &offsets, self.slop, self.has_repeats)
+        }
+    }
+}
+
+// Counts the starts common to every term.
+fn exact_freq(starts: &[Vec<i64>]) -> u32 {
+    let mut indexes = vec![0; starts.len()];
+    let mut freq = 0;
+    'starts: for &start in &starts[0] {
+        for (term_starts, index) in starts[1..].iter().zip(indexes[1..].iter_mut()) {
+            while *index < term_starts.len() && term_starts[*index] < start {
+                *index += 1;
+            }
+            if *index == term_starts.len() {
+                break 'starts;
+            }
+            if term_starts[*index] != start {
+                continue 'starts;
+            }
+        }
+        freq += 1;
+    }
+    freq
+}
+
+// Sweeps windows over the starts of the terms, always moving the term with
+// the smallest start. A window is only counted once it can't get any smaller
+// by moving that term, so that overlapping occurrences of a term don't count
+// twice for the same match.
+fn sloppy_freq(starts: &[Vec<i64>], offsets: &[i64], slop: u32, has_repeats: bool) -> f32 {
+    let mut indexes = vec![0; starts.len()];
+    let mut freq = 0.0;
+    loop {
+        let (min_term, start) = (0..starts.len())
+            .map(|term| (term, starts[term][indexes[term]]))
+            .min_by_key(|&(term, start)| (start, term))
+            .unwrap();
+        let end = (0..starts.len())
+            .map(|term| starts[term][indexes[term]])
+            .max()
+            .unwrap();
+        let second_start = (0..starts.len())
+            .filter(|&term| term != min_term)
+            .map(|term| starts[term][indexes[term]])
+            .min()
+            .unwrap_or(i64::MAX);
+        let next_start = starts[min_term].get(indexes[min_term] + 1);
+        let is_minimal = next_start.is_none_or(|&next_start| next_start > second_start);
+        let distance = end - start;
+        // A term repeated in the phrase must match distinct positions.
+        let is_valid = !has_repeats || {
+            let mut positions: Vec<_> = (0..starts.len())
+                .map(|term| starts[term][indexes[term]] + offsets[term])
+                .collect();
+            positions.sort_unstable();
+            positions.windows(2).all(|pair| pair[0] != pair[1])
+        };
+        if is_minimal && is_valid && distance <= slop as i64 {
+            freq += 1.0 / (1.0 + distance as f32);
+        }
+        indexes[min_term] += 1;
+        if indexes[min_term] == starts[min_term].len() {
+            return freq;
+        }
+    }
+}
+
+impl<'a> DocSet for PhraseScorer<'a> {
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn next(&mut self) -> DocId {
+        if self.doc == TERMINATED {
+            return TERMINATED;
+        }
+        self.find_match(self.doc + 1)
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        if self.doc >= target {
+            return self.doc;
+        }
+        self.find_match(target)
+    }
+}
+
+impl<'a> Scorer for PhraseScorer<'a> {
+    fn score(&self) -> f32 {
+        let field_length = self.segment_reader.field_length(self.field, self.doc);
+        self.similarity_scorer.score(self.freq, field_length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{exact_freq, sloppy_freq};
+
+    #[test]
+    fn test_exact_freq() {
+        assert_eq!(exact_freq(&[vec![0, 3, 7]]), 3);
+        assert_eq!(
+            exact_freq(&[vec![0, 3, 7], vec![1, 3, 7], vec![3, 7, 9]]),
+            2
+        );
+        assert_eq!(exact_freq(&[vec![0, 3], vec![1, 2]]), 0);
+    }
+
+    #[test]
+    fn test_sloppy_freq() {
+        // "a b" in "a x b": one move apart.
+        assert_eq!(sloppy_freq(&[vec![0], vec![1]], &[0, 1], 1, false), 0.5);
+        assert_eq!(sloppy_freq(&[vec![0], vec![1]], &[0, 1], 0, false), 0.0);
+        // "a b" in "a a b": only the closest "a" counts.
+        assert_eq!(sloppy_freq(&[vec![0, 1], vec![1]], &[0, 1], 2, false), 1.0);
+        // "a b" in "b a": reversed terms are two moves apart.
+        assert_eq!(sloppy_freq(&[vec![1], vec![-1]], &[0, 1], 1, false), 0.0);
+        assert_eq!(
+            sloppy_freq(&[vec![1], vec![-1]], &[0, 1], 2, false),
+            1.0 / 3.0
+        );
+        // "a a" in "a": a repeated term can't match the same position twice.
+        assert_eq!(sloppy_freq(&[vec![0], vec![-1]], &[0, 1], 2, true), 0.0);
+        assert_eq!(
+            sloppy_freq(&[vec![0, 1], vec![-1, 0]], &[0, 1], 1, true),
+            1.0
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{automata::PrefixAutomaton, index::Searcher, schema::Field};
+
+use super::{
+    multi_term_query::{expand_terms, rewrite, DEFAULT_MAX_EXPANSIONS},
+    Query, RewriteMethod, Weight,
+};
+
+// Matches the docs containing a term starting with a prefix.
+//
+// Only the first `max_expansions` matching terms in term order are searched.
+pub struct PrefixQuery {
+    field: Field,
+    prefix: String,
+    max_expansions: usize,
+    rewrite_method: RewriteMethod,
+}
+
+impl PrefixQuery {
+    pub fn new(field: Field, prefix: &str) -> Self {
+        Self {
+            field,
+            prefix: prefix.to_string(),
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+            rewrite_method: RewriteMethod::default(),
+        }
+    }
+
+    pub fn set_max_expansions(mut self, max_expansions: usize) -> Self {
+        self.max_expansions = max_expansions;
+        self
+    }
+
+    pub fn set_rewrite_method(mut self, rewrite_method: RewriteMethod) -> Self {
+        self.rewrite_method = rewrite_method;
+        self
+    }
+}
+
+impl Query for PrefixQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        let automaton = PrefixAutomaton::new(&self.prefix);
+        let mut terms = expand_terms(searcher, self.field, &automaton);
+        terms.truncate(self.max_expansions);
+        rewrite(searcher, self.field, terms, self.rewrite_method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{multi_term_query::search_docs, search_all, text_searcher, RewriteMethod},
+    };
+
+    use super::PrefixQuery;
+
+    #[test]
+    fn test_simple() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::Basic,
+            &[
+                &["apple pie", "banana", "apricot jam"],
+                &["application", "grape"],
+            ],
+        );
+
+        let query = PrefixQuery::new(body, "ap");
+        assert_eq!(search_docs(&searcher, &query), [(0, 0), (0, 2), (1, 0)]);
+        assert!(search_docs(&searcher, &PrefixQuery::new(body, "x")).is_empty());
+
+        // "apple" and "application" come first in term order.
+        let query = PrefixQuery::new(body, "ap").set_max_expansions(2);
+        assert_eq!(search_docs(&searcher, &query), [(0, 0), (1, 0)]);
+
+        let query = PrefixQuery::new(body, "ap").set_rewrite_method(RewriteMethod::ConstantScore);
+        assert_eq!(
+            search_all(&searcher, &query),
+            [(0, 0, 1.0), (0, 2, 1.0), (1, 0, 1.0)]
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use crate::index::Searcher;
+
+use super::Weight;
+
+pub trait Query: Send + Sync {
+    // Terms are matched as they are given, so query text must have been
+    // analyzed already, e.g. with `IndexReader::search_tokens`.
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight>;
+}
+
+// Runs the query over every segment, returning the segment ord, doc and score
+// of each match.
+#[cfg(test)]
+pub(crate) fn search_all(
+    searcher: &Searcher,
+    query: &dyn Query,
+) -> Vec<(usize, crate::DocId, f32)> {
+    let weight = query.weight(searcher);
+    let mut docs = vec![];
+    for (segment_ord, segment_reader) in searcher.segment_readers().iter().enumerate() {
+        if let Some(mut scorer) = weight.scorer(segment_reader) {
+            for (doc, score) in super::collect_scores(&mut *scorer) {
+                docs.push((segment_ord, doc, score));
+            }
+        }
+    }
+    docs
+}
+
+// A searcher over the docs, with a segment for each group of docs, all of
+// them flushed but the last.
+#[cfg(test)]
+pub(crate) fn segmented_searcher(
+    mut writer: crate::index::IndexWriter,
+    segments: &[&[crate::document::Document]],
+) -> Searcher {
+    for (i, docs) in segments.iter().enumerate() {
+        if i > 0 {
+            writer.flush();
+        }
+        for doc in *docs {
+            writer.add_document(doc);
+        }
+    }
+    writer.reader().searcher()
+}
+
+// A searcher over a single text field, indexed with the analyzer and record
+// option, with a doc for each text and segments as above.
+#[cfg(test)]
+pub(crate) fn text_searcher<T: AsRef<str>>(
+    analyzer: &str,
+    record_option: crate::schema::IndexRecordOption,
+    segments: &[&[T]],
+) -> (Searcher, crate::schema::Field) {
+    use crate::{
+        document::Document,
+        index::IndexWriter,
+        schema::{Schema, TextOptions},
+    };
+
+    let mut builder = Schema::builder();
+    let body = builder.add_text_field(
+        "body",
+        TextOptions::default()
+            .set_indexed()
+            .set_analyzer(analyzer)
+            .set_index_record_option(record_option),
+    );
+    let segments: Vec<Vec<Document>> = segments
+        .iter()
+        .map(|texts| {
+            texts
+                .iter()
+                .map(|text| {
+                    let mut doc = Document::new();
+                    doc.add_text(body, text.as_ref());
+                    doc
+                })
+                .collect()
+        })
+        .collect();
+    let segments: Vec<&[Document]> = segments.iter().map(Vec::as_slice).collect();
+    let writer = IndexWriter::new(builder.build());
+    (segmented_searcher(writer, &segments), body)
+}
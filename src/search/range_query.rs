@@ -0,0 +1,295 @@
+use std::ops::{Bound, RangeInclusive};
+
+use crate::{
+    document::Value,
+    index::Searcher,
+    schema::{Field, FieldEntry},
+    segment::SegmentReader,
+};
+
+use super::{BitSetScorer, Query, Scorer, Weight};
+
+// Matches the docs with a point of a numeric field within a range in every
+// dimension, with a score of one.
+//
+// Bounds must have the type of the field, and there must be a range for each
+// of its dimensions, or the query panics when searched.
+#[derive(Clone, Debug)]
+pub struct RangeQuery {
+    field: Field,
+    ranges: Vec<(Bound<Value>, Bound<Value>)>,
+}
+
+impl RangeQuery {
+    pub fn new<V: Into<Value>>(field: Field, lower: Bound<V>, upper: Bound<V>) -> Self {
+        Self::with_ranges(field, vec![(lower, upper)])
+    }
+
+    // A range for each dimension of a point field.
+    pub fn with_ranges<V: Into<Value>>(field: Field, ranges: Vec<(Bound<V>, Bound<V>)>) -> Self {
+        let ranges = ranges
+            .into_iter()
+            .map(|(lower, upper)| (lower.map(Into::into), upper.map(Into::into)))
+            .collect();
+        Self { field, ranges }
+    }
+
+    pub fn field(&self) -> Field {
+        self.field
+    }
+}
+
+fn to_sortable(field_entry: &FieldEntry, value: &Value) -> u64 {
+    value
+        .to_field_sortable_u64(field_entry.field_type())
+        .unwrap_or_else(|| {
+            panic!(
+                "Range bound {:?} doesn't have the type of field `{}`",
+                value,
+                field_entry.name()
+            )
+        })
+}
+
+// Converts the bounds to an inclusive range of sortable u64s, which is empty
+// when no number is within the bounds.
+fn to_sortable_range(
+    field_entry: &FieldEntry,
+    lower: &Bound<Value>,
+    upper: &Bound<Value>,
+) -> RangeInclusive<u64> {
+    let empty = RangeInclusive::new(1, 0);
+    let start = match lower {
+        Bound::Included(value) => to_sortable(field_entry, value),
+        Bound::Excluded(value) => match to_sortable(field_entry, value).checked_add(1) {
+            Some(start) => start,
+            None => return empty,
+        },
+        Bound::Unbounded => 0,
+    };
+    let end = match upper {
+        Bound::Included(value) => to_sortable(field_entry, value),
+        Bound::Excluded(value) => match to_sortable(field_entry, value).checked_sub(1) {
+            Some(end) => end,
+            None => return empty,
+        },
+        Bound::Unbounded => u64::MAX,
+    };
+    start..=end
+}
+
+impl Query for RangeQuery {
+    // Panics if the field isn't indexed as points, if the bounds don't have
+    // its type, or if there isn't a range for each of its dimensions.
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        let field_entry = searcher.schema().field_entry(self.field);
+        let dimensions = field_entry
+            .field_type()
+            .point_dimensions()
+            .unwrap_or_else(|| panic!("Field `{}` is not indexed as points", field_entry.name()));
+        assert_eq!(
+            self.ranges.len(),
+            dimensions,
+            "Field `{}` needs a range for each of its dimensions",
+            field_entry.name()
+        );
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|(lower, upper)| to_sortable_range(field_entry, lower, upper))
+            .collect();
+        Box::new(RangeWeight {
+            field: self.field,
+            ranges,
+        })
+    }
+}
+
+struct RangeWeight {
+    field: Field,
+    ranges: Vec<RangeInclusive<u64>>,
+}
+
+impl Weight for RangeWeight {
+    fn scorer<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Scorer + 'a>> {
+        if self.ranges.iter().any(|range| range.is_empty()) {
+            return None;
+        }
+        let mut words = vec![0u64; (segment_reader.max_doc() as usize).div_ceil(64)];
+        let mut is_empty = true;
+        segment_reader.visit_points(self.field, &self.ranges, |doc| {
+            words[doc as usize / 64] |= 1 << (doc % 64);
+            is_empty = false;
+        });
+        if is_empty {
+            return None;
+        }
+        Some(Box::new(BitSetScorer::new(words)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use crate::{
+        document::{DateTime, Document},
+        index::IndexWriter,
+        schema::{NumericOptions, Schema},
+        search::search_all,
+    };
+
+    use super::RangeQuery;
+
+    fn docs(searcher: &crate::index::Searcher, query: &RangeQuery) -> Vec<(usize, u32)> {
+        search_all(searcher, query)
+            .into_iter()
+            .map(|(segment_ord, doc, score)| {
+                assert_eq!(score, 1.0);
+                (segment_ord, doc)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        let mut builder = Schema::builder();
+        let price = builder.add_f64_field("price", NumericOptions::default().set_indexed());
+        let stock = builder.add_i64_field("stock", NumericOptions::default().set_indexed());
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        for (i, value) in [9.5, -2.0, 100.0, 9.5].into_iter().enumerate() {
+            if i == 2 {
+                writer.flush();
+            }
+            let mut doc = Document::new();
+            doc.add_f64(price, value);
+            doc.add_i64(stock, i as i64 - 1);
+            writer.add_document(&doc);
+        }
+        // Values of another type than the field are ignored.
+        let mut doc = Document::new();
+        doc.add_u64(price, 10);
+        doc.add_text(stock, "3");
+        writer.add_document(&doc);
+        let searcher = writer.reader().searcher();
+
+        let query = RangeQuery::new(price, Bound::Included(9.5), Bound::Excluded(100.0));
+        assert_eq!(docs(&searcher, &query), [(0, 0), (1, 1)]);
+        let query = RangeQuery::new(price, Bound::Excluded(9.5), Bound::Unbounded);
+        assert_eq!(docs(&searcher, &query), [(1, 0)]);
+        let query = RangeQuery::new(price, Bound::Unbounded, Bound::Included(0.0));
+        assert_eq!(docs(&searcher, &query), [(0, 1)]);
+        let query = RangeQuery::new(price, Bound::Excluded(9.5), Bound::Excluded(9.5));
+        assert!(docs(&searcher, &query).is_empty());
+
+        let query = RangeQuery::new(stock, Bound::Included(-1i64), Bound::Included(0));
+        assert_eq!(docs(&searcher, &query), [(0, 0), (0, 1)]);
+        let query = RangeQuery::new(stock, Bound::Unbounded, Bound::Excluded(i64::MIN));
+        assert!(docs(&searcher, &query).is_empty());
+        let query = RangeQuery::new::<i64>(stock, Bound::Unbounded, Bound::Unbounded);
+        assert_eq!(docs(&searcher, &query).len(), 4);
+    }
+
+    #[test]
+    fn test_dates() {
+        let mut builder = Schema::builder();
+        let created = builder.add_date_field("created", NumericOptions::default().set_indexed());
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        for secs in [0, 3600, 7200, 7200, 86400] {
+            let mut doc = Document::new();
+            doc.add_date(created, DateTime::from_timestamp_secs(secs));
+            writer.add_document(&doc);
+        }
+        writer.flush();
+        let searcher = writer.reader().searcher();
+
+        let query = RangeQuery::new(
+            created,
+            Bound::Included(DateTime::from_timestamp_millis(3_600_000)),
+            Bound::Excluded(DateTime::from_timestamp_secs(86400)),
+        );
+        assert_eq!(docs(&searcher, &query), [(0, 1), (0, 2), (0, 3)]);
+    }
+
+    #[test]
+    fn test_points() {
+        let mut builder = Schema::builder();
+        let location = builder.add_i64_field(
+            "location",
+            NumericOptions::default().set_indexed().set_dimensions(2),
+        );
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        let points: [[i64; 2]; 4] = [[0, 0], [10, 5], [-3, 7], [10, 20]];
+        for (i, point) in points.into_iter().enumerate() {
+            if i == 2 {
+                writer.flush();
+            }
+            let mut doc = Document::new();
+            doc.add_point(location, point);
+            // A doc with several points matches if any of them does.
+            if i == 3 {
+                doc.add_point(location, [-5i64, -5]);
+            }
+            writer.add_document(&doc);
+        }
+        // Points with another number of dimensions are ignored.
+        let mut doc = Document::new();
+        doc.add_i64(location, 0);
+        writer.add_document(&doc);
+        let searcher = writer.reader().searcher();
+
+        let query = RangeQuery::with_ranges(
+            location,
+            vec![
+                (Bound::Included(-5i64), Bound::Included(10)),
+                (Bound::Included(0), Bound::Excluded(20)),
+            ],
+        );
+        assert_eq!(docs(&searcher, &query), [(0, 0), (0, 1), (1, 0)]);
+        let query = RangeQuery::with_ranges(
+            location,
+            vec![
+                (Bound::Unbounded, Bound::Excluded(0i64)),
+                (Bound::Unbounded, Bound::Excluded(0)),
+            ],
+        );
+        assert_eq!(docs(&searcher, &query), [(1, 1)]);
+    }
+
+    fn price_searcher() -> (crate::index::Searcher, crate::schema::Field) {
+        let mut builder = Schema::builder();
+        let price = builder.add_f64_field("price", NumericOptions::default().set_indexed());
+        let mut writer = IndexWriter::new(builder.build());
+        let mut doc = Document::new();
+        doc.add_f64(price, 9.5);
+        writer.add_document(&doc);
+        (writer.reader().searcher(), price)
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't have the type of field `price`")]
+    fn test_bound_type() {
+        let (searcher, price) = price_searcher();
+        docs(
+            &searcher,
+            &RangeQuery::new(price, Bound::Included(9u64), Bound::Unbounded),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "needs a range for each of its dimensions")]
+    fn test_dimensions() {
+        let (searcher, price) = price_searcher();
+        let range = (Bound::Included(0.0), Bound::Included(10.0));
+        docs(
+            &searcher,
+            &RangeQuery::with_ranges(price, vec![range, range]),
+        );
+    }
+}
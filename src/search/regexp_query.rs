@@ -0,0 +1,80 @@
+use crate::{
+    automata::{BuildError, RegexAutomaton},
+    index::Searcher,
+    schema::Field,
+};
+
+use super::{
+    multi_term_query::{expand_terms, rewrite, DEFAULT_MAX_EXPANSIONS},
+    Query, RewriteMethod, Weight,
+};
+
+// Matches the docs containing a term matching a regular expression as a
+// whole, with the syntax of the `regex` crate.
+//
+// Only the first `max_expansions` matching terms in term order are searched.
+pub struct RegexpQuery {
+    field: Field,
+    automaton: RegexAutomaton,
+    max_expansions: usize,
+    rewrite_method: RewriteMethod,
+}
+
+impl RegexpQuery {
+    pub fn new(field: Field, pattern: &str) -> Result<Self, Box<BuildError>> {
+        Ok(Self {
+            field,
+            automaton: RegexAutomaton::new(pattern)?,
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+            rewrite_method: RewriteMethod::default(),
+        })
+    }
+
+    pub fn set_max_expansions(mut self, max_expansions: usize) -> Self {
+        self.max_expansions = max_expansions;
+        self
+    }
+
+    pub fn set_rewrite_method(mut self, rewrite_method: RewriteMethod) -> Self {
+        self.rewrite_method = rewrite_method;
+        self
+    }
+}
+
+impl Query for RegexpQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        let mut terms = expand_terms(searcher, self.field, &self.automaton);
+        terms.truncate(self.max_expansions);
+        rewrite(searcher, self.field, terms, self.rewrite_method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{multi_term_query::search_docs, text_searcher},
+    };
+
+    use super::RegexpQuery;
+
+    #[test]
+    fn test_simple() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::Basic,
+            &[&["color", "colour", "collar"], &["colours", "cooler"]],
+        );
+
+        let query = RegexpQuery::new(body, "colou?r").unwrap();
+        assert_eq!(search_docs(&searcher, &query), [(0, 0), (0, 1)]);
+        let query = RegexpQuery::new(body, "co[a-z]+r").unwrap();
+        assert_eq!(
+            search_docs(&searcher, &query),
+            [(0, 0), (0, 1), (0, 2), (1, 1)]
+        );
+        let query = RegexpQuery::new(body, "c.*s").unwrap();
+        assert_eq!(search_docs(&searcher, &query), [(1, 0)]);
+        assert!(RegexpQuery::new(body, "colo(u").is_err());
+    }
+}
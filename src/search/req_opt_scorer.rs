@@ -0,0 +1,51 @@
+use crate::DocId;
+
+use super::{DocSet, Scorer};
+
+// Matches the docs of the required scorer, adding the score of the optional
+// scorer to the docs it matches too.
+pub struct ReqOptScorer<'a> {
+    required: Box<dyn Scorer + 'a>,
+    optional: Box<dyn Scorer + 'a>,
+}
+
+impl<'a> ReqOptScorer<'a> {
+    pub fn new(required: Box<dyn Scorer + 'a>, optional: Box<dyn Scorer + 'a>) -> Self {
+        let mut scorer = Self { required, optional };
+        scorer.align_optional();
+        scorer
+    }
+
+    fn align_optional(&mut self) -> DocId {
+        let doc = self.required.doc();
+        self.optional.advance(doc);
+        doc
+    }
+}
+
+impl<'a> DocSet for ReqOptScorer<'a> {
+    fn doc(&self) -> DocId {
+        self.required.doc()
+    }
+
+    fn next(&mut self) -> DocId {
+        self.required.next();
+        self.align_optional()
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        self.required.advance(target);
+        self.align_optional()
+    }
+}
+
+impl<'a> Scorer for ReqOptScorer<'a> {
+    fn score(&self) -> f32 {
+        let score = self.required.score();
+        if self.optional.doc() == self.required.doc() {
+            score + self.optional.score()
+        } else {
+            score
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{DocId, TERMINATED};
+
+use super::DocSet;
+
+// Scores the docs matching a query in a segment.
+pub trait Scorer: DocSet {
+    // The score of the current doc, which must not be `TERMINATED`.
+    fn score(&self) -> f32;
+
+    // Bounds the scores of all the docs, which are unbounded by default.
+    fn max_score(&self) -> f32 {
+        f32::INFINITY
+    }
+
+    // The last doc of the block containing `target`, which must not be before
+    // the current doc, and the bound of the scores of the docs from the
+    // current one to it.
+    fn block_max_score(&self, _target: DocId) -> (DocId, f32) {
+        (TERMINATED, self.max_score())
+    }
+
+    // Lets the scorer skip the docs scoring below `min_score`, e.g. the lowest
+    // score of a full top-k heap. It only ever increases, and is ignored by
+    // default.
+    fn set_min_competitive_score(&mut self, _min_score: f32) {}
+}
+
+#[cfg(test)]
+pub(crate) fn collect_scores<S: Scorer + ?Sized>(scorer: &mut S) -> Vec<(crate::DocId, f32)> {
+    let mut docs = vec![];
+    while scorer.doc() != crate::TERMINATED {
+        docs.push((scorer.doc(), scorer.score()));
+        scorer.next();
+    }
+    docs
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use crate::{index::Searcher, schema::Field, segment::SegmentReader};
+
+use super::{
+    combined_spans::CombinedSpans,
+    span_query::{clauses_field, SpanWeight},
+    span_within_query::contains,
+    Query, Span, SpanQuery, Spans, Weight,
+};
+
+// Matches the spans of `big` containing a span of `little`.
+#[derive(Clone)]
+pub struct SpanContainingQuery {
+    big: Arc<dyn SpanQuery>,
+    little: Arc<dyn SpanQuery>,
+}
+
+impl SpanContainingQuery {
+    pub fn new(big: Box<dyn SpanQuery>, little: Box<dyn SpanQuery>) -> Self {
+        let big = Arc::from(big);
+        let little = Arc::from(little);
+        clauses_field(&[Arc::clone(&big), Arc::clone(&little)]);
+        Self { big, little }
+    }
+}
+
+impl Query for SpanContainingQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        Box::new(SpanWeight::new(searcher, Arc::new(self.clone())))
+    }
+}
+
+impl SpanQuery for SpanContainingQuery {
+    fn field(&self) -> Field {
+        self.big.field()
+    }
+
+    fn extract_terms(&self, terms: &mut Vec<String>) {
+        self.big.extract_terms(terms);
+    }
+
+    fn spans<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Spans + 'a>> {
+        let big = self.big.spans(segment_reader)?;
+        let little = self.little.spans(segment_reader)?;
+        let combine = Box::new(|clause_spans: &[&[Span]]| {
+            clause_spans[0]
+                .iter()
+                .filter(|big| {
+                    clause_spans[1]
+                        .iter()
+                        .any(|little| contains(std::slice::from_ref(big), little))
+                })
+                .copied()
+                .collect()
+        });
+        Some(Box::new(CombinedSpans::new(
+            vec![(big, true), (little, true)],
+            combine,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{
+            span_query::collect_spans, text_searcher, SpanNearQuery, SpanQuery, SpanTermQuery,
+        },
+    };
+
+    use super::SpanContainingQuery;
+
+    #[test]
+    fn test_simple() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::WithPositions,
+            &[&["a x b c", "a b", "c a b"]],
+        );
+        let term = |term: &str| -> Box<dyn SpanQuery> { Box::new(SpanTermQuery::new(body, term)) };
+        let near = || -> Box<dyn SpanQuery> {
+            Box::new(SpanNearQuery::new(vec![term("a"), term("b")], 1, true))
+        };
+
+        let query = SpanContainingQuery::new(near(), term("x"));
+        assert_eq!(collect_spans(&searcher, &query), [(0, vec![(0, 3)])]);
+        let query = SpanContainingQuery::new(near(), term("c"));
+        assert!(collect_spans(&searcher, &query).is_empty());
+    }
+}
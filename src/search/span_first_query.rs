@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use crate::{index::Searcher, schema::Field, segment::SegmentReader};
+
+use super::{
+    combined_spans::CombinedSpans, span_query::SpanWeight, Query, Span, SpanQuery, Spans, Weight,
+};
+
+// Matches the spans of a query ending within the first `end` positions of a
+// field.
+#[derive(Clone)]
+pub struct SpanFirstQuery {
+    query: Arc<dyn SpanQuery>,
+    end: u32,
+}
+
+impl SpanFirstQuery {
+    pub fn new(query: Box<dyn SpanQuery>, end: u32) -> Self {
+        Self {
+            query: Arc::from(query),
+            end,
+        }
+    }
+
+    pub fn end(&self) -> u32 {
+        self.end
+    }
+}
+
+impl Query for SpanFirstQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        Box::new(SpanWeight::new(searcher, Arc::new(self.clone())))
+    }
+}
+
+impl SpanQuery for SpanFirstQuery {
+    fn field(&self) -> Field {
+        self.query.field()
+    }
+
+    fn extract_terms(&self, terms: &mut Vec<String>) {
+        self.query.extract_terms(terms);
+    }
+
+    fn spans<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Spans + 'a>> {
+        let spans = self.query.spans(segment_reader)?;
+        let end = self.end;
+        let combine = Box::new(move |clause_spans: &[&[Span]]| {
+            clause_spans[0]
+                .iter()
+                .filter(|span| span.end <= end)
+                .copied()
+                .collect()
+        });
+        Some(Box::new(CombinedSpans::new(vec![(spans, true)], combine)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{
+            span_query::collect_spans, text_searcher, SpanNearQuery, SpanQuery, SpanTermQuery,
+        },
+    };
+
+    use super::SpanFirstQuery;
+
+    #[test]
+    fn test_simple() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::WithPositions,
+            &[&["a b", "x a b", "x x a b", "b a"]],
+        );
+        let term = |term: &str| -> Box<dyn SpanQuery> { Box::new(SpanTermQuery::new(body, term)) };
+
+        let query = SpanFirstQuery::new(term("a"), 2);
+        assert_eq!(
+            collect_spans(&searcher, &query),
+            [(0, vec![(0, 1)]), (1, vec![(1, 2)]), (3, vec![(1, 2)])]
+        );
+
+        let near = SpanNearQuery::new(vec![term("a"), term("b")], 0, true);
+        let query = SpanFirstQuery::new(Box::new(near), 3);
+        assert_eq!(
+            collect_spans(&searcher, &query),
+            [(0, vec![(0, 2)]), (1, vec![(1, 3)])]
+        );
+    }
+}
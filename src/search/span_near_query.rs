@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use crate::{index::Searcher, schema::Field, segment::SegmentReader};
+
+use super::{
+    combined_spans::CombinedSpans,
+    span_query::{clauses_field, SpanWeight},
+    spans::normalize_spans,
+    Query, Span, SpanQuery, Spans, Weight,
+};
+
+// Matches spans of all the clauses near each other, with at most `slop`
+// positions in between that none of them covers. With `in_order`, each
+// clause must also start after the previous one ends.
+#[derive(Clone)]
+pub struct SpanNearQuery {
+    field: Field,
+    clauses: Vec<Arc<dyn SpanQuery>>,
+    slop: u32,
+    in_order: bool,
+}
+
+impl SpanNearQuery {
+    pub fn new(clauses: Vec<Box<dyn SpanQuery>>, slop: u32, in_order: bool) -> Self {
+        let clauses: Vec<_> = clauses.into_iter().map(Arc::from).collect();
+        Self {
+            field: clauses_field(&clauses),
+            clauses,
+            slop,
+            in_order,
+        }
+    }
+
+    pub fn slop(&self) -> u32 {
+        self.slop
+    }
+
+    pub fn is_in_order(&self) -> bool {
+        self.in_order
+    }
+}
+
+impl Query for SpanNearQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        Box::new(SpanWeight::new(searcher, Arc::new(self.clone())))
+    }
+}
+
+impl SpanQuery for SpanNearQuery {
+    fn field(&self) -> Field {
+        self.field
+    }
+
+    fn extract_terms(&self, terms: &mut Vec<String>) {
+        for clause in &self.clauses {
+            clause.extract_terms(terms);
+        }
+    }
+
+    fn spans<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Spans + 'a>> {
+        let clauses = self
+            .clauses
+            .iter()
+            .map(|clause| Some((clause.spans(segment_reader)?, true)))
+            .collect::<Option<_>>()?;
+        let slop = self.slop;
+        let combine = if self.in_order {
+            Box::new(move |spans: &[&[Span]]| ordered_spans(spans, slop)) as _
+        } else {
+            Box::new(move |spans: &[&[Span]]| unordered_spans(spans, slop)) as _
+        };
+        Some(Box::new(CombinedSpans::new(clauses, combine)))
+    }
+}
+
+// For each span of the first clause, chains the first span of each following
+// clause starting after the previous one ends.
+fn ordered_spans(clause_spans: &[&[Span]], slop: u32) -> Vec<Span> {
+    let mut spans = Vec::new();
+    'first: for first in clause_spans[0] {
+        let mut end = first.end;
+        let mut width = first.width;
+        for other_spans in &clause_spans[1..] {
+            let index = other_spans.partition_point(|span| span.start < end);
+            let Some(span) = other_spans.get(index) else {
+                break 'first;
+            };
+            width += span.start - end + span.width;
+            end = span.end;
+        }
+        if width <= slop {
+            spans.push(Span::new(first.start, end, width));
+        }
+    }
+    normalize_spans(&mut spans);
+    spans
+}
+
+// Sweeps windows holding a span of each clause, always moving the clause
+// whose span starts first.
+fn unordered_spans(clause_spans: &[&[Span]], slop: u32) -> Vec<Span> {
+    if clause_spans.iter().any(|spans| spans.is_empty()) {
+        return Vec::new();
+    }
+    let mut indexes = vec![0; clause_spans.len()];
+    let mut spans = Vec::new();
+    loop {
+        let current = || (0..clause_spans.len()).map(|i| clause_spans[i][indexes[i]]);
+        let (min_clause, start) = current()
+            .enumerate()
+            .map(|(i, span)| (i, span.start))
+            .min_by_key(|&(i, start)| (start, i))
+            .unwrap();
+        let end = current().map(|span| span.end).max().unwrap();
+        let covered: u32 = current().map(|span| span.len() - span.width).sum();
+        let inner_width: u32 = current().map(|span| span.width).sum();
+        let width = (end - start).saturating_sub(covered).max(inner_width);
+        if width <= slop {
+            spans.push(Span::new(start, end, width));
+        }
+        indexes[min_clause] += 1;
+        if indexes[min_clause] == clause_spans[min_clause].len() {
+            break;
+        }
+    }
+    normalize_spans(&mut spans);
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{span_query::collect_spans, text_searcher, SpanQuery, SpanTermQuery},
+    };
+
+    use super::{unordered_spans, Span, SpanNearQuery};
+
+    #[test]
+    fn test_ordered() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::WithPositions,
+            &[&["a b c", "a x b", "a x x b", "b a", "x y"]],
+        );
+        let term = |term: &str| -> Box<dyn SpanQuery> { Box::new(SpanTermQuery::new(body, term)) };
+
+        let query = SpanNearQuery::new(vec![term("a"), term("b")], 0, true);
+        assert_eq!(collect_spans(&searcher, &query), [(0, vec![(0, 2)])]);
+        let query = SpanNearQuery::new(vec![term("a"), term("b")], 1, true);
+        assert_eq!(
+            collect_spans(&searcher, &query),
+            [(0, vec![(0, 2)]), (1, vec![(0, 3)])]
+        );
+        let query = SpanNearQuery::new(vec![term("a"), term("b"), term("c")], 0, true);
+        assert_eq!(collect_spans(&searcher, &query), [(0, vec![(0, 3)])]);
+
+        // Nested spans add up their widths.
+        let inner = SpanNearQuery::new(vec![term("a"), term("b")], 1, true);
+        let query = SpanNearQuery::new(vec![Box::new(inner), term("c")], 1, true);
+        assert_eq!(collect_spans(&searcher, &query), [(0, vec![(0, 3)])]);
+        assert!(collect_spans(
+            &searcher,
+            &SpanNearQuery::new(vec![term("a"), term("y")], 5, true)
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_unordered() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::WithPositions,
+            &[&["a b c", "a x b", "a x x b", "b a", "b x a a"]],
+        );
+        let term = |term: &str| -> Box<dyn SpanQuery> { Box::new(SpanTermQuery::new(body, term)) };
+
+        let query = SpanNearQuery::new(vec![term("a"), term("b")], 0, false);
+        assert_eq!(
+            collect_spans(&searcher, &query),
+            [(0, vec![(0, 2)]), (3, vec![(0, 2)])]
+        );
+        let query = SpanNearQuery::new(vec![term("a"), term("b")], 1, false);
+        assert_eq!(
+            collect_spans(&searcher, &query),
+            [
+                (0, vec![(0, 2)]),
+                (1, vec![(0, 3)]),
+                (3, vec![(0, 2)]),
+                (4, vec![(0, 3)])
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "indexed without positions")]
+    fn test_unordered_without_positions() {
+        let (searcher, body) =
+            text_searcher("whitespace", IndexRecordOption::Basic, &[&["a b", "b a"]]);
+        let term = |term: &str| -> Box<dyn SpanQuery> { Box::new(SpanTermQuery::new(body, term)) };
+        let query = SpanNearQuery::new(vec![term("a"), term("b")], 0, false);
+        collect_spans(&searcher, &query);
+    }
+
+    #[test]
+    fn test_unordered_empty_clause() {
+        let spans = [Span::new(0, 1, 0)];
+        assert!(unordered_spans(&[&spans, &[]], 1).is_empty());
+        assert!(unordered_spans(&[&[], &spans], 1).is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use crate::{index::Searcher, schema::Field, segment::SegmentReader};
+
+use super::{
+    combined_spans::CombinedSpans,
+    span_query::{clauses_field, SpanWeight},
+    Query, Span, SpanQuery, Spans, Weight,
+};
+
+// Matches the spans of `include` that don't overlap any span of `exclude`.
+// Spans of `exclude` ending up to `pre` positions before, or starting up to
+// `post` positions after a span of `include` exclude it too.
+#[derive(Clone)]
+pub struct SpanNotQuery {
+    include: Arc<dyn SpanQuery>,
+    exclude: Arc<dyn SpanQuery>,
+    pre: u32,
+    post: u32,
+}
+
+impl SpanNotQuery {
+    pub fn new(include: Box<dyn SpanQuery>, exclude: Box<dyn SpanQuery>) -> Self {
+        let include = Arc::from(include);
+        let exclude = Arc::from(exclude);
+        clauses_field(&[Arc::clone(&include), Arc::clone(&exclude)]);
+        Self {
+            include,
+            exclude,
+            pre: 0,
+            post: 0,
+        }
+    }
+
+    pub fn set_pre(mut self, pre: u32) -> Self {
+        self.pre = pre;
+        self
+    }
+
+    pub fn set_post(mut self, post: u32) -> Self {
+        self.post = post;
+        self
+    }
+}
+
+impl Query for SpanNotQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        Box::new(SpanWeight::new(searcher, Arc::new(self.clone())))
+    }
+}
+
+impl SpanQuery for SpanNotQuery {
+    fn field(&self) -> Field {
+        self.include.field()
+    }
+
+    fn extract_terms(&self, terms: &mut Vec<String>) {
+        self.include.extract_terms(terms);
+    }
+
+    fn spans<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Spans + 'a>> {
+        let include = self.include.spans(segment_reader)?;
+        let Some(exclude) = self.exclude.spans(segment_reader) else {
+            return Some(include);
+        };
+        let (pre, post) = (self.pre, self.post);
+        let combine = Box::new(move |clause_spans: &[&[Span]]| {
+            let excluded = clause_spans[1];
+            clause_spans[0]
+                .iter()
+                .filter(|span| {
+                    let start = span.start.saturating_sub(pre);
+                    let end = span.end.saturating_add(post);
+                    !excluded
+                        .iter()
+                        .any(|excluded| excluded.start < end && excluded.end > start)
+                })
+                .copied()
+                .collect()
+        });
+        Some(Box::new(CombinedSpans::new(
+            vec![(include, true), (exclude, false)],
+            combine,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{
+            span_query::collect_spans, text_searcher, SpanNearQuery, SpanQuery, SpanTermQuery,
+        },
+    };
+
+    use super::SpanNotQuery;
+
+    #[test]
+    fn test_simple() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::WithPositions,
+            &[&["a b", "a x b", "a b x a", "x a y"]],
+        );
+        let term = |term: &str| -> Box<dyn SpanQuery> { Box::new(SpanTermQuery::new(body, term)) };
+        let near = || -> Box<dyn SpanQuery> {
+            Box::new(SpanNearQuery::new(vec![term("a"), term("b")], 1, true))
+        };
+
+        // "a" near "b", not overlapping "x".
+        let query = SpanNotQuery::new(near(), term("x"));
+        assert_eq!(
+            collect_spans(&searcher, &query),
+            [(0, vec![(0, 2)]), (2, vec![(0, 2)])]
+        );
+
+        let query = SpanNotQuery::new(near(), term("x")).set_post(1);
+        assert_eq!(collect_spans(&searcher, &query), [(0, vec![(0, 2)])]);
+
+        let query = SpanNotQuery::new(term("a"), term("x")).set_pre(1);
+        assert_eq!(
+            collect_spans(&searcher, &query),
+            [(0, vec![(0, 1)]), (1, vec![(0, 1)]), (2, vec![(0, 1)])]
+        );
+
+        let query = SpanNotQuery::new(term("a"), term("missing"));
+        assert_eq!(collect_spans(&searcher, &query).len(), 4);
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use crate::{index::Searcher, schema::Field, segment::SegmentReader};
+
+use super::{
+    combined_spans::CombinedSpans,
+    span_query::{clauses_field, SpanWeight},
+    spans::normalize_spans,
+    Query, Span, SpanQuery, Spans, Weight,
+};
+
+// Matches the spans of any of the clauses.
+#[derive(Clone)]
+pub struct SpanOrQuery {
+    field: Field,
+    clauses: Vec<Arc<dyn SpanQuery>>,
+}
+
+impl SpanOrQuery {
+    pub fn new(clauses: Vec<Box<dyn SpanQuery>>) -> Self {
+        let clauses: Vec<_> = clauses.into_iter().map(Arc::from).collect();
+        Self {
+            field: clauses_field(&clauses),
+            clauses,
+        }
+    }
+}
+
+impl Query for SpanOrQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        Box::new(SpanWeight::new(searcher, Arc::new(self.clone())))
+    }
+}
+
+impl SpanQuery for SpanOrQuery {
+    fn field(&self) -> Field {
+        self.field
+    }
+
+    fn extract_terms(&self, terms: &mut Vec<String>) {
+        for clause in &self.clauses {
+            clause.extract_terms(terms);
+        }
+    }
+
+    fn spans<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Spans + 'a>> {
+        let clauses: Vec<_> = self
+            .clauses
+            .iter()
+            .filter_map(|clause| Some((clause.spans(segment_reader)?, false)))
+            .collect();
+        if clauses.is_empty() {
+            return None;
+        }
+        let combine = Box::new(|clause_spans: &[&[Span]]| {
+            let mut spans = clause_spans.concat();
+            normalize_spans(&mut spans);
+            spans
+        });
+        Some(Box::new(CombinedSpans::new(clauses, combine)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{
+            span_query::collect_spans, text_searcher, SpanNearQuery, SpanQuery, SpanTermQuery,
+        },
+    };
+
+    use super::SpanOrQuery;
+
+    #[test]
+    fn test_simple() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::WithPositions,
+            &[&["a b", "c", "b c a", "d"]],
+        );
+        let term = |term: &str| -> Box<dyn SpanQuery> { Box::new(SpanTermQuery::new(body, term)) };
+
+        let query = SpanOrQuery::new(vec![term("a"), term("c"), term("missing")]);
+        assert_eq!(
+            collect_spans(&searcher, &query),
+            [
+                (0, vec![(0, 1)]),
+                (1, vec![(0, 1)]),
+                (2, vec![(1, 2), (2, 3)])
+            ]
+        );
+
+        let either = SpanOrQuery::new(vec![term("a"), term("c")]);
+        let query = SpanNearQuery::new(vec![term("b"), Box::new(either)], 0, true);
+        assert_eq!(collect_spans(&searcher, &query), [(2, vec![(0, 2)])]);
+    }
+}
@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use crate::{
+    index::Searcher, schema::Field, segment::SegmentReader, similarity::SimilarityScorer, DocId,
+};
+
+use super::{DocSet, Query, Scorer, Spans, Weight};
+
+// A query matching spans of positions, which span queries compose into larger
+// spans. The field must be indexed with positions.
+pub trait SpanQuery: Query {
+    fn field(&self) -> Field;
+
+    // Adds the terms of the query, whose statistics are used for scoring.
+    fn extract_terms(&self, terms: &mut Vec<String>);
+
+    // Returns `None` when no doc of the segment can match.
+    fn spans<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Spans + 'a>>;
+}
+
+// Panics unless all the clauses are on the same field, and returns it.
+pub(super) fn clauses_field(clauses: &[Arc<dyn SpanQuery>]) -> Field {
+    assert!(!clauses.is_empty(), "Span query without clauses");
+    let field = clauses[0].field();
+    assert!(
+        clauses.iter().all(|clause| clause.field() == field),
+        "Span clauses must be on the same field"
+    );
+    field
+}
+
+pub(super) struct SpanWeight {
+    query: Arc<dyn SpanQuery>,
+    similarity_scorer: Box<dyn SimilarityScorer>,
+}
+
+impl SpanWeight {
+    pub(super) fn new(searcher: &Searcher, query: Arc<dyn SpanQuery>) -> Self {
+        let mut terms = Vec::new();
+        query.extract_terms(&mut terms);
+        terms.sort_unstable();
+        terms.dedup();
+        let field = query.field();
+        let term_statistics: Vec<_> = terms
+            .iter()
+            .map(|term| searcher.term_statistics(field, term))
+            .collect();
+        let similarity_scorer = searcher.similarity().scorer(
+            1.0,
+            &searcher.collection_statistics(field),
+            &term_statistics,
+        );
+        Self {
+            query,
+            similarity_scorer,
+        }
+    }
+}
+
+impl Weight for SpanWeight {
+    fn scorer<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Scorer + 'a>> {
+        let spans = self.query.spans(segment_reader)?;
+        Some(Box::new(SpanScorer {
+            spans,
+            segment_reader,
+            field: self.query.field(),
+            similarity_scorer: self.similarity_scorer.as_ref(),
+        }))
+    }
+}
+
+// Scores the spans of a doc like sloppy phrase matches, so that each span
+// counts for `1 / (1 + width)` in the freq.
+pub struct SpanScorer<'a> {
+    spans: Box<dyn Spans + 'a>,
+    segment_reader: &'a SegmentReader,
+    field: Field,
+    similarity_scorer: &'a dyn SimilarityScorer,
+}
+
+impl<'a> SpanScorer<'a> {
+    pub fn freq(&self) -> f32 {
+        self.spans
+            .spans()
+            .iter()
+            .map(|span| 1.0 / (1.0 + span.width as f32))
+            .sum()
+    }
+}
+
+impl<'a> DocSet for SpanScorer<'a> {
+    fn doc(&self) -> DocId {
+        self.spans.doc()
+    }
+
+    fn next(&mut self) -> DocId {
+        self.spans.next()
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        self.spans.advance(target)
+    }
+}
+
+impl<'a> Scorer for SpanScorer<'a> {
+    fn score(&self) -> f32 {
+        let field_length = self.segment_reader.field_length(self.field, self.doc());
+        self.similarity_scorer.score(self.freq(), field_length)
+    }
+}
+
+// Collects the start and end of the spans of each doc.
+#[cfg(test)]
+pub(crate) fn collect_spans(
+    searcher: &Searcher,
+    query: &dyn SpanQuery,
+) -> Vec<(DocId, Vec<(u32, u32)>)> {
+    let mut docs = vec![];
+    for segment_reader in searcher.segment_readers() {
+        if let Some(mut spans) = query.spans(segment_reader) {
+            while spans.doc() != crate::TERMINATED {
+                let doc_spans = spans.spans().iter().map(|s| (s.start, s.end)).collect();
+                docs.push((spans.doc(), doc_spans));
+                spans.next();
+            }
+        }
+    }
+    docs
+}
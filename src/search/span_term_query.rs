@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use crate::{
+    index::Searcher, postings::PostingsIterator, schema::Field, segment::SegmentReader, DocId,
+    TERMINATED,
+};
+
+use super::{span_query::SpanWeight, DocSet, Query, Span, SpanQuery, Spans, Weight};
+
+// Matches each position of a term as a span of one position.
+#[derive(Clone)]
+pub struct SpanTermQuery {
+    field: Field,
+    term: String,
+}
+
+impl SpanTermQuery {
+    pub fn new(field: Field, term: &str) -> Self {
+        Self {
+            field,
+            term: term.to_string(),
+        }
+    }
+
+    pub fn term(&self) -> &str {
+        &self.term
+    }
+}
+
+impl Query for SpanTermQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        Box::new(SpanWeight::new(searcher, Arc::new(self.clone())))
+    }
+}
+
+impl SpanQuery for SpanTermQuery {
+    fn field(&self) -> Field {
+        self.field
+    }
+
+    fn extract_terms(&self, terms: &mut Vec<String>) {
+        terms.push(self.term.clone());
+    }
+
+    // Panics if the field is indexed without positions, since every doc would
+    // otherwise have no spans.
+    fn spans<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Spans + 'a>> {
+        let field_entry = segment_reader.schema().field_entry(self.field);
+        if let Some(record_option) = field_entry.field_type().index_record_option() {
+            assert!(
+                record_option.has_positions(),
+                "Field `{}` is indexed without positions",
+                field_entry.name()
+            );
+        }
+        let postings = segment_reader.postings(self.field, &self.term)?;
+        if postings.doc() == TERMINATED {
+            return None;
+        }
+        let mut spans = TermSpans {
+            postings,
+            spans: Vec::new(),
+        };
+        spans.load_spans();
+        Some(Box::new(spans))
+    }
+}
+
+struct TermSpans<'a> {
+    postings: Box<dyn PostingsIterator + 'a>,
+    spans: Vec<Span>,
+}
+
+impl<'a> TermSpans<'a> {
+    fn load_spans(&mut self) -> DocId {
+        self.spans.clear();
+        self.spans.extend(
+            self.postings
+                .positions()
+                .iter()
+                .map(|&position| Span::new(position, position + 1, 0)),
+        );
+        self.postings.doc()
+    }
+}
+
+impl<'a> DocSet for TermSpans<'a> {
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn next(&mut self) -> DocId {
+        self.postings.next();
+        self.load_spans()
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        if self.postings.doc() >= target {
+            return self.postings.doc();
+        }
+        self.postings.advance(target);
+        self.load_spans()
+    }
+}
+
+impl<'a> Spans for TermSpans<'a> {
+    fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{search_all, span_query::collect_spans, text_searcher},
+    };
+
+    use super::SpanTermQuery;
+
+    #[test]
+    fn test_simple() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::WithPositions,
+            &[&["a b a", "b c", "c a"]],
+        );
+        let query = SpanTermQuery::new(body, "a");
+        assert_eq!(
+            collect_spans(&searcher, &query),
+            [(0, vec![(0, 1), (2, 3)]), (2, vec![(1, 2)])]
+        );
+        let docs = search_all(&searcher, &query);
+        assert!(docs[0].2 > docs[1].2);
+        assert!(collect_spans(&searcher, &SpanTermQuery::new(body, "d")).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "indexed without positions")]
+    fn test_without_positions() {
+        let (searcher, body) = text_searcher("whitespace", IndexRecordOption::Basic, &[&["a b a"]]);
+        search_all(&searcher, &SpanTermQuery::new(body, "a"));
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use crate::{index::Searcher, schema::Field, segment::SegmentReader};
+
+use super::{
+    combined_spans::CombinedSpans,
+    span_query::{clauses_field, SpanWeight},
+    Query, Span, SpanQuery, Spans, Weight,
+};
+
+// Matches the spans of `little` contained in a span of `big`, e.g. terms
+// within a sentence delimited by marker terms.
+#[derive(Clone)]
+pub struct SpanWithinQuery {
+    little: Arc<dyn SpanQuery>,
+    big: Arc<dyn SpanQuery>,
+}
+
+impl SpanWithinQuery {
+    pub fn new(little: Box<dyn SpanQuery>, big: Box<dyn SpanQuery>) -> Self {
+        let little = Arc::from(little);
+        let big = Arc::from(big);
+        clauses_field(&[Arc::clone(&little), Arc::clone(&big)]);
+        Self { little, big }
+    }
+}
+
+impl Query for SpanWithinQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        Box::new(SpanWeight::new(searcher, Arc::new(self.clone())))
+    }
+}
+
+impl SpanQuery for SpanWithinQuery {
+    fn field(&self) -> Field {
+        self.little.field()
+    }
+
+    fn extract_terms(&self, terms: &mut Vec<String>) {
+        self.little.extract_terms(terms);
+    }
+
+    fn spans<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Spans + 'a>> {
+        let little = self.little.spans(segment_reader)?;
+        let big = self.big.spans(segment_reader)?;
+        let combine = Box::new(|clause_spans: &[&[Span]]| {
+            clause_spans[0]
+                .iter()
+                .filter(|little| contains(clause_spans[1], little))
+                .copied()
+                .collect()
+        });
+        Some(Box::new(CombinedSpans::new(
+            vec![(little, true), (big, true)],
+            combine,
+        )))
+    }
+}
+
+// Whether any of the spans contains the other span.
+pub(super) fn contains(spans: &[Span], other: &Span) -> bool {
+    spans
+        .iter()
+        .take_while(|span| span.start <= other.start)
+        .any(|span| span.end >= other.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{
+            span_query::collect_spans, text_searcher, SpanNearQuery, SpanQuery, SpanTermQuery,
+        },
+    };
+
+    use super::SpanWithinQuery;
+
+    #[test]
+    fn test_simple() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::WithPositions,
+            &[&["<s> a b </s> c", "<s> b </s> a", "a <s> c </s> <s> a </s>"]],
+        );
+        let term = |term: &str| -> Box<dyn SpanQuery> { Box::new(SpanTermQuery::new(body, term)) };
+        let sentence = || -> Box<dyn SpanQuery> {
+            Box::new(SpanNearQuery::new(
+                vec![term("<s>"), term("</s>")],
+                10,
+                true,
+            ))
+        };
+
+        let query = SpanWithinQuery::new(term("a"), sentence());
+        assert_eq!(
+            collect_spans(&searcher, &query),
+            [(0, vec![(1, 2)]), (2, vec![(5, 6)])]
+        );
+        let query = SpanWithinQuery::new(term("c"), sentence());
+        assert_eq!(collect_spans(&searcher, &query), [(2, vec![(2, 3)])]);
+    }
+}
@@ -0,0 +1,40 @@
+use super::DocSet;
+
+// A range of positions matched in a doc, from `start` to `end` exclusive.
+// The width is how many positions within the range are not covered by the
+// terms matched, and makes wider spans score lower.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Span {
+    pub start: u32,
+    pub end: u32,
+    pub width: u32,
+}
+
+impl Span {
+    pub fn new(start: u32, end: u32, width: u32) -> Self {
+        Self { start, end, width }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+// Iterates the docs where a span query matches, along with the spans matched
+// in each doc.
+pub trait Spans: DocSet {
+    // The spans of the current doc, sorted and never empty unless the doc is
+    // `TERMINATED`.
+    fn spans(&self) -> &[Span];
+}
+
+// Sorts spans, keeping only the narrowest width of the spans with the same
+// range.
+pub(super) fn normalize_spans(spans: &mut Vec<Span>) {
+    spans.sort_unstable();
+    spans.dedup_by_key(|span| (span.start, span.end));
+}
@@ -0,0 +1,177 @@
+use crate::{
+    index::Searcher,
+    postings::{Impact, PostingsIterator},
+    schema::Field,
+    segment::SegmentReader,
+    similarity::SimilarityScorer,
+    DocId, TERMINATED,
+};
+
+use super::{DocSet, Query, Scorer, Weight};
+
+// Matches the docs containing a term in a field.
+pub struct TermQuery {
+    field: Field,
+    term: String,
+}
+
+impl TermQuery {
+    pub fn new(field: Field, term: &str) -> Self {
+        Self {
+            field,
+            term: term.to_string(),
+        }
+    }
+
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    pub fn term(&self) -> &str {
+        &self.term
+    }
+}
+
+impl Query for TermQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        let similarity_scorer = searcher.similarity().scorer(
+            1.0,
+            &searcher.collection_statistics(self.field),
+            &[searcher.term_statistics(self.field, &self.term)],
+        );
+        Box::new(TermWeight {
+            field: self.field,
+            term: self.term.clone(),
+            similarity_scorer,
+        })
+    }
+}
+
+struct TermWeight {
+    field: Field,
+    term: String,
+    similarity_scorer: Box<dyn SimilarityScorer>,
+}
+
+impl Weight for TermWeight {
+    fn scorer<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Scorer + 'a>> {
+        let postings = segment_reader.postings(self.field, &self.term)?;
+        if postings.doc() == TERMINATED {
+            return None;
+        }
+        Some(Box::new(TermScorer {
+            postings,
+            segment_reader,
+            field: self.field,
+            similarity_scorer: self.similarity_scorer.as_ref(),
+        }))
+    }
+}
+
+pub struct TermScorer<'a> {
+    postings: Box<dyn PostingsIterator + 'a>,
+    segment_reader: &'a SegmentReader,
+    field: Field,
+    similarity_scorer: &'a dyn SimilarityScorer,
+}
+
+impl<'a> DocSet for TermScorer<'a> {
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn next(&mut self) -> DocId {
+        self.postings.next()
+    }
+
+    fn advance(&mut self, target: DocId) -> DocId {
+        self.postings.advance(target)
+    }
+}
+
+impl<'a> TermScorer<'a> {
+    fn impact_score(&self, impact: Impact) -> f32 {
+        self.similarity_scorer
+            .score(impact.freq as f32, impact.field_length)
+    }
+}
+
+impl<'a> Scorer for TermScorer<'a> {
+    fn score(&self) -> f32 {
+        let field_length = self.segment_reader.field_length(self.field, self.doc());
+        self.similarity_scorer
+            .score(self.postings.freq() as f32, field_length)
+    }
+
+    fn max_score(&self) -> f32 {
+        self.impact_score(self.postings.max_impact())
+    }
+
+    fn block_max_score(&self, target: DocId) -> (DocId, f32) {
+        let (last_doc, impact) = self.postings.block_max_impact(target);
+        (last_doc, self.impact_score(impact))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        document::Document,
+        index::IndexWriter,
+        schema::{Schema, TextOptions},
+        search::search_all,
+        similarity::{Bm25Similarity, CollectionStatistics, Similarity, TermStatistics},
+    };
+
+    use super::TermQuery;
+
+    #[test]
+    fn test_simple() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        for text in ["hello world", "hello", "big world"] {
+            let mut doc = Document::new();
+            doc.add_text(title, text);
+            writer.add_document(&doc);
+        }
+        writer.flush();
+        let mut doc = Document::new();
+        doc.add_text(title, "hello hello world");
+        writer.add_document(&doc);
+        let searcher = writer.reader().searcher();
+
+        let docs = search_all(&searcher, &TermQuery::new(title, "hello"));
+        let similarity_scorer = Bm25Similarity::default().scorer(
+            1.0,
+            &CollectionStatistics {
+                doc_count: 4,
+                total_field_length: 8,
+            },
+            &[TermStatistics { doc_freq: 3 }],
+        );
+        assert_eq!(
+            docs,
+            vec![
+                (0, 0, similarity_scorer.score(1.0, 2)),
+                (0, 1, similarity_scorer.score(1.0, 1)),
+                (1, 0, similarity_scorer.score(2.0, 3)),
+            ]
+        );
+        assert!(docs[1].2 > docs[0].2);
+
+        assert!(search_all(&searcher, &TermQuery::new(title, "missing")).is_empty());
+        let docs = search_all(&searcher, &TermQuery::new(title, "big"));
+        assert_eq!(
+            docs.iter().map(|d| (d.0, d.1)).collect::<Vec<_>>(),
+            [(0, 2)]
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+use crate::{segment::SegmentReader, DocId};
+
+use super::{Collector, SegmentCollector};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoredDoc {
+    pub score: f32,
+    pub segment_ord: usize,
+    pub doc: DocId,
+}
+
+// Orders docs from the worst to the best: by score, then with ties going to
+// the first doc.
+struct Ranked(ScoredDoc);
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .score
+            .total_cmp(&other.0.score)
+            .then_with(|| (other.0.segment_ord, other.0.doc).cmp(&(self.0.segment_ord, self.0.doc)))
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ranked {}
+
+// Keeps the `k` greatest of the items it is given.
+pub(super) struct TopK<T> {
+    k: usize,
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopK<T> {
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k),
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(item));
+        } else if self.k > 0 && item > self.heap.peek().unwrap().0 {
+            self.heap.pop();
+            self.heap.push(Reverse(item));
+        }
+    }
+
+    // The least of the items once there are `k` of them, which any other
+    // item must exceed to be kept.
+    pub fn threshold(&self) -> Option<&T> {
+        if self.heap.len() < self.k {
+            return None;
+        }
+        self.heap.peek().map(|Reverse(item)| item)
+    }
+
+    // From the greatest.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(item)| item)
+            .collect()
+    }
+}
+
+// Collects the `k` best scoring docs, from the best.
+//
+// Once `k` docs are collected, the lowest of their scores is the min
+// competitive score, so that scorers may skip the docs that can't make it
+// into the top-k. Results are the same as scoring every doc.
+#[derive(Clone, Debug)]
+pub struct TopDocs {
+    k: usize,
+    search_after: Option<ScoredDoc>,
+}
+
+impl TopDocs {
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            search_after: None,
+        }
+    }
+
+    // Only collects the docs ranked after `scored_doc`, typically the last
+    // doc of the previous page.
+    pub fn set_search_after(mut self, scored_doc: ScoredDoc) -> Self {
+        self.search_after = Some(scored_doc);
+        self
+    }
+}
+
+impl Collector for TopDocs {
+    type Fruit = Vec<ScoredDoc>;
+    type SegmentFruit = Vec<ScoredDoc>;
+    type Child<'a> = SegmentTopDocs;
+
+    fn for_segment(&self, segment_ord: usize, _segment_reader: &SegmentReader) -> SegmentTopDocs {
+        SegmentTopDocs {
+            segment_ord,
+            search_after: self.search_after.map(Ranked),
+            top_k: TopK::new(self.k),
+        }
+    }
+
+    fn merge_fruits(&self, fruits: Vec<Vec<ScoredDoc>>) -> Vec<ScoredDoc> {
+        let mut top_k = TopK::new(self.k);
+        for scored_doc in fruits.into_iter().flatten() {
+            top_k.push(Ranked(scored_doc));
+        }
+        top_k
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Ranked(scored_doc)| scored_doc)
+            .collect()
+    }
+}
+
+pub struct SegmentTopDocs {
+    segment_ord: usize,
+    search_after: Option<Ranked>,
+    top_k: TopK<Ranked>,
+}
+
+impl SegmentCollector for SegmentTopDocs {
+    type Fruit = Vec<ScoredDoc>;
+
+    fn collect(&mut self, doc: DocId, score: f32) {
+        let scored_doc = Ranked(ScoredDoc {
+            score,
+            segment_ord: self.segment_ord,
+            doc,
+        });
+        if self
+            .search_after
+            .as_ref()
+            .is_some_and(|search_after| scored_doc >= *search_after)
+        {
+            return;
+        }
+        self.top_k.push(scored_doc);
+    }
+
+    fn min_competitive_score(&self) -> f32 {
+        self.top_k
+            .threshold()
+            .map_or(0.0, |Ranked(scored_doc)| scored_doc.score)
+    }
+
+    fn harvest(self) -> Vec<ScoredDoc> {
+        self.top_k
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Ranked(scored_doc)| scored_doc)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        index::Searcher,
+        schema::{Field, IndexRecordOption},
+        search::{
+            collect_scores, search_all, text_searcher, BooleanQuery, Count, Occur, Query,
+            TermQuery, TotalHits,
+        },
+        util::Random,
+    };
+
+    use super::{ScoredDoc, TopDocs};
+
+    // Docs of pseudo-random words, whose freqs and lengths vary.
+    fn searcher() -> (Searcher, Field) {
+        let words = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let mut random = Random::new(42);
+        let texts: Vec<_> = (0..3000)
+            .map(|_| {
+                let len = 1 + random.below(20);
+                let text: Vec<&str> = (0..len)
+                    // Skews the distribution towards the first words.
+                    .map(|_| {
+                        words[random
+                            .below(words.len() as u64)
+                            .min(random.below(words.len() as u64))
+                            as usize]
+                    })
+                    .collect();
+                text.join(" ")
+            })
+            .collect();
+        text_searcher(
+            "whitespace",
+            IndexRecordOption::Basic,
+            &[&texts[..1200], &texts[1200..2500], &texts[2500..]],
+        )
+    }
+
+    fn exhaustive_top_docs(searcher: &Searcher, query: &dyn Query, k: usize) -> Vec<ScoredDoc> {
+        let mut docs: Vec<ScoredDoc> = search_all(searcher, query)
+            .into_iter()
+            .map(|(segment_ord, doc, score)| ScoredDoc {
+                score,
+                segment_ord,
+                doc,
+            })
+            .collect();
+        docs.sort_by(|left, right| {
+            right
+                .score
+                .total_cmp(&left.score)
+                .then_with(|| (left.segment_ord, left.doc).cmp(&(right.segment_ord, right.doc)))
+        });
+        docs.truncate(k);
+        docs
+    }
+
+    fn should(field: Field, terms: &[&str]) -> BooleanQuery {
+        BooleanQuery::new(
+            terms
+                .iter()
+                .map(|term| {
+                    let query: Box<dyn Query> = Box::new(TermQuery::new(field, term));
+                    (Occur::Should, query)
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_simple() {
+        let (searcher, body) = searcher();
+        let query = TermQuery::new(body, "h");
+        assert!(searcher.search(&query, &TopDocs::new(0)).is_empty());
+        let docs = searcher.search(&query, &TopDocs::new(10));
+        assert_eq!(docs.len(), 10);
+        assert_eq!(docs, exhaustive_top_docs(&searcher, &query, 10));
+        let query = TermQuery::new(body, "missing");
+        assert!(searcher.search(&query, &TopDocs::new(10)).is_empty());
+    }
+
+    #[test]
+    fn test_disjunctions() {
+        let (searcher, body) = searcher();
+        for terms in [
+            &["a", "h"][..],
+            &["a", "b", "c"],
+            &["g", "h"],
+            &["a", "d", "g", "h", "missing"],
+        ] {
+            let query = should(body, terms);
+            for k in [1, 10, 100, 10000] {
+                assert_eq!(
+                    searcher.search(&query, &TopDocs::new(k)),
+                    exhaustive_top_docs(&searcher, &query, k)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_after() {
+        let (searcher, body) = searcher();
+        let query = should(body, &["a", "h"]);
+        let expected = exhaustive_top_docs(&searcher, &query, 100);
+        let mut docs = searcher.search(&query, &TopDocs::new(30));
+        while docs.len() < 100 {
+            let collector = TopDocs::new(30).set_search_after(*docs.last().unwrap());
+            docs.extend(searcher.search(&query, &collector));
+        }
+        docs.truncate(100);
+        assert_eq!(docs, expected);
+    }
+
+    #[test]
+    fn test_count() {
+        let (searcher, body) = searcher();
+        let query = should(body, &["a", "g", "h"]);
+        let len = search_all(&searcher, &query).len();
+
+        let (total_hits, docs) = searcher.search(&query, &(Count::new(), TopDocs::new(10)));
+        assert_eq!(
+            total_hits,
+            TotalHits {
+                count: len,
+                is_lower_bound: false
+            }
+        );
+        assert_eq!(docs, exhaustive_top_docs(&searcher, &query, 10));
+
+        // Past the threshold, docs that can't make it into the top-k are
+        // skipped.
+        let collector = (Count::new().set_threshold(100), TopDocs::new(10));
+        let (total_hits, docs) = searcher.search(&query, &collector);
+        assert!(total_hits.is_lower_bound);
+        assert!(total_hits.count >= 100 && total_hits.count < len);
+        assert_eq!(docs, exhaustive_top_docs(&searcher, &query, 10));
+
+        let total_hits = searcher.search(&query, &Count::new().set_threshold(len + 1));
+        assert_eq!(
+            total_hits,
+            TotalHits {
+                count: len,
+                is_lower_bound: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_min_competitive_score() {
+        let (searcher, body) = searcher();
+        let query = should(body, &["a", "g", "h"]);
+        let weight = query.weight(&searcher);
+        let all_docs = search_all(&searcher, &query);
+        let min_score = all_docs[all_docs.len() / 2].2;
+        for (segment_ord, segment_reader) in searcher.segment_readers().iter().enumerate() {
+            let mut scorer = weight.scorer(segment_reader).unwrap();
+            scorer.set_min_competitive_score(min_score);
+            let docs = collect_scores(&mut *scorer);
+            // Docs below the min score may be skipped, and some are.
+            let expected: Vec<_> = all_docs
+                .iter()
+                .filter(|&&(ord, _, score)| ord == segment_ord && score >= min_score)
+                .map(|&(_, doc, score)| (doc, score))
+                .collect();
+            let competitive: Vec<_> = docs
+                .iter()
+                .copied()
+                .filter(|&(_, score)| score >= min_score)
+                .collect();
+            assert_eq!(competitive, expected);
+            let segment_len = all_docs.iter().filter(|doc| doc.0 == segment_ord).count();
+            assert!(docs.len() < segment_len);
+        }
+    }
+}
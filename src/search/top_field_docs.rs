@@ -0,0 +1,535 @@
+use std::cmp::Ordering;
+
+use crate::{
+    columnar::NumericDocValues,
+    document::Value,
+    points::UninvertedPoints,
+    schema::{Field, FieldType},
+    segment::SegmentReader,
+    util::f64_to_u64,
+    DocId,
+};
+
+use super::{top_docs::TopK, Collector, SegmentCollector};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortField {
+    // The best scores first.
+    Score,
+    // The values of a numeric field, from its doc values or else from its
+    // points if it is indexed in one dimension. Docs with several values sort
+    // by the first of them in the order, and docs without any come last.
+    Field(Field, Order),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SortValue {
+    Score(f32),
+    Field(Option<Value>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldDoc {
+    // A value for each sort field.
+    pub sort_values: Vec<SortValue>,
+    pub score: f32,
+    pub segment_ord: usize,
+    pub doc: DocId,
+}
+
+// Ranks docs by keys which are greater for the docs that come first: sortable
+// scores and values, whose bits are flipped for ascending order, and `None`
+// for missing values. Ties go to the first doc.
+struct Ranked {
+    keys: Vec<Option<u64>>,
+    segment_ord: usize,
+    doc: DocId,
+    score: f32,
+}
+
+impl Ranked {
+    fn cmp_keys<I: Iterator<Item = Option<u64>>>(
+        &self,
+        keys: I,
+        segment_ord: usize,
+        doc: DocId,
+    ) -> Ordering {
+        self.keys
+            .iter()
+            .copied()
+            .cmp(keys)
+            .then_with(|| (segment_ord, doc).cmp(&(self.segment_ord, self.doc)))
+    }
+}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_keys(other.keys.iter().copied(), other.segment_ord, other.doc)
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ranked {}
+
+fn score_key(score: f32) -> Option<u64> {
+    Some(f64_to_u64(score as f64))
+}
+
+fn value_key(value: u64, order: Order) -> u64 {
+    match order {
+        Order::Asc => !value,
+        Order::Desc => value,
+    }
+}
+
+// Collects the `k` first docs by one or more sort fields, then by doc.
+#[derive(Clone, Debug)]
+pub struct TopFieldDocs {
+    sort_fields: Vec<SortField>,
+    k: usize,
+    search_after: Option<FieldDoc>,
+}
+
+impl TopFieldDocs {
+    pub fn new(sort_fields: Vec<SortField>, k: usize) -> Self {
+        Self {
+            sort_fields,
+            k,
+            search_after: None,
+        }
+    }
+
+    // Only collects the docs sorted after `field_doc`, typically the last doc
+    // of the previous page.
+    pub fn set_search_after(mut self, field_doc: FieldDoc) -> Self {
+        self.search_after = Some(field_doc);
+        self
+    }
+
+    fn ranked(&self, field_doc: &FieldDoc) -> Ranked {
+        assert_eq!(
+            field_doc.sort_values.len(),
+            self.sort_fields.len(),
+            "There must be a sort value for each sort field"
+        );
+        let keys = self
+            .sort_fields
+            .iter()
+            .zip(&field_doc.sort_values)
+            .map(|(sort_field, sort_value)| match (sort_field, sort_value) {
+                (SortField::Score, SortValue::Score(score)) => score_key(*score),
+                (SortField::Field(_, order), SortValue::Field(value)) => {
+                    value.as_ref().map(|value| {
+                        let value = value
+                            .to_sortable_u64()
+                            .expect("Sort values must be numbers");
+                        value_key(value, *order)
+                    })
+                }
+                _ => panic!("Sort values don't match the sort fields"),
+            })
+            .collect();
+        Ranked {
+            keys,
+            segment_ord: field_doc.segment_ord,
+            doc: field_doc.doc,
+            score: field_doc.score,
+        }
+    }
+}
+
+impl Collector for TopFieldDocs {
+    type Fruit = Vec<FieldDoc>;
+    type SegmentFruit = Vec<FieldDoc>;
+    type Child<'a> = SegmentTopFieldDocs<'a>;
+
+    fn for_segment<'a>(
+        &'a self,
+        segment_ord: usize,
+        segment_reader: &'a SegmentReader,
+    ) -> SegmentTopFieldDocs<'a> {
+        let schema = segment_reader.schema();
+        let mut field_types = Vec::with_capacity(self.sort_fields.len());
+        let mut sort_keys = Vec::with_capacity(self.sort_fields.len());
+        for sort_field in &self.sort_fields {
+            let SortField::Field(field, order) = *sort_field else {
+                field_types.push(None);
+                sort_keys.push(SortKeys::Score);
+                continue;
+            };
+            let field_type = schema.field_entry(field).field_type();
+            if let Some(doc_values) = segment_reader.numeric_doc_values(field) {
+                sort_keys.push(SortKeys::DocValues(doc_values, order));
+            } else {
+                assert_eq!(
+                    field_type.point_dimensions(),
+                    Some(1),
+                    "Can only sort by numeric fields with doc values or indexed in one dimension"
+                );
+                sort_keys.push(SortKeys::Points(
+                    segment_reader.uninverted_points(field),
+                    order,
+                ));
+            }
+            field_types.push(Some(field_type.clone()));
+        }
+        SegmentTopFieldDocs {
+            sort_fields: self.sort_fields.clone(),
+            field_types,
+            sort_keys,
+            doc_keys: Vec::with_capacity(self.sort_fields.len()),
+            values: Vec::new(),
+            segment_ord,
+            search_after: self
+                .search_after
+                .as_ref()
+                .map(|field_doc| self.ranked(field_doc)),
+            top_k: TopK::new(self.k),
+        }
+    }
+
+    fn merge_fruits(&self, fruits: Vec<Vec<FieldDoc>>) -> Vec<FieldDoc> {
+        let mut field_docs: Vec<_> = fruits
+            .into_iter()
+            .flatten()
+            .map(|field_doc| (self.ranked(&field_doc), field_doc))
+            .collect();
+        field_docs.sort_by(|(left, _), (right, _)| right.cmp(left));
+        field_docs.truncate(self.k);
+        field_docs
+            .into_iter()
+            .map(|(_, field_doc)| field_doc)
+            .collect()
+    }
+}
+
+// Reads the keys of the collected docs for a sort field.
+enum SortKeys<'a> {
+    Score,
+    DocValues(Box<dyn NumericDocValues + 'a>, Order),
+    // The points uninverted by the segment reader, if the segment has any.
+    Points(Option<&'a UninvertedPoints>, Order),
+}
+
+pub struct SegmentTopFieldDocs<'a> {
+    sort_fields: Vec<SortField>,
+    // The type of each field, to decode its values.
+    field_types: Vec<Option<FieldType>>,
+    sort_keys: Vec<SortKeys<'a>>,
+    // The keys of the doc being collected.
+    doc_keys: Vec<Option<u64>>,
+    values: Vec<u64>,
+    segment_ord: usize,
+    search_after: Option<Ranked>,
+    top_k: TopK<Ranked>,
+}
+
+impl<'a> SegmentTopFieldDocs<'a> {
+    fn load_keys(&mut self, doc: DocId, score: f32) {
+        self.doc_keys.clear();
+        for sort_keys in &self.sort_keys {
+            let key = match sort_keys {
+                SortKeys::Score => score_key(score),
+                SortKeys::DocValues(doc_values, order) => {
+                    doc_values.values(doc, &mut self.values);
+                    self.values
+                        .iter()
+                        .map(|&value| value_key(value, *order))
+                        .max()
+                }
+                SortKeys::Points(points, order) => points
+                    .and_then(|points| points.bounds(doc))
+                    .map(|(min, max)| match order {
+                        Order::Asc => value_key(min, Order::Asc),
+                        Order::Desc => value_key(max, Order::Desc),
+                    }),
+            };
+            self.doc_keys.push(key);
+        }
+    }
+}
+
+impl<'a> SegmentCollector for SegmentTopFieldDocs<'a> {
+    type Fruit = Vec<FieldDoc>;
+
+    fn collect(&mut self, doc: DocId, score: f32) {
+        self.load_keys(doc, score);
+        let keys = || self.doc_keys.iter().copied();
+        // Compares the keys before copying them.
+        if let Some(search_after) = &self.search_after {
+            if search_after.cmp_keys(keys(), self.segment_ord, doc) != Ordering::Greater {
+                return;
+            }
+        }
+        if let Some(threshold) = self.top_k.threshold() {
+            if threshold.cmp_keys(keys(), self.segment_ord, doc) != Ordering::Less {
+                return;
+            }
+        }
+        let ranked = Ranked {
+            keys: self.doc_keys.clone(),
+            segment_ord: self.segment_ord,
+            doc,
+            score,
+        };
+        self.top_k.push(ranked);
+    }
+
+    fn harvest(self) -> Vec<FieldDoc> {
+        self.top_k
+            .into_sorted_vec()
+            .into_iter()
+            .map(|ranked| {
+                let sort_values = self
+                    .sort_fields
+                    .iter()
+                    .zip(&self.field_types)
+                    .zip(&ranked.keys)
+                    .map(|((sort_field, field_type), key)| match sort_field {
+                        SortField::Score => SortValue::Score(ranked.score),
+                        SortField::Field(_, order) => SortValue::Field(key.and_then(|key| {
+                            Value::from_sortable_u64(
+                                field_type.as_ref().unwrap(),
+                                value_key(key, *order),
+                            )
+                        })),
+                    })
+                    .collect();
+                FieldDoc {
+                    sort_values,
+                    score: ranked.score,
+                    segment_ord: ranked.segment_ord,
+                    doc: ranked.doc,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        document::{Document, Value},
+        index::{IndexWriter, Searcher},
+        schema::{Cardinality, Field, NumericOptions, Schema, TextOptions},
+        search::{segmented_searcher, MatchAllQuery, TermQuery},
+    };
+
+    use super::{FieldDoc, Order, SortField, SortValue, TopFieldDocs};
+
+    // Docs with a pseudo-random price and rating, and no price every fifth doc.
+    fn searcher() -> (Searcher, Field, Field, Field) {
+        let mut builder = Schema::builder();
+        let body = builder.add_text_field(
+            "body",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("whitespace"),
+        );
+        let price = builder.add_f64_field("price", NumericOptions::default().set_indexed());
+        let rating = builder.add_i64_field("rating", NumericOptions::default().set_indexed());
+        let schema = builder.build();
+
+        let docs: Vec<_> = (0..200u64)
+            .map(|i| {
+                let mut doc = Document::new();
+                doc.add_text(body, if i % 3 == 0 { "a a" } else { "a b c" });
+                if i % 5 != 0 {
+                    doc.add_f64(price, ((i * 37) % 50) as f64 / 2.0);
+                }
+                doc.add_i64(rating, ((i * 13) % 7) as i64 - 3);
+                doc
+            })
+            .collect();
+        let searcher = segmented_searcher(
+            IndexWriter::new(schema),
+            &[&docs[..80], &docs[80..150], &docs[150..]],
+        );
+        (searcher, body, price, rating)
+    }
+
+    fn price_and_doc(field_doc: &FieldDoc) -> (Option<f64>, usize, u32) {
+        let SortValue::Field(value) = &field_doc.sort_values[0] else {
+            panic!("Not a field value");
+        };
+        (
+            value.as_ref().and_then(Value::as_f64),
+            field_doc.segment_ord,
+            field_doc.doc,
+        )
+    }
+
+    #[test]
+    fn test_simple() {
+        let (searcher, _, price, _) = searcher();
+        let all_docs = searcher.search(
+            &MatchAllQuery,
+            &TopFieldDocs::new(vec![SortField::Field(price, Order::Asc)], 1000),
+        );
+        assert_eq!(all_docs.len(), 200);
+        let all_docs: Vec<_> = all_docs.iter().map(price_and_doc).collect();
+        let mut expected = all_docs.clone();
+        expected.sort_by(|left, right| match (left.0, right.0) {
+            (Some(left_price), Some(right_price)) => left_price
+                .total_cmp(&right_price)
+                .then_with(|| (left.1, left.2).cmp(&(right.1, right.2))),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => (left.1, left.2).cmp(&(right.1, right.2)),
+        });
+        assert_eq!(all_docs, expected);
+        assert_eq!(all_docs[0].0, Some(0.5));
+        assert!(all_docs[160..].iter().all(|doc| doc.0.is_none()));
+
+        let top_docs = searcher.search(
+            &MatchAllQuery,
+            &TopFieldDocs::new(vec![SortField::Field(price, Order::Desc)], 3),
+        );
+        let top_docs: Vec<_> = top_docs.iter().map(price_and_doc).collect();
+        assert!(top_docs.iter().all(|doc| doc.0 == Some(24.5)));
+        assert!(top_docs
+            .windows(2)
+            .all(|docs| (docs[0].1, docs[0].2) < (docs[1].1, docs[1].2)));
+    }
+
+    #[test]
+    fn test_tie_breakers() {
+        let (searcher, body, price, rating) = searcher();
+        let sort_fields = vec![
+            SortField::Field(rating, Order::Desc),
+            SortField::Score,
+            SortField::Field(price, Order::Asc),
+        ];
+        let query = TermQuery::new(body, "a");
+        let docs = searcher.search(&query, &TopFieldDocs::new(sort_fields.clone(), 1000));
+        assert_eq!(docs.len(), 200);
+        let key = |field_doc: &FieldDoc| {
+            let [SortValue::Field(Some(Value::I64(rating))), SortValue::Score(score), SortValue::Field(price)] =
+                &field_doc.sort_values[..]
+            else {
+                panic!("Unexpected sort values");
+            };
+            assert_eq!(*score, field_doc.score);
+            (
+                -rating,
+                -score,
+                price
+                    .as_ref()
+                    .map_or(f64::INFINITY, |price| price.as_f64().unwrap()),
+                field_doc.segment_ord,
+                field_doc.doc,
+            )
+        };
+        assert!(docs.windows(2).all(
+            |docs| key(&docs[0]).partial_cmp(&key(&docs[1])) == Some(std::cmp::Ordering::Less)
+        ));
+
+        let mut pages = searcher.search(&query, &TopFieldDocs::new(sort_fields.clone(), 7));
+        while pages.len() < docs.len() {
+            let collector = TopFieldDocs::new(sort_fields.clone(), 7)
+                .set_search_after(pages.last().unwrap().clone());
+            let page = searcher.search(&query, &collector);
+            assert!(!page.is_empty());
+            pages.extend(page);
+        }
+        assert_eq!(pages, docs);
+    }
+
+    #[test]
+    fn test_cached_points() {
+        let (searcher, _, price, _) = searcher();
+        let collector = TopFieldDocs::new(vec![SortField::Field(price, Order::Desc)], 10);
+        let top_docs = searcher.search(&MatchAllQuery, &collector);
+        for segment_reader in searcher.segment_readers() {
+            let points = segment_reader.uninverted_points(price).unwrap();
+            assert!(std::ptr::eq(
+                points,
+                segment_reader.uninverted_points(price).unwrap()
+            ));
+        }
+        assert_eq!(searcher.search(&MatchAllQuery, &collector), top_docs);
+    }
+
+    #[test]
+    fn test_doc_values() {
+        let mut builder = Schema::builder();
+        let sizes = builder.add_u64_field(
+            "sizes",
+            NumericOptions::default().set_doc_values(Cardinality::Multi),
+        );
+        let schema = builder.build();
+
+        let mut writer = IndexWriter::new(schema);
+        for (i, values) in [&[3u64, 40][..], &[], &[7], &[1, 9], &[40]]
+            .iter()
+            .enumerate()
+        {
+            if i == 3 {
+                writer.flush();
+            }
+            let mut doc = Document::new();
+            for &value in *values {
+                doc.add_u64(sizes, value);
+            }
+            writer.add_document(&doc);
+        }
+        let searcher = writer.reader().searcher();
+
+        let docs = |order| {
+            searcher
+                .search(
+                    &MatchAllQuery,
+                    &TopFieldDocs::new(vec![SortField::Field(sizes, order)], 10),
+                )
+                .into_iter()
+                .map(|field_doc| {
+                    let SortValue::Field(value) = &field_doc.sort_values[0] else {
+                        panic!("Not a field value");
+                    };
+                    (
+                        field_doc.segment_ord,
+                        field_doc.doc,
+                        value.as_ref().and_then(Value::as_u64),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            docs(Order::Asc),
+            [
+                (1, 0, Some(1)),
+                (0, 0, Some(3)),
+                (0, 2, Some(7)),
+                (1, 1, Some(40)),
+                (0, 1, None)
+            ]
+        );
+        assert_eq!(
+            docs(Order::Desc),
+            [
+                (0, 0, Some(40)),
+                (1, 1, Some(40)),
+                (1, 0, Some(9)),
+                (0, 2, Some(7)),
+                (0, 1, None)
+            ]
+        );
+    }
+}
@@ -0,0 +1,10 @@
+use crate::segment::SegmentReader;
+
+use super::Scorer;
+
+// A query prepared for a searcher, with the statistics it needs to score
+// docs, from which scorers are created for each segment.
+pub trait Weight: Send + Sync {
+    // Returns `None` when no doc of the segment can match.
+    fn scorer<'a>(&'a self, segment_reader: &'a SegmentReader) -> Option<Box<dyn Scorer + 'a>>;
+}
@@ -0,0 +1,78 @@
+use crate::{automata::RegexAutomaton, index::Searcher, schema::Field};
+
+use super::{
+    multi_term_query::{expand_terms, rewrite, DEFAULT_MAX_EXPANSIONS},
+    Query, RewriteMethod, Weight,
+};
+
+// Matches the docs containing a term matching a wildcard pattern, where `*`
+// matches any chars, `?` matches a single char, and `\` escapes the next char.
+//
+// Only the first `max_expansions` matching terms in term order are searched.
+pub struct WildcardQuery {
+    field: Field,
+    pattern: String,
+    max_expansions: usize,
+    rewrite_method: RewriteMethod,
+}
+
+impl WildcardQuery {
+    pub fn new(field: Field, pattern: &str) -> Self {
+        Self {
+            field,
+            pattern: pattern.to_string(),
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+            rewrite_method: RewriteMethod::default(),
+        }
+    }
+
+    pub fn set_max_expansions(mut self, max_expansions: usize) -> Self {
+        self.max_expansions = max_expansions;
+        self
+    }
+
+    pub fn set_rewrite_method(mut self, rewrite_method: RewriteMethod) -> Self {
+        self.rewrite_method = rewrite_method;
+        self
+    }
+}
+
+impl Query for WildcardQuery {
+    fn weight(&self, searcher: &Searcher) -> Box<dyn Weight> {
+        let automaton = RegexAutomaton::wildcard(&self.pattern);
+        let mut terms = expand_terms(searcher, self.field, &automaton);
+        terms.truncate(self.max_expansions);
+        rewrite(searcher, self.field, terms, self.rewrite_method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::IndexRecordOption,
+        search::{multi_term_query::search_docs, text_searcher},
+    };
+
+    use super::WildcardQuery;
+
+    #[test]
+    fn test_simple() {
+        let (searcher, body) = text_searcher(
+            "whitespace",
+            IndexRecordOption::Basic,
+            &[&["test", "text", "toast"], &["tent", "best", "t*t"]],
+        );
+
+        let query = WildcardQuery::new(body, "te?t");
+        assert_eq!(search_docs(&searcher, &query), [(0, 0), (0, 1), (1, 0)]);
+        let query = WildcardQuery::new(body, "t*t");
+        assert_eq!(
+            search_docs(&searcher, &query),
+            [(0, 0), (0, 1), (0, 2), (1, 0), (1, 2)]
+        );
+        let query = WildcardQuery::new(body, r"t\*t");
+        assert_eq!(search_docs(&searcher, &query), [(1, 2)]);
+        let query = WildcardQuery::new(body, "*est").set_max_expansions(1);
+        assert_eq!(search_docs(&searcher, &query), [(1, 1)]);
+    }
+}
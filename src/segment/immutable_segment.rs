@@ -0,0 +1,328 @@
+use std::sync::OnceLock;
+
+use crate::{
+    columnar::{ImmutableDocValues, NumericUpdates},
+    points::{KdTree, UninvertedPoints},
+    postings::{
+        BlockPostingsIterator, BlockPostingsSerializer, PostingsIterator, TermDictionary,
+        TermDictionaryBuilder,
+    },
+    schema::{Field, IndexRecordOption, Schema},
+    store::DocStore,
+    util::byte4_to_int,
+    DocId, TERMINATED,
+};
+
+use super::RealtimeSegmentReader;
+
+struct ImmutableFieldIndex {
+    record_option: IndexRecordOption,
+    term_dictionary: TermDictionary,
+    postings: Vec<u8>,
+    positions: Vec<u8>,
+    // One byte per doc, absent when the field omits norms.
+    norms: Option<Vec<u8>>,
+    total_field_length: u64,
+    field_doc_count: u64,
+}
+
+// A segment that is never written again, built from a snapshot of a realtime
+// segment. Its postings are stored in the block format.
+pub struct ImmutableSegment {
+    schema: Schema,
+    max_doc: DocId,
+    field_indexes: Vec<Option<ImmutableFieldIndex>>,
+    kd_trees: Vec<Option<KdTree>>,
+    // Built from the kd-trees on the first sort by a field without doc values.
+    uninverted_points: Vec<OnceLock<UninvertedPoints>>,
+    doc_values: Vec<Option<ImmutableDocValues>>,
+    // The only part written after the build: the updates of the updatable
+    // fields, overlaid on their doc values.
+    numeric_updates: Vec<Option<NumericUpdates>>,
+    doc_store: DocStore,
+}
+
+impl ImmutableSegment {
+    pub fn build(segment_reader: &RealtimeSegmentReader) -> Self {
+        let segment = segment_reader.segment();
+        let schema = segment.schema().clone();
+        let max_doc = segment_reader.max_doc();
+
+        let field_indexes = schema
+            .fields()
+            .map(|(field, _)| {
+                let terms = segment.terms(field)?;
+                let record_option = segment.record_option(field);
+                let mut terms: Vec<_> = terms.collect();
+                terms.sort_by_key(|&(term, _)| term);
+
+                let mut serializer = BlockPostingsSerializer::new(record_option);
+                let mut builder = TermDictionaryBuilder::new();
+                for (term, posting_writer) in terms {
+                    let mut postings = posting_writer.postings(max_doc);
+                    if postings.doc() == TERMINATED {
+                        continue;
+                    }
+                    let term_info =
+                        serializer.serialize(&mut postings, |doc| segment.field_length(field, doc));
+                    builder.insert(term, term_info);
+                }
+                let (postings, positions) = serializer.finish();
+                let norms = schema.field_entry(field).has_norms().then(|| {
+                    (0..max_doc)
+                        .map(|doc| segment.norm(field, doc).unwrap())
+                        .collect()
+                });
+
+                Some(ImmutableFieldIndex {
+                    record_option,
+                    term_dictionary: builder.build(),
+                    postings,
+                    positions,
+                    norms,
+                    total_field_length: segment.total_field_length(field, max_doc),
+                    field_doc_count: segment.field_doc_count(field, max_doc),
+                })
+            })
+            .collect();
+
+        let kd_trees = schema
+            .fields()
+            .map(|(field, _)| {
+                let point_writer = segment.point_writer(field)?;
+                let mut docs = Vec::new();
+                let mut values = Vec::new();
+                point_writer.for_each(max_doc, |doc, point| {
+                    docs.push(doc);
+                    values.extend_from_slice(point);
+                });
+                Some(KdTree::build(point_writer.dimensions(), docs, values))
+            })
+            .collect();
+        let uninverted_points = schema.fields().map(|_| OnceLock::new()).collect();
+
+        let doc_values = schema
+            .fields()
+            .map(|(field, _)| {
+                let doc_values_writer = segment.doc_values_writer(field)?;
+                Some(ImmutableDocValues::build(
+                    doc_values_writer,
+                    max_doc,
+                    segment_reader.update_seq(),
+                ))
+            })
+            .collect();
+        let numeric_updates = schema
+            .fields()
+            .map(|(_, field_entry)| {
+                field_entry
+                    .field_type()
+                    .is_updatable()
+                    .then(NumericUpdates::new)
+            })
+            .collect();
+
+        Self {
+            schema,
+            max_doc,
+            field_indexes,
+            kd_trees,
+            uninverted_points,
+            doc_values,
+            numeric_updates,
+            doc_store: DocStore::build(segment.store_writer(), max_doc),
+        }
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn max_doc(&self) -> DocId {
+        self.max_doc
+    }
+
+    pub fn term_dictionary(&self, field: Field) -> Option<&TermDictionary> {
+        self.field_index(field)
+            .map(|field_index| &field_index.term_dictionary)
+    }
+
+    pub fn postings(&self, field: Field, term: &str) -> Option<BlockPostingsIterator<'_>> {
+        let field_index = self.field_index(field)?;
+        let term_info = field_index.term_dictionary.get(term)?;
+        Some(BlockPostingsIterator::new(
+            field_index.record_option,
+            &field_index.postings,
+            &field_index.positions,
+            term_info,
+        ))
+    }
+
+    pub fn doc_freq(&self, field: Field, term: &str) -> u32 {
+        self.field_index(field)
+            .and_then(|field_index| field_index.term_dictionary.get(term))
+            .map_or(0, |term_info| term_info.doc_freq)
+    }
+
+    pub fn norm(&self, field: Field, doc: DocId) -> Option<u8> {
+        self.field_index(field)
+            .and_then(|field_index| field_index.norms.as_ref())
+            .map(|norms| norms[doc as usize])
+    }
+
+    // Decoded from the norm like in the realtime segment the doc was added to.
+    pub fn field_length(&self, field: Field, doc: DocId) -> u32 {
+        match self.field_index(field) {
+            Some(field_index) => field_index
+                .norms
+                .as_ref()
+                .map_or(1, |norms| byte4_to_int(norms[doc as usize])),
+            None => 0,
+        }
+    }
+
+    pub fn total_field_length(&self, field: Field) -> u64 {
+        self.field_index(field)
+            .map_or(0, |field_index| field_index.total_field_length)
+    }
+
+    pub fn field_doc_count(&self, field: Field) -> u64 {
+        self.field_index(field)
+            .map_or(0, |field_index| field_index.field_doc_count)
+    }
+
+    pub fn kd_tree(&self, field: Field) -> Option<&KdTree> {
+        self.kd_trees
+            .get(field.field_id() as usize)
+            .and_then(|kd_tree| kd_tree.as_ref())
+    }
+
+    pub fn uninverted_points(&self, field: Field) -> Option<&UninvertedPoints> {
+        let kd_tree = self.kd_tree(field)?;
+        Some(
+            self.uninverted_points[field.field_id() as usize].get_or_init(|| {
+                let mut uninverted_points = UninvertedPoints::new(self.max_doc);
+                kd_tree.for_each(|doc, point| uninverted_points.add(doc, point[0]));
+                uninverted_points
+            }),
+        )
+    }
+
+    pub fn doc_values(&self, field: Field) -> Option<&ImmutableDocValues> {
+        self.doc_values
+            .get(field.field_id() as usize)
+            .and_then(|doc_values| doc_values.as_ref())
+    }
+
+    pub fn doc_store(&self) -> &DocStore {
+        &self.doc_store
+    }
+
+    pub fn numeric_updates(&self, field: Field) -> Option<&NumericUpdates> {
+        self.numeric_updates
+            .get(field.field_id() as usize)
+            .and_then(|updates| updates.as_ref())
+    }
+
+    fn field_index(&self, field: Field) -> Option<&ImmutableFieldIndex> {
+        self.field_indexes
+            .get(field.field_id() as usize)
+            .and_then(|field_index| field_index.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        analysis::AnalyzerRegistry,
+        document::Document,
+        postings::{collect_postings, PostingsIterator},
+        schema::{IndexRecordOption, Schema, TextOptions},
+        segment::{RealtimeSegment, RealtimeSegmentReader},
+    };
+
+    use super::ImmutableSegment;
+
+    #[test]
+    fn test_simple() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard")
+                .set_index_record_option(IndexRecordOption::WithPositionsAndOffsets),
+        );
+        let tag = builder.add_text_field("tag", TextOptions::default().set_indexed());
+        let note = builder.add_text_field("note", TextOptions::default());
+        let body = builder.add_text_field(
+            "body",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard")
+                .set_omit_norms(),
+        );
+        let schema = builder.build();
+
+        let segment = Arc::new(RealtimeSegment::new(schema, &AnalyzerRegistry::default()));
+        let count = 300;
+        for i in 0..count {
+            let mut doc = Document::new();
+            doc.add_text(title, format!("hello term{} hello", i));
+            doc.add_text(tag, format!("tag{}", i % 3));
+            doc.add_text(note, "note");
+            doc.add_text(body, "word ".repeat(i as usize % 50));
+            segment.add_document(&doc);
+        }
+        let segment_reader = RealtimeSegmentReader::new(segment.clone());
+
+        let mut doc = Document::new();
+        doc.add_text(title, "hello future");
+        segment.add_document(&doc);
+
+        let segment = ImmutableSegment::build(&segment_reader);
+        assert_eq!(segment.max_doc(), count);
+
+        let hello = collect_postings(&mut segment.postings(title, "hello").unwrap());
+        let expect: Vec<_> = (0..count).map(|i| (i, 2)).collect();
+        assert_eq!(hello, expect);
+
+        let mut postings = segment.postings(title, "term7").unwrap();
+        assert_eq!(postings.doc(), 7);
+        assert_eq!(postings.positions(), &[1]);
+        assert_eq!(postings.offsets(), &[(6, 11)]);
+
+        let mut postings = segment.postings(title, "hello").unwrap();
+        assert_eq!(postings.positions(), &[0, 2]);
+        assert_eq!(postings.offsets(), &[(0, 5), (12, 17)]);
+        postings.next();
+        assert_eq!(postings.positions(), &[0, 2]);
+
+        let tag1 = collect_postings(&mut segment.postings(tag, "tag1").unwrap());
+        let expect: Vec<_> = (0..count).filter(|i| i % 3 == 1).map(|i| (i, 1)).collect();
+        assert_eq!(tag1, expect);
+
+        assert!(segment.postings(title, "future").is_none());
+        assert!(segment.postings(note, "note").is_none());
+        assert_eq!(segment.term_dictionary(tag).unwrap().len(), 3);
+
+        assert_eq!(segment.doc_freq(title, "hello"), count);
+        assert_eq!(segment.doc_freq(tag, "tag1"), count / 3);
+        assert_eq!(segment.doc_freq(title, "future"), 0);
+        assert_eq!(segment.field_length(title, 7), 3);
+        assert_eq!(segment.field_length(note, 7), 0);
+        assert_eq!(segment.norm(title, 7), Some(3));
+        assert_eq!(segment.norm(note, 7), None);
+        assert_eq!(segment.norm(body, 7), None);
+        assert_eq!(segment.field_length(body, 7), 1);
+        assert_eq!(segment.total_field_length(body), count as u64);
+        assert_eq!(segment.total_field_length(title), 3 * count as u64);
+        assert_eq!(segment.total_field_length(tag), count as u64);
+        assert_eq!(segment.field_doc_count(title), count as u64);
+        // Without norms, docs with an empty body still count.
+        assert_eq!(segment.field_doc_count(body), count as u64);
+        assert_eq!(segment.field_doc_count(note), 0);
+    }
+}
@@ -0,0 +1,123 @@
+use std::{ops::RangeInclusive, sync::Arc};
+
+use crate::{
+    automata::Automaton,
+    columnar::{BinaryColumn, Column, SortedColumn, UpdatedNumericDocValues},
+    points::UninvertedPoints,
+    postings::BlockPostingsIterator,
+    schema::Field,
+    DocId,
+};
+
+use super::ImmutableSegment;
+
+// Numbers of updatable fields are read with the updates up to the update seq
+// of the reader.
+pub struct ImmutableSegmentReader {
+    segment: Arc<ImmutableSegment>,
+    update_seq: u64,
+}
+
+impl ImmutableSegmentReader {
+    // Sees every update. Must only be used from the writer thread, as the
+    // updates it reads may be freed by the next ones.
+    pub fn new(segment: Arc<ImmutableSegment>) -> Self {
+        Self::with_update_seq(segment, u64::MAX)
+    }
+
+    pub fn with_update_seq(segment: Arc<ImmutableSegment>, update_seq: u64) -> Self {
+        Self {
+            segment,
+            update_seq,
+        }
+    }
+
+    pub fn max_doc(&self) -> DocId {
+        self.segment.max_doc()
+    }
+
+    pub fn segment(&self) -> &ImmutableSegment {
+        &self.segment
+    }
+
+    pub fn postings(&self, field: Field, term: &str) -> Option<BlockPostingsIterator<'_>> {
+        self.segment.postings(field, term)
+    }
+
+    pub fn doc_freq(&self, field: Field, term: &str) -> u32 {
+        self.segment.doc_freq(field, term)
+    }
+
+    pub fn matching_terms<A: Automaton>(&self, field: Field, automaton: &A) -> Vec<String> {
+        let Some(term_dictionary) = self.segment.term_dictionary(field) else {
+            return Vec::new();
+        };
+        term_dictionary
+            .search(automaton)
+            .into_iter()
+            .map(|term_ord| term_dictionary.term(term_ord).to_string())
+            .collect()
+    }
+
+    pub fn visit_points<F: FnMut(DocId)>(
+        &self,
+        field: Field,
+        ranges: &[RangeInclusive<u64>],
+        visitor: F,
+    ) {
+        if let Some(kd_tree) = self.segment.kd_tree(field) {
+            kd_tree.visit(ranges, visitor);
+        }
+    }
+
+    pub fn for_each_point<F: FnMut(DocId, &[u64])>(&self, field: Field, visitor: F) {
+        if let Some(kd_tree) = self.segment.kd_tree(field) {
+            kd_tree.for_each(visitor);
+        }
+    }
+
+    // The values of the points of a field indexed in one dimension, by doc.
+    pub fn uninverted_points(&self, field: Field) -> Option<&UninvertedPoints> {
+        self.segment.uninverted_points(field)
+    }
+
+    pub fn numeric_doc_values(&self, field: Field) -> Option<UpdatedNumericDocValues<'_, &Column>> {
+        let column = self.segment.doc_values(field)?.numeric()?;
+        Some(UpdatedNumericDocValues::new(
+            column,
+            self.segment.numeric_updates(field),
+            self.update_seq,
+            self.segment.max_doc(),
+        ))
+    }
+
+    pub fn binary_doc_values(&self, field: Field) -> Option<&BinaryColumn> {
+        self.segment.doc_values(field)?.binary()
+    }
+
+    pub fn sorted_doc_values(&self, field: Field) -> Option<&SortedColumn> {
+        self.segment.doc_values(field)?.sorted()
+    }
+
+    // The serialized stored field values of the doc.
+    pub fn doc_bytes(&self, doc: DocId) -> Vec<u8> {
+        assert!(doc < self.max_doc(), "Doc {} does not exist", doc);
+        self.segment.doc_store().doc_bytes(doc)
+    }
+
+    pub fn norm(&self, field: Field, doc: DocId) -> Option<u8> {
+        self.segment.norm(field, doc)
+    }
+
+    pub fn field_length(&self, field: Field, doc: DocId) -> u32 {
+        self.segment.field_length(field, doc)
+    }
+
+    pub fn total_field_length(&self, field: Field) -> u64 {
+        self.segment.total_field_length(field)
+    }
+
+    pub fn field_doc_count(&self, field: Field) -> u64 {
+        self.segment.field_doc_count(field)
+    }
+}
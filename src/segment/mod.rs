@@ -0,0 +1,11 @@
+mod immutable_segment;
+mod immutable_segment_reader;
+mod realtime_segment;
+mod realtime_segment_reader;
+mod segment_reader;
+
+pub use immutable_segment::ImmutableSegment;
+pub use immutable_segment_reader::ImmutableSegmentReader;
+pub use realtime_segment::RealtimeSegment;
+pub use realtime_segment_reader::RealtimeSegmentReader;
+pub use segment_reader::SegmentReader;
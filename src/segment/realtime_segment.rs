@@ -0,0 +1,638 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    iter, slice,
+    sync::Arc,
+};
+
+use crate::{
+    analysis::{Analyzer, AnalyzerRegistry, Token},
+    columnar::DocValuesWriter,
+    document::{Document, Value},
+    points::PointWriter,
+    postings::PostingWriter,
+    schema::{Field, FieldType, IndexRecordOption, Schema},
+    store::StoreWriter,
+    util::{
+        byte4_to_int, int_to_byte4, AcqRelUsize, ChunkedVector, ExponentialCapacityPolicy,
+        LayeredHashMap, LayeredHashMapIter, RelaxedU64,
+    },
+    DocId,
+};
+
+const TERM_DICTIONARY_INITIAL_CAPACITY: usize = 1024;
+const NORMS_CHUNK_EXPONENT: usize = 12;
+const NORMS_TREE_EXPONENT: usize = 4;
+const NORMS_CHECKPOINT_INTERVAL: usize = 64;
+
+type TermDictionary = LayeredHashMap<String, PostingWriter, RandomState, ExponentialCapacityPolicy>;
+
+#[derive(Default)]
+struct FieldTerms {
+    terms: HashMap<String, TermOccurrences>,
+    next_position: u32,
+    offset_base: usize,
+    length: u32,
+}
+
+#[derive(Default)]
+struct TermOccurrences {
+    positions: Vec<u32>,
+    offsets: Vec<(u32, u32)>,
+}
+
+impl FieldTerms {
+    fn add(&mut self, token: Token) {
+        let position = (self.next_position + token.position_increment).saturating_sub(1);
+        self.next_position = position + 1;
+        // Tokens stacked at the same position, like synonyms, are not counted
+        // in the field length.
+        if token.position_increment > 0 {
+            self.length += 1;
+        }
+        let occurrences = self.terms.entry(token.text).or_default();
+        occurrences.positions.push(position);
+        occurrences.offsets.push((
+            (self.offset_base + token.start_offset) as u32,
+            (self.offset_base + token.end_offset) as u32,
+        ));
+    }
+
+    // Offsets of the following values of a multi-valued field start after
+    // the end of this value.
+    fn end_value(&mut self, len: usize) {
+        self.offset_base += len;
+    }
+}
+
+// The norm of a field in each doc, which is its length in one byte with a
+// lossy encoding. The total of the decoded lengths and the number of docs with
+// the field before every checkpoint interval are recorded as well, so that
+// the totals up to any doc are summed from at most one interval of norms.
+struct FieldNorms {
+    norms: ChunkedVector<u8>,
+    checkpoints: ChunkedVector<(u64, u64)>,
+    total: RelaxedU64,
+    doc_count: RelaxedU64,
+}
+
+impl FieldNorms {
+    fn new() -> Self {
+        Self {
+            norms: ChunkedVector::new(NORMS_CHUNK_EXPONENT, NORMS_TREE_EXPONENT),
+            checkpoints: ChunkedVector::new(NORMS_CHUNK_EXPONENT, NORMS_TREE_EXPONENT),
+            total: RelaxedU64::new(0),
+            doc_count: RelaxedU64::new(0),
+        }
+    }
+
+    fn push(&self, length: u32) {
+        let total = self.total.load();
+        let doc_count = self.doc_count.load();
+        if self.norms.len().is_multiple_of(NORMS_CHECKPOINT_INTERVAL) {
+            self.checkpoints.push((total, doc_count));
+        }
+        let norm = int_to_byte4(length);
+        self.norms.push(norm);
+        self.total.store(total + byte4_to_int(norm) as u64);
+        self.doc_count.store(doc_count + (length > 0) as u64);
+    }
+
+    fn get(&self, doc: DocId) -> u8 {
+        self.norms.get(doc as usize).copied().unwrap_or_default()
+    }
+
+    // The total length of the docs below `max_doc` and the number of them
+    // with the field, which must have been added.
+    fn totals(&self, max_doc: DocId) -> (u64, u64) {
+        if max_doc == 0 {
+            return (0, 0);
+        }
+        let checkpoint = (max_doc as usize - 1) / NORMS_CHECKPOINT_INTERVAL;
+        let start = checkpoint * NORMS_CHECKPOINT_INTERVAL;
+        let (mut total, mut doc_count) = *self.checkpoints.get(checkpoint).unwrap();
+        for doc in start..max_doc as usize {
+            let norm = *self.norms.get(doc).unwrap();
+            total += byte4_to_int(norm) as u64;
+            doc_count += (norm > 0) as u64;
+        }
+        (total, doc_count)
+    }
+}
+
+pub struct RealtimeSegment {
+    schema: Schema,
+    analyzers: Vec<Option<Arc<Analyzer>>>,
+    term_dictionaries: Vec<Option<TermDictionary>>,
+    field_norms: Vec<Option<FieldNorms>>,
+    point_writers: Vec<Option<PointWriter>>,
+    doc_values_writers: Vec<Option<DocValuesWriter>>,
+    store_writer: StoreWriter,
+    doc_count: AcqRelUsize,
+}
+
+impl RealtimeSegment {
+    // Panics if a field refers to an analyzer missing from the registry.
+    pub fn new(schema: Schema, analyzer_registry: &AnalyzerRegistry) -> Self {
+        let analyzers = schema
+            .fields()
+            .map(|(_, field_entry)| match field_entry.field_type() {
+                FieldType::Text(options) => options.analyzer().map(|name| {
+                    analyzer_registry.get(name).unwrap_or_else(|| {
+                        panic!(
+                            "Analyzer `{}` of field `{}` is not registered",
+                            name,
+                            field_entry.name()
+                        )
+                    })
+                }),
+                _ => None,
+            })
+            .collect();
+        let term_dictionaries = schema
+            .fields()
+            .map(|(_, field_entry)| {
+                if field_entry.field_type().index_record_option().is_some() {
+                    Some(TermDictionary::with_initial_capacity(
+                        TERM_DICTIONARY_INITIAL_CAPACITY,
+                        RandomState::new(),
+                        ExponentialCapacityPolicy,
+                    ))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let field_norms = schema
+            .fields()
+            .map(|(_, field_entry)| field_entry.has_norms().then(FieldNorms::new))
+            .collect();
+        let point_writers = schema
+            .fields()
+            .map(|(_, field_entry)| {
+                field_entry
+                    .field_type()
+                    .point_dimensions()
+                    .map(PointWriter::new)
+            })
+            .collect();
+        let doc_values_writers = schema
+            .fields()
+            .map(|(_, field_entry)| DocValuesWriter::new(field_entry.field_type()))
+            .collect();
+
+        Self {
+            schema,
+            analyzers,
+            term_dictionaries,
+            field_norms,
+            point_writers,
+            doc_values_writers,
+            store_writer: StoreWriter::new(),
+            doc_count: AcqRelUsize::new(0),
+        }
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    // Must only be called from the single writer thread. The document becomes
+    // visible to readers once the doc count is published at the end. Values of
+    // another type than their field are ignored.
+    pub(crate) fn add_document(&self, doc: &Document) -> DocId {
+        let doc_id = self.doc_count() as DocId;
+
+        // Values are indexed into their field, then into each of its copies.
+        let mut field_terms: HashMap<Field, FieldTerms> = HashMap::new();
+        let mut field_doc_values: HashMap<Field, Vec<&Value>> = HashMap::new();
+        for field_value in doc.field_values() {
+            let source = field_value.field();
+            let targets = self.schema.copy_targets(source);
+            for field in iter::once(source).chain(targets.iter().copied()) {
+                if self.doc_values_writer(field).is_some() {
+                    field_doc_values
+                        .entry(field)
+                        .or_default()
+                        .push(field_value.value());
+                }
+                if let Some(point_writer) = self.point_writer(field) {
+                    let field_type = self.schema.field_entry(field).field_type();
+                    if let Some(point) = to_point(field_type, field_value.value())
+                        .filter(|point| point.len() == point_writer.dimensions())
+                    {
+                        point_writer.add_point(doc_id, &point);
+                    }
+                    continue;
+                }
+                if self.term_dictionary(field).is_none() {
+                    continue;
+                }
+                // Only text fields have terms.
+                let Value::Str(text) = field_value.value() else {
+                    continue;
+                };
+                let field_terms = field_terms.entry(field).or_default();
+                match &self.analyzers[field.field_id() as usize] {
+                    Some(analyzer) => {
+                        for token in analyzer.analyze(text) {
+                            field_terms.add(token);
+                        }
+                    }
+                    None => field_terms.add(Token::new(text.clone(), 0, text.len())),
+                }
+                field_terms.end_value(text.len());
+            }
+        }
+
+        for (field_id, field_norms) in self.field_norms.iter().enumerate() {
+            if let Some(field_norms) = field_norms {
+                let field = Field::from_field_id(field_id as u32);
+                field_norms.push(field_terms.get(&field).map_or(0, |terms| terms.length));
+            }
+        }
+
+        // Every doc is added to the doc values, with or without values.
+        for (field_id, doc_values_writer) in self.doc_values_writers.iter().enumerate() {
+            if let Some(doc_values_writer) = doc_values_writer {
+                let field = Field::from_field_id(field_id as u32);
+                let values = field_doc_values.remove(&field).unwrap_or_default();
+                doc_values_writer.add_doc(values);
+            }
+        }
+
+        // Only the values of the doc are stored, not their copies.
+        self.store_writer.add_doc(
+            doc.field_values()
+                .iter()
+                .filter(|field_value| self.schema.field_entry(field_value.field()).is_stored()),
+        );
+
+        for (field, field_terms) in field_terms {
+            let term_dictionary = self.term_dictionary(field).unwrap();
+            let record_option = self.record_option(field);
+            for (term, occurrences) in field_terms.terms {
+                let posting_writer = match term_dictionary.get(&term) {
+                    Some(posting_writer) => posting_writer,
+                    None => {
+                        term_dictionary.insert(term.clone(), PostingWriter::new(record_option));
+                        term_dictionary.get(&term).unwrap()
+                    }
+                };
+                posting_writer.add_doc(doc_id, &occurrences.positions, &occurrences.offsets);
+            }
+        }
+
+        self.doc_count.store(doc_id as usize + 1);
+
+        doc_id
+    }
+
+    pub fn doc_count(&self) -> usize {
+        self.doc_count.load()
+    }
+
+    // The encoded length of the field in the doc, if the field has norms.
+    pub fn norm(&self, field: Field, doc: DocId) -> Option<u8> {
+        self.field_norms(field)
+            .map(|field_norms| field_norms.get(doc))
+    }
+
+    // The number of tokens indexed in the field of the doc, decoded from its
+    // norm. Without norms, every doc counts as having a field of length one,
+    // and fields that are not indexed have a length of zero.
+    pub fn field_length(&self, field: Field, doc: DocId) -> u32 {
+        match self.norm(field, doc) {
+            Some(norm) => byte4_to_int(norm),
+            None => self.has_terms(field) as u32,
+        }
+    }
+
+    // The total length of the field in the docs below `max_doc`, which must
+    // not exceed the doc count.
+    pub fn total_field_length(&self, field: Field, max_doc: DocId) -> u64 {
+        match self.field_norms(field) {
+            Some(field_norms) => field_norms.totals(max_doc).0,
+            None if self.has_terms(field) => max_doc as u64,
+            None => 0,
+        }
+    }
+
+    // The number of docs below `max_doc` with at least a token in the field,
+    // which must not exceed the doc count. Without norms, every doc counts as
+    // having the field, like for its length.
+    pub fn field_doc_count(&self, field: Field, max_doc: DocId) -> u64 {
+        match self.field_norms(field) {
+            Some(field_norms) => field_norms.totals(max_doc).1,
+            None if self.has_terms(field) => max_doc as u64,
+            None => 0,
+        }
+    }
+
+    pub(crate) fn posting_writer(&self, field: Field, term: &str) -> Option<&PostingWriter> {
+        self.term_dictionary(field)
+            .and_then(|term_dictionary| term_dictionary.get(term))
+    }
+
+    pub(crate) fn terms(
+        &self,
+        field: Field,
+    ) -> Option<LayeredHashMapIter<'_, String, PostingWriter>> {
+        self.term_dictionary(field)
+            .map(|term_dictionary| term_dictionary.iter())
+    }
+
+    pub(crate) fn point_writer(&self, field: Field) -> Option<&PointWriter> {
+        self.point_writers
+            .get(field.field_id() as usize)
+            .and_then(|point_writer| point_writer.as_ref())
+    }
+
+    pub(crate) fn store_writer(&self) -> &StoreWriter {
+        &self.store_writer
+    }
+
+    pub(crate) fn doc_values_writer(&self, field: Field) -> Option<&DocValuesWriter> {
+        self.doc_values_writers
+            .get(field.field_id() as usize)
+            .and_then(|doc_values_writer| doc_values_writer.as_ref())
+    }
+
+    pub fn record_option(&self, field: Field) -> IndexRecordOption {
+        self.schema
+            .field_entry(field)
+            .field_type()
+            .index_record_option()
+            .unwrap_or_default()
+    }
+
+    fn field_norms(&self, field: Field) -> Option<&FieldNorms> {
+        self.field_norms
+            .get(field.field_id() as usize)
+            .and_then(|field_norms| field_norms.as_ref())
+    }
+
+    fn term_dictionary(&self, field: Field) -> Option<&TermDictionary> {
+        self.term_dictionaries
+            .get(field.field_id() as usize)
+            .and_then(|term_dictionary| term_dictionary.as_ref())
+    }
+
+    fn has_terms(&self, field: Field) -> bool {
+        self.term_dictionary(field).is_some()
+    }
+}
+
+// The value as a point of the type of the field, encoded as u64s of the same
+// order, or `None` if any of its numbers has another type.
+fn to_point(field_type: &FieldType, value: &Value) -> Option<Vec<u64>> {
+    let values = match value {
+        Value::Point(values) => values.as_slice(),
+        value => slice::from_ref(value),
+    };
+    values
+        .iter()
+        .map(|value| value.to_field_sortable_u64(field_type))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::{
+            Analyzer, AnalyzerRegistry, EdgeNGramFilter, LowerCaseFilter, StandardTokenizer,
+        },
+        document::Document,
+        postings::{collect_postings, PostingsIterator},
+        schema::{Field, IndexRecordOption, Schema, TextOptions},
+        DocId,
+    };
+
+    use super::RealtimeSegment;
+
+    fn postings(segment: &RealtimeSegment, field: Field, term: &str) -> Option<Vec<(u32, u32)>> {
+        let max_doc = segment.doc_count() as DocId;
+        segment
+            .posting_writer(field, term)
+            .map(|posting_writer| collect_postings(&mut posting_writer.postings(max_doc)))
+    }
+
+    #[test]
+    fn test_simple() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let tag = builder.add_text_field("tag", TextOptions::default().set_indexed());
+        let note = builder.add_text_field("note", TextOptions::default());
+        let schema = builder.build();
+
+        let segment = RealtimeSegment::new(schema, &AnalyzerRegistry::default());
+        assert_eq!(segment.doc_count(), 0);
+
+        let mut doc = Document::new();
+        doc.add_text(title, "hello world hello");
+        doc.add_text(tag, "hello world");
+        doc.add_text(note, "hello");
+        assert_eq!(segment.add_document(&doc), 0);
+
+        let mut doc = Document::new();
+        doc.add_text(title, "world");
+        assert_eq!(segment.add_document(&doc), 1);
+        assert_eq!(segment.doc_count(), 2);
+
+        assert_eq!(postings(&segment, title, "hello"), Some(vec![(0, 2)]));
+        assert_eq!(
+            postings(&segment, title, "world"),
+            Some(vec![(0, 1), (1, 1)])
+        );
+
+        assert_eq!(postings(&segment, tag, "hello world"), Some(vec![(0, 1)]));
+        assert!(postings(&segment, tag, "hello").is_none());
+
+        assert!(postings(&segment, note, "hello").is_none());
+        assert!(postings(&segment, title, "rust").is_none());
+
+        let mut terms: Vec<_> = segment
+            .terms(title)
+            .unwrap()
+            .map(|(term, _)| term.as_str())
+            .collect();
+        terms.sort();
+        assert_eq!(terms, vec!["hello", "world"]);
+        assert!(segment.terms(note).is_none());
+    }
+
+    #[test]
+    fn test_positions_and_offsets() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard")
+                .set_index_record_option(IndexRecordOption::WithPositionsAndOffsets),
+        );
+        let schema = builder.build();
+        let segment = RealtimeSegment::new(schema, &AnalyzerRegistry::default());
+
+        let mut doc = Document::new();
+        doc.add_text(title, "Hello, world hello");
+        doc.add_text(title, "hello");
+        segment.add_document(&doc);
+
+        let posting_writer = segment.posting_writer(title, "hello").unwrap();
+        let mut postings = posting_writer.postings(1);
+        assert_eq!(postings.doc(), 0);
+        assert_eq!(postings.freq(), 3);
+        assert_eq!(postings.positions(), &[0, 2, 3]);
+        assert_eq!(postings.offsets(), &[(0, 5), (13, 18), (18, 23)]);
+
+        let posting_writer = segment.posting_writer(title, "world").unwrap();
+        let mut postings = posting_writer.postings(1);
+        assert_eq!(postings.positions(), &[1]);
+        assert_eq!(postings.offsets(), &[(7, 12)]);
+    }
+
+    #[test]
+    fn test_edge_ngrams() {
+        let mut registry = AnalyzerRegistry::default();
+        registry.register(
+            "autocomplete",
+            Analyzer::new(StandardTokenizer)
+                .add_token_filter(LowerCaseFilter)
+                .add_token_filter(EdgeNGramFilter::new(1, 10)),
+        );
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("autocomplete"),
+        );
+        let segment = RealtimeSegment::new(builder.build(), &registry);
+
+        let mut doc = Document::new();
+        doc.add_text(title, "Rust search");
+        segment.add_document(&doc);
+        let mut doc = Document::new();
+        doc.add_text(title, "Ruby");
+        segment.add_document(&doc);
+
+        assert_eq!(postings(&segment, title, "ru"), Some(vec![(0, 1), (1, 1)]));
+        assert_eq!(postings(&segment, title, "sea"), Some(vec![(0, 1)]));
+        assert!(postings(&segment, title, "ust").is_none());
+    }
+
+    #[test]
+    fn test_copy_to() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let title_keyword =
+            builder.add_sub_field(title, "keyword", TextOptions::default().set_indexed());
+        let body = builder.add_text_field("body", TextOptions::default());
+        let all = builder.add_text_field(
+            "all",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard")
+                .set_index_record_option(IndexRecordOption::WithPositions),
+        );
+        builder.add_copy_to(title, all);
+        builder.add_copy_to(body, all);
+        let segment = RealtimeSegment::new(builder.build(), &AnalyzerRegistry::default());
+
+        let mut doc = Document::new();
+        doc.add_text(title, "Hello World");
+        doc.add_text(body, "hello rust");
+        segment.add_document(&doc);
+
+        assert_eq!(postings(&segment, title, "hello"), Some(vec![(0, 1)]));
+        assert_eq!(
+            postings(&segment, title_keyword, "Hello World"),
+            Some(vec![(0, 1)])
+        );
+        assert!(postings(&segment, title_keyword, "hello").is_none());
+        assert!(postings(&segment, body, "rust").is_none());
+        assert_eq!(postings(&segment, all, "rust"), Some(vec![(0, 1)]));
+
+        let posting_writer = segment.posting_writer(all, "hello").unwrap();
+        let mut postings = posting_writer.postings(1);
+        assert_eq!(postings.freq(), 2);
+        assert_eq!(postings.positions(), &[0, 2]);
+    }
+
+    #[test]
+    fn test_norms() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let tag = builder.add_text_field(
+            "tag",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard")
+                .set_omit_norms(),
+        );
+        let note = builder.add_text_field("note", TextOptions::default());
+        let segment = RealtimeSegment::new(builder.build(), &AnalyzerRegistry::default());
+
+        let count: usize = 200;
+        for i in 0..count {
+            let mut doc = Document::new();
+            if !i.is_multiple_of(3) {
+                doc.add_text(title, "word ".repeat(i % 7));
+            }
+            doc.add_text(tag, "a b c");
+            doc.add_text(note, "not indexed");
+            segment.add_document(&doc);
+        }
+        let length = |i: usize| if !i.is_multiple_of(3) { i % 7 } else { 0 } as u32;
+        for i in 0..count {
+            let doc = i as DocId;
+            assert_eq!(segment.norm(title, doc), Some(length(i) as u8));
+            assert_eq!(segment.field_length(title, doc), length(i));
+            assert_eq!(segment.norm(tag, doc), None);
+            assert_eq!(segment.field_length(tag, doc), 1);
+            assert_eq!(segment.field_length(note, doc), 0);
+        }
+        for max_doc in [0, 1, 63, 64, 65, 128, 199, 200] {
+            let total: u64 = (0..max_doc).map(|i| length(i) as u64).sum();
+            assert_eq!(segment.total_field_length(title, max_doc as DocId), total);
+            assert_eq!(
+                segment.total_field_length(tag, max_doc as DocId),
+                max_doc as u64
+            );
+            let doc_count = (0..max_doc).filter(|&i| length(i) > 0).count() as u64;
+            assert_eq!(segment.field_doc_count(title, max_doc as DocId), doc_count);
+            assert_eq!(
+                segment.field_doc_count(tag, max_doc as DocId),
+                max_doc as u64
+            );
+        }
+        assert_eq!(segment.total_field_length(note, count as DocId), 0);
+        assert_eq!(segment.field_doc_count(note, count as DocId), 0);
+
+        let mut doc = Document::new();
+        doc.add_text(title, "word ".repeat(100));
+        let doc = segment.add_document(&doc);
+        assert_eq!(segment.norm(title, doc), Some(57));
+        assert_eq!(segment.field_length(title, doc), 96);
+        let total: u64 = (0..count).map(|i| length(i) as u64).sum();
+        assert_eq!(
+            segment.total_field_length(title, count as DocId + 1),
+            total + 96
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use std::{
+    ops::RangeInclusive,
+    sync::{Arc, OnceLock},
+};
+
+use crate::{
+    automata::Automaton,
+    columnar::{
+        RealtimeBinaryDocValues, RealtimeNumericDocValues, RealtimeSortedDocValues,
+        UpdatedNumericDocValues,
+    },
+    points::UninvertedPoints,
+    postings::{PostingsIterator, RealtimePostingsIterator},
+    schema::Field,
+    DocId, TERMINATED,
+};
+
+use super::RealtimeSegment;
+
+// A point-in-time view of a realtime segment.
+//
+// The writer publishes the doc count only after every structure of a document
+// has been written, so capturing it once gives a watermark below which all
+// data is visible. Every iterator created from this reader is clamped to the
+// watermark, even if the underlying structures have already grown past it.
+// Likewise, numbers of updatable fields are read with the updates up to the
+// update seq of the reader.
+pub struct RealtimeSegmentReader {
+    segment: Arc<RealtimeSegment>,
+    max_doc: DocId,
+    update_seq: u64,
+    // Built from the points below the watermark on the first sort by a field
+    // without doc values, and shared by the searches of this snapshot.
+    uninverted_points: Vec<OnceLock<UninvertedPoints>>,
+}
+
+impl RealtimeSegmentReader {
+    // Sees every update. Must only be used from the writer thread, as the
+    // updates it reads may be freed by the next ones.
+    pub fn new(segment: Arc<RealtimeSegment>) -> Self {
+        Self::with_update_seq(segment, u64::MAX)
+    }
+
+    pub fn with_update_seq(segment: Arc<RealtimeSegment>, update_seq: u64) -> Self {
+        let max_doc = segment.doc_count() as DocId;
+        let uninverted_points = segment.schema().fields().map(|_| OnceLock::new()).collect();
+        Self {
+            segment,
+            max_doc,
+            update_seq,
+            uninverted_points,
+        }
+    }
+
+    pub fn max_doc(&self) -> DocId {
+        self.max_doc
+    }
+
+    pub fn update_seq(&self) -> u64 {
+        self.update_seq
+    }
+
+    pub fn segment(&self) -> &RealtimeSegment {
+        &self.segment
+    }
+
+    pub fn postings(&self, field: Field, term: &str) -> Option<RealtimePostingsIterator<'_>> {
+        self.segment
+            .posting_writer(field, term)
+            .map(|posting_writer| posting_writer.postings(self.max_doc))
+    }
+
+    // The number of docs below the watermark that contain the term.
+    pub fn doc_freq(&self, field: Field, term: &str) -> u32 {
+        self.segment
+            .posting_writer(field, term)
+            .map_or(0, |posting_writer| {
+                posting_writer.doc_freq_below(self.max_doc) as u32
+            })
+    }
+
+    // The terms with docs below the watermark that the automaton matches, in
+    // order. The terms of a realtime segment aren't sorted, so each of them is
+    // tested.
+    pub fn matching_terms<A: Automaton>(&self, field: Field, automaton: &A) -> Vec<String> {
+        let Some(terms) = self.segment.terms(field) else {
+            return Vec::new();
+        };
+        let mut matching_terms: Vec<_> = terms
+            .filter(|(term, posting_writer)| {
+                automaton.matches(term) && posting_writer.postings(self.max_doc).doc() != TERMINATED
+            })
+            .map(|(term, _)| term.clone())
+            .collect();
+        matching_terms.sort_unstable();
+        matching_terms
+    }
+
+    // Calls `visitor` with the doc of each point below the watermark within
+    // the ranges, once per matching point.
+    pub fn visit_points<F: FnMut(DocId)>(
+        &self,
+        field: Field,
+        ranges: &[RangeInclusive<u64>],
+        visitor: F,
+    ) {
+        if let Some(point_writer) = self.segment.point_writer(field) {
+            point_writer.visit(self.max_doc, ranges, visitor);
+        }
+    }
+
+    // Calls `visitor` with each point below the watermark.
+    pub fn for_each_point<F: FnMut(DocId, &[u64])>(&self, field: Field, visitor: F) {
+        if let Some(point_writer) = self.segment.point_writer(field) {
+            point_writer.for_each(self.max_doc, visitor);
+        }
+    }
+
+    // The values of the points of a field indexed in one dimension, by doc.
+    pub fn uninverted_points(&self, field: Field) -> Option<&UninvertedPoints> {
+        let point_writer = self.segment.point_writer(field)?;
+        Some(
+            self.uninverted_points[field.field_id() as usize].get_or_init(|| {
+                let mut uninverted_points = UninvertedPoints::new(self.max_doc);
+                point_writer.for_each(self.max_doc, |doc, point| {
+                    uninverted_points.add(doc, point[0]);
+                });
+                uninverted_points
+            }),
+        )
+    }
+
+    pub fn numeric_doc_values(
+        &self,
+        field: Field,
+    ) -> Option<UpdatedNumericDocValues<'_, RealtimeNumericDocValues<'_>>> {
+        let doc_values_writer = self.segment.doc_values_writer(field)?;
+        Some(UpdatedNumericDocValues::new(
+            doc_values_writer.numeric_doc_values(self.max_doc)?,
+            doc_values_writer.updates(),
+            self.update_seq,
+            self.max_doc,
+        ))
+    }
+
+    pub fn binary_doc_values(&self, field: Field) -> Option<RealtimeBinaryDocValues<'_>> {
+        self.segment
+            .doc_values_writer(field)?
+            .binary_doc_values(self.max_doc)
+    }
+
+    pub fn sorted_doc_values(&self, field: Field) -> Option<RealtimeSortedDocValues<'_>> {
+        self.segment
+            .doc_values_writer(field)?
+            .sorted_doc_values(self.max_doc)
+    }
+
+    // The serialized stored field values of the doc, below the watermark.
+    pub fn doc_bytes(&self, doc: DocId) -> &[u8] {
+        assert!(doc < self.max_doc, "Doc {} does not exist", doc);
+        self.segment.store_writer().doc_bytes(doc).unwrap()
+    }
+
+    pub fn norm(&self, field: Field, doc: DocId) -> Option<u8> {
+        self.segment.norm(field, doc)
+    }
+
+    pub fn field_length(&self, field: Field, doc: DocId) -> u32 {
+        self.segment.field_length(field, doc)
+    }
+
+    pub fn total_field_length(&self, field: Field) -> u64 {
+        self.segment.total_field_length(field, self.max_doc)
+    }
+
+    pub fn field_doc_count(&self, field: Field) -> u64 {
+        self.segment.field_doc_count(field, self.max_doc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread, time::Duration};
+
+    use crate::{
+        analysis::AnalyzerRegistry,
+        document::Document,
+        postings::collect_postings,
+        schema::{Schema, TextOptions},
+    };
+
+    use super::{RealtimeSegment, RealtimeSegmentReader};
+
+    #[test]
+    fn test_simple() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let schema = builder.build();
+        let segment = Arc::new(RealtimeSegment::new(schema, &AnalyzerRegistry::default()));
+
+        let mut doc = Document::new();
+        doc.add_text(title, "hello world");
+        segment.add_document(&doc);
+
+        let reader = RealtimeSegmentReader::new(segment.clone());
+        assert_eq!(reader.max_doc(), 1);
+
+        let mut doc = Document::new();
+        doc.add_text(title, "hello rust");
+        segment.add_document(&doc);
+
+        let hello: Vec<_> = collect_postings(&mut reader.postings(title, "hello").unwrap());
+        assert_eq!(hello, vec![(0, 1)]);
+        assert!(collect_postings(&mut reader.postings(title, "rust").unwrap()).is_empty());
+
+        let reader = RealtimeSegmentReader::new(segment.clone());
+        assert_eq!(reader.max_doc(), 2);
+        let hello: Vec<_> = collect_postings(&mut reader.postings(title, "hello").unwrap());
+        assert_eq!(hello, vec![(0, 1), (1, 1)]);
+        let rust: Vec<_> = collect_postings(&mut reader.postings(title, "rust").unwrap());
+        assert_eq!(rust, vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let schema = builder.build();
+        let segment = Arc::new(RealtimeSegment::new(schema, &AnalyzerRegistry::default()));
+
+        let mut doc = Document::new();
+        doc.add_text(title, "hello world");
+        segment.add_document(&doc);
+        let reader = RealtimeSegmentReader::new(segment.clone());
+
+        let mut doc = Document::new();
+        doc.add_text(title, "hello big world");
+        segment.add_document(&doc);
+
+        assert_eq!(reader.doc_freq(title, "hello"), 1);
+        assert_eq!(reader.doc_freq(title, "big"), 0);
+        assert_eq!(reader.doc_freq(title, "missing"), 0);
+        assert_eq!(reader.field_length(title, 0), 2);
+        assert_eq!(reader.total_field_length(title), 2);
+
+        let reader = RealtimeSegmentReader::new(segment.clone());
+        assert_eq!(reader.doc_freq(title, "hello"), 2);
+        assert_eq!(reader.field_length(title, 1), 3);
+        assert_eq!(reader.total_field_length(title), 5);
+    }
+
+    #[test]
+    fn test_multithreads() {
+        let mut builder = Schema::builder();
+        let title = builder.add_text_field(
+            "title",
+            TextOptions::default()
+                .set_indexed()
+                .set_analyzer("standard"),
+        );
+        let schema = builder.build();
+        let segment = Arc::new(RealtimeSegment::new(schema, &AnalyzerRegistry::default()));
+        let count = 1024;
+
+        thread::scope(|scope| {
+            let t = scope.spawn(|| loop {
+                let reader = RealtimeSegmentReader::new(segment.clone());
+                let max_doc = reader.max_doc();
+                let hello: Vec<_> = reader
+                    .postings(title, "hello")
+                    .map_or(vec![], |mut p| collect_postings(&mut p));
+                assert_eq!(hello.len(), max_doc as usize);
+                for i in 0..count {
+                    let term = format!("term{}", i);
+                    let postings: Vec<_> = reader
+                        .postings(title, &term)
+                        .map_or(vec![], |mut p| collect_postings(&mut p));
+                    if i < max_doc {
+                        assert_eq!(postings, vec![(i, 1)]);
+                    } else {
+                        assert!(postings.is_empty());
+                    }
+                }
+                if max_doc == count {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(1));
+            });
+
+            for i in 0..count {
+                let mut doc = Document::new();
+                doc.add_text(title, format!("hello term{}", i));
+                segment.add_document(&doc);
+            }
+
+            t.join().unwrap();
+        });
+    }
+}
@@ -0,0 +1,174 @@
+use std::ops::RangeInclusive;
+
+use crate::{
+    automata::Automaton,
+    columnar::{BinaryDocValues, NumericDocValues, SortedDocValues},
+    document::Document,
+    points::UninvertedPoints,
+    postings::PostingsIterator,
+    schema::{Field, Schema},
+    store::deserialize_doc,
+    DocId,
+};
+
+use super::{ImmutableSegmentReader, RealtimeSegmentReader};
+
+// Reads either kind of segment through the same interface.
+pub enum SegmentReader {
+    Realtime(RealtimeSegmentReader),
+    Immutable(ImmutableSegmentReader),
+}
+
+impl SegmentReader {
+    pub fn schema(&self) -> &Schema {
+        match self {
+            SegmentReader::Realtime(reader) => reader.segment().schema(),
+            SegmentReader::Immutable(reader) => reader.segment().schema(),
+        }
+    }
+
+    pub fn max_doc(&self) -> DocId {
+        match self {
+            SegmentReader::Realtime(reader) => reader.max_doc(),
+            SegmentReader::Immutable(reader) => reader.max_doc(),
+        }
+    }
+
+    pub fn postings(&self, field: Field, term: &str) -> Option<Box<dyn PostingsIterator + '_>> {
+        match self {
+            SegmentReader::Realtime(reader) => reader
+                .postings(field, term)
+                .map(|postings| Box::new(postings) as Box<dyn PostingsIterator>),
+            SegmentReader::Immutable(reader) => reader
+                .postings(field, term)
+                .map(|postings| Box::new(postings) as Box<dyn PostingsIterator>),
+        }
+    }
+
+    pub fn doc_freq(&self, field: Field, term: &str) -> u32 {
+        match self {
+            SegmentReader::Realtime(reader) => reader.doc_freq(field, term),
+            SegmentReader::Immutable(reader) => reader.doc_freq(field, term),
+        }
+    }
+
+    // The terms of the field matched by the automaton, in order.
+    pub fn matching_terms<A: Automaton>(&self, field: Field, automaton: &A) -> Vec<String> {
+        match self {
+            SegmentReader::Realtime(reader) => reader.matching_terms(field, automaton),
+            SegmentReader::Immutable(reader) => reader.matching_terms(field, automaton),
+        }
+    }
+
+    // Calls `visitor` with the doc of each point of the field within the range
+    // of every dimension, once per matching point, in no particular order.
+    pub fn visit_points<F: FnMut(DocId)>(
+        &self,
+        field: Field,
+        ranges: &[RangeInclusive<u64>],
+        visitor: F,
+    ) {
+        match self {
+            SegmentReader::Realtime(reader) => reader.visit_points(field, ranges, visitor),
+            SegmentReader::Immutable(reader) => reader.visit_points(field, ranges, visitor),
+        }
+    }
+
+    // Calls `visitor` with each point of the field, in no particular order.
+    pub fn for_each_point<F: FnMut(DocId, &[u64])>(&self, field: Field, visitor: F) {
+        match self {
+            SegmentReader::Realtime(reader) => reader.for_each_point(field, visitor),
+            SegmentReader::Immutable(reader) => reader.for_each_point(field, visitor),
+        }
+    }
+
+    // The smallest and greatest point of each doc, for a field indexed in one
+    // dimension. Built on first use, then cached by the immutable segment or
+    // by the realtime snapshot.
+    pub fn uninverted_points(&self, field: Field) -> Option<&UninvertedPoints> {
+        match self {
+            SegmentReader::Realtime(reader) => reader.uninverted_points(field),
+            SegmentReader::Immutable(reader) => reader.uninverted_points(field),
+        }
+    }
+
+    pub fn numeric_doc_values(&self, field: Field) -> Option<Box<dyn NumericDocValues + '_>> {
+        match self {
+            SegmentReader::Realtime(reader) => reader
+                .numeric_doc_values(field)
+                .map(|doc_values| Box::new(doc_values) as Box<dyn NumericDocValues>),
+            SegmentReader::Immutable(reader) => reader
+                .numeric_doc_values(field)
+                .map(|doc_values| Box::new(doc_values) as Box<dyn NumericDocValues>),
+        }
+    }
+
+    pub fn binary_doc_values(&self, field: Field) -> Option<Box<dyn BinaryDocValues + '_>> {
+        match self {
+            SegmentReader::Realtime(reader) => reader
+                .binary_doc_values(field)
+                .map(|doc_values| Box::new(doc_values) as Box<dyn BinaryDocValues>),
+            SegmentReader::Immutable(reader) => reader
+                .binary_doc_values(field)
+                .map(|doc_values| Box::new(doc_values) as Box<dyn BinaryDocValues>),
+        }
+    }
+
+    pub fn sorted_doc_values(&self, field: Field) -> Option<Box<dyn SortedDocValues + '_>> {
+        match self {
+            SegmentReader::Realtime(reader) => reader
+                .sorted_doc_values(field)
+                .map(|doc_values| Box::new(doc_values) as Box<dyn SortedDocValues>),
+            SegmentReader::Immutable(reader) => reader
+                .sorted_doc_values(field)
+                .map(|doc_values| Box::new(doc_values) as Box<dyn SortedDocValues>),
+        }
+    }
+
+    // The stored field values of the doc, in the order they were added.
+    // Panics if the doc doesn't exist.
+    pub fn doc(&self, doc: DocId) -> Document {
+        self.stored_doc(doc, None)
+    }
+
+    // Like `doc`, with only the values of the given fields.
+    pub fn doc_with_fields(&self, doc: DocId, fields: &[Field]) -> Document {
+        self.stored_doc(doc, Some(fields))
+    }
+
+    fn stored_doc(&self, doc: DocId, fields: Option<&[Field]>) -> Document {
+        match self {
+            SegmentReader::Realtime(reader) => deserialize_doc(reader.doc_bytes(doc), fields),
+            SegmentReader::Immutable(reader) => deserialize_doc(&reader.doc_bytes(doc), fields),
+        }
+    }
+
+    pub fn norm(&self, field: Field, doc: DocId) -> Option<u8> {
+        match self {
+            SegmentReader::Realtime(reader) => reader.norm(field, doc),
+            SegmentReader::Immutable(reader) => reader.norm(field, doc),
+        }
+    }
+
+    pub fn field_length(&self, field: Field, doc: DocId) -> u32 {
+        match self {
+            SegmentReader::Realtime(reader) => reader.field_length(field, doc),
+            SegmentReader::Immutable(reader) => reader.field_length(field, doc),
+        }
+    }
+
+    pub fn total_field_length(&self, field: Field) -> u64 {
+        match self {
+            SegmentReader::Realtime(reader) => reader.total_field_length(field),
+            SegmentReader::Immutable(reader) => reader.total_field_length(field),
+        }
+    }
+
+    // The number of docs with at least a token in the field.
+    pub fn field_doc_count(&self, field: Field) -> u64 {
+        match self {
+            SegmentReader::Realtime(reader) => reader.field_doc_count(field),
+            SegmentReader::Immutable(reader) => reader.field_doc_count(field),
+        }
+    }
+}
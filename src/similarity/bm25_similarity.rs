@@ -0,0 +1,124 @@
+use super::{CollectionStatistics, Similarity, SimilarityScorer, TermStatistics};
+
+const DEFAULT_K1: f32 = 1.2;
+const DEFAULT_B: f32 = 0.75;
+
+// Okapi BM25. `k1` controls how quickly the score saturates as the freq grows,
+// and `b` how much the field length normalizes it.
+#[derive(Clone, Copy, Debug)]
+pub struct Bm25Similarity {
+    k1: f32,
+    b: f32,
+}
+
+impl Default for Bm25Similarity {
+    fn default() -> Self {
+        Self::new(DEFAULT_K1, DEFAULT_B)
+    }
+}
+
+impl Bm25Similarity {
+    pub fn new(k1: f32, b: f32) -> Self {
+        assert!(k1.is_finite() && k1 >= 0.0, "Invalid k1 {}", k1);
+        assert!((0.0..=1.0).contains(&b), "Invalid b {}", b);
+        Self { k1, b }
+    }
+
+    pub fn k1(&self) -> f32 {
+        self.k1
+    }
+
+    pub fn b(&self) -> f32 {
+        self.b
+    }
+
+    pub fn idf(doc_freq: u64, doc_count: u64) -> f32 {
+        let doc_freq = doc_freq as f64;
+        let doc_count = doc_count.max(doc_freq as u64) as f64;
+        (1.0 + (doc_count - doc_freq + 0.5) / (doc_freq + 0.5)).ln() as f32
+    }
+}
+
+impl Similarity for Bm25Similarity {
+    fn scorer(
+        &self,
+        boost: f32,
+        collection_statistics: &CollectionStatistics,
+        term_statistics: &[TermStatistics],
+    ) -> Box<dyn SimilarityScorer> {
+        let idf: f32 = term_statistics
+            .iter()
+            .map(|term| Self::idf(term.doc_freq, collection_statistics.doc_count))
+            .sum();
+        Box::new(Bm25Scorer {
+            weight: boost * idf,
+            k1: self.k1,
+            b: self.b,
+            average_field_length: collection_statistics.average_field_length(),
+        })
+    }
+}
+
+struct Bm25Scorer {
+    weight: f32,
+    k1: f32,
+    b: f32,
+    average_field_length: f32,
+}
+
+impl SimilarityScorer for Bm25Scorer {
+    fn score(&self, freq: f32, field_length: u32) -> f32 {
+        let length_ratio = field_length as f32 / self.average_field_length;
+        let norm = self.k1 * (1.0 - self.b + self.b * length_ratio);
+        self.weight * freq / (freq + norm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::similarity::{CollectionStatistics, Similarity, TermStatistics};
+
+    use super::Bm25Similarity;
+
+    #[test]
+    fn test_simple() {
+        let collection_statistics = CollectionStatistics {
+            doc_count: 10,
+            total_field_length: 100,
+        };
+        let similarity = Bm25Similarity::default();
+        let scorer = similarity.scorer(
+            1.0,
+            &collection_statistics,
+            &[TermStatistics { doc_freq: 2 }],
+        );
+        let idf = (1.0f32 + 8.5 / 2.5).ln();
+        assert!((scorer.score(1.0, 10) - idf / 2.2).abs() < 1e-6);
+
+        // Scores grow with the freq and decrease with the field length.
+        assert!(scorer.score(2.0, 10) > scorer.score(1.0, 10));
+        assert!(scorer.score(1.0, 5) > scorer.score(1.0, 10));
+        // Rarer terms score higher.
+        let rare = similarity.scorer(
+            1.0,
+            &collection_statistics,
+            &[TermStatistics { doc_freq: 1 }],
+        );
+        assert!(rare.score(1.0, 10) > scorer.score(1.0, 10));
+
+        // Without length normalization, only the freq matters.
+        let scorer = Bm25Similarity::new(1.2, 0.0).scorer(
+            2.0,
+            &collection_statistics,
+            &[TermStatistics { doc_freq: 2 }],
+        );
+        assert_eq!(scorer.score(3.0, 1), scorer.score(3.0, 1000));
+        assert!((scorer.score(1.0, 10) - 2.0 * idf / 2.2).abs() < 1e-6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_b() {
+        Bm25Similarity::new(1.2, 1.5);
+    }
+}
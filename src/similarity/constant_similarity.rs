@@ -0,0 +1,41 @@
+use super::{CollectionStatistics, Similarity, SimilarityScorer, TermStatistics};
+
+// Gives every matching doc the boost as score, for filtering-like queries
+// where relevance doesn't matter.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConstantSimilarity;
+
+impl Similarity for ConstantSimilarity {
+    fn scorer(
+        &self,
+        boost: f32,
+        _collection_statistics: &CollectionStatistics,
+        _term_statistics: &[TermStatistics],
+    ) -> Box<dyn SimilarityScorer> {
+        Box::new(ConstantScorer { score: boost })
+    }
+}
+
+struct ConstantScorer {
+    score: f32,
+}
+
+impl SimilarityScorer for ConstantScorer {
+    fn score(&self, _freq: f32, _field_length: u32) -> f32 {
+        self.score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::similarity::{CollectionStatistics, Similarity};
+
+    use super::ConstantSimilarity;
+
+    #[test]
+    fn test_simple() {
+        let scorer = ConstantSimilarity.scorer(2.5, &CollectionStatistics::default(), &[]);
+        assert_eq!(scorer.score(1.0, 10), 2.5);
+        assert_eq!(scorer.score(7.0, 1), 2.5);
+    }
+}
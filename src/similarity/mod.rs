@@ -0,0 +1,11 @@
+mod bm25_similarity;
+mod constant_similarity;
+mod similarity_scorer;
+mod statistics;
+mod tf_idf_similarity;
+
+pub use bm25_similarity::Bm25Similarity;
+pub use constant_similarity::ConstantSimilarity;
+pub use similarity_scorer::{Similarity, SimilarityScorer};
+pub use statistics::{CollectionStatistics, TermStatistics};
+pub use tf_idf_similarity::TfIdfSimilarity;
@@ -0,0 +1,20 @@
+use super::{CollectionStatistics, TermStatistics};
+
+// Scores the docs matching some terms of a field, from the freq of the terms
+// in each doc and the length of its field.
+pub trait Similarity: Send + Sync {
+    // Several term statistics are given for the terms matched together, as in
+    // a phrase.
+    fn scorer(
+        &self,
+        boost: f32,
+        collection_statistics: &CollectionStatistics,
+        term_statistics: &[TermStatistics],
+    ) -> Box<dyn SimilarityScorer>;
+}
+
+pub trait SimilarityScorer: Send + Sync {
+    // The freq is fractional for sloppy matches, which count less the further
+    // apart their terms are.
+    fn score(&self, freq: f32, field_length: u32) -> f32;
+}
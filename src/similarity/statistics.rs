@@ -0,0 +1,22 @@
+// Statistics of a field over all the docs searched.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CollectionStatistics {
+    pub doc_count: u64,
+    pub total_field_length: u64,
+}
+
+impl CollectionStatistics {
+    pub fn average_field_length(&self) -> f32 {
+        if self.doc_count == 0 {
+            1.0
+        } else {
+            (self.total_field_length as f64 / self.doc_count as f64) as f32
+        }
+    }
+}
+
+// Statistics of a term over all the docs searched.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TermStatistics {
+    pub doc_freq: u64,
+}
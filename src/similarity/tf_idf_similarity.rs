@@ -0,0 +1,64 @@
+use super::{CollectionStatistics, Similarity, SimilarityScorer, TermStatistics};
+
+// The classic vector space scoring: the square root of the freq, times the
+// idf, divided by the square root of the field length.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TfIdfSimilarity;
+
+impl TfIdfSimilarity {
+    pub fn idf(doc_freq: u64, doc_count: u64) -> f32 {
+        (1.0 + ((doc_count as f64 + 1.0) / (doc_freq as f64 + 1.0)).ln()) as f32
+    }
+}
+
+impl Similarity for TfIdfSimilarity {
+    fn scorer(
+        &self,
+        boost: f32,
+        collection_statistics: &CollectionStatistics,
+        term_statistics: &[TermStatistics],
+    ) -> Box<dyn SimilarityScorer> {
+        let idf: f32 = term_statistics
+            .iter()
+            .map(|term| Self::idf(term.doc_freq, collection_statistics.doc_count))
+            .sum();
+        Box::new(TfIdfScorer {
+            weight: boost * idf,
+        })
+    }
+}
+
+struct TfIdfScorer {
+    weight: f32,
+}
+
+impl SimilarityScorer for TfIdfScorer {
+    fn score(&self, freq: f32, field_length: u32) -> f32 {
+        let norm = 1.0 / (field_length.max(1) as f32).sqrt();
+        self.weight * freq.sqrt() * norm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::similarity::{CollectionStatistics, Similarity, TermStatistics};
+
+    use super::TfIdfSimilarity;
+
+    #[test]
+    fn test_simple() {
+        let collection_statistics = CollectionStatistics {
+            doc_count: 9,
+            total_field_length: 90,
+        };
+        let scorer = TfIdfSimilarity.scorer(
+            1.0,
+            &collection_statistics,
+            &[TermStatistics { doc_freq: 4 }],
+        );
+        let idf = 1.0 + 2.0f32.ln();
+        assert!((scorer.score(4.0, 4) - idf).abs() < 1e-6);
+        assert!((scorer.score(1.0, 0) - idf).abs() < 1e-6);
+        assert!(scorer.score(4.0, 4) > scorer.score(4.0, 16));
+    }
+}
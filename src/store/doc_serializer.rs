@@ -0,0 +1,184 @@
+use crate::{
+    document::{DateTime, Document, FieldValue, Value},
+    schema::Field,
+    util::{read_vint, write_vint},
+};
+
+const STR: u8 = 0;
+const I64: u8 = 1;
+const U64: u8 = 2;
+const F64: u8 = 3;
+const DATE: u8 = 4;
+const BYTES: u8 = 5;
+const POINT: u8 = 6;
+
+fn write_value(value: &Value, output: &mut Vec<u8>) {
+    match value {
+        Value::Str(text) => {
+            output.push(STR);
+            write_vint(text.len() as u64, output);
+            output.extend_from_slice(text.as_bytes());
+        }
+        Value::I64(value) => {
+            output.push(I64);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Value::U64(value) => {
+            output.push(U64);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Value::F64(value) => {
+            output.push(F64);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Value::Date(value) => {
+            output.push(DATE);
+            output.extend_from_slice(&value.into_timestamp_micros().to_le_bytes());
+        }
+        Value::Bytes(bytes) => {
+            output.push(BYTES);
+            write_vint(bytes.len() as u64, output);
+            output.extend_from_slice(bytes);
+        }
+        Value::Point(values) => {
+            output.push(POINT);
+            write_vint(values.len() as u64, output);
+            for value in values {
+                write_value(value, output);
+            }
+        }
+    }
+}
+
+struct Input<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Input<'a> {
+    fn is_empty(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    fn read_vint(&mut self) -> usize {
+        let mut bytes = self.bytes[self.pos..].iter().copied();
+        let value = read_vint(&mut bytes).unwrap() as usize;
+        self.pos = self.bytes.len() - bytes.len();
+        value
+    }
+
+    fn read_bytes(&mut self, len: usize) -> &'a [u8] {
+        let bytes = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        bytes
+    }
+
+    fn read_u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.read_bytes(8).try_into().unwrap())
+    }
+
+    fn read_value(&mut self) -> Value {
+        let tag = self.read_bytes(1)[0];
+        match tag {
+            STR => {
+                let len = self.read_vint();
+                Value::Str(String::from_utf8(self.read_bytes(len).to_vec()).unwrap())
+            }
+            I64 => Value::I64(self.read_u64() as i64),
+            U64 => Value::U64(self.read_u64()),
+            F64 => Value::F64(f64::from_bits(self.read_u64())),
+            DATE => Value::Date(DateTime::from_timestamp_micros(self.read_u64() as i64)),
+            BYTES => {
+                let len = self.read_vint();
+                Value::Bytes(self.read_bytes(len).to_vec())
+            }
+            POINT => {
+                let len = self.read_vint();
+                Value::Point((0..len).map(|_| self.read_value()).collect())
+            }
+            _ => panic!("Unknown value tag {}", tag),
+        }
+    }
+
+    fn skip_value(&mut self) {
+        let tag = self.read_bytes(1)[0];
+        match tag {
+            STR | BYTES => {
+                let len = self.read_vint();
+                self.pos += len;
+            }
+            I64 | U64 | F64 | DATE => self.pos += 8,
+            POINT => {
+                for _ in 0..self.read_vint() {
+                    self.skip_value();
+                }
+            }
+            _ => panic!("Unknown value tag {}", tag),
+        }
+    }
+}
+
+// Appends the field values to `output`, each as the id of its field followed
+// by its type and its content. Nothing is written without values.
+pub fn serialize_field_values<'a, I: IntoIterator<Item = &'a FieldValue>>(
+    field_values: I,
+    output: &mut Vec<u8>,
+) {
+    for field_value in field_values {
+        write_vint(field_value.field().field_id() as u64, output);
+        write_value(field_value.value(), output);
+    }
+}
+
+// The document of the serialized field values, with only the values of
+// `fields` if given.
+pub fn deserialize_doc(bytes: &[u8], fields: Option<&[Field]>) -> Document {
+    let mut input = Input { bytes, pos: 0 };
+    let mut doc = Document::new();
+    while !input.is_empty() {
+        let field = Field::from_field_id(input.read_vint() as u32);
+        if fields.is_some_and(|fields| !fields.contains(&field)) {
+            input.skip_value();
+        } else {
+            doc.add_field_value(field, input.read_value());
+        }
+    }
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        document::{DateTime, Document},
+        schema::Field,
+    };
+
+    use super::{deserialize_doc, serialize_field_values};
+
+    #[test]
+    fn test_simple() {
+        let title = Field::from_field_id(0);
+        let price = Field::from_field_id(1);
+        let location = Field::from_field_id(200);
+        let mut doc = Document::new();
+        doc.add_text(title, "hello");
+        doc.add_f64(price, -9.5);
+        doc.add_point(location, [48i64, -2]);
+        doc.add_text(title, "");
+        doc.add_u64(price, u64::MAX);
+        doc.add_date(price, DateTime::from_timestamp_secs(-3));
+        doc.add_bytes(location, vec![0, 255]);
+        doc.add_i64(price, i64::MIN);
+
+        let mut bytes = Vec::new();
+        serialize_field_values(doc.field_values(), &mut bytes);
+        assert_eq!(deserialize_doc(&bytes, None), doc);
+
+        let mut expected = Document::new();
+        expected.add_point(location, [48i64, -2]);
+        expected.add_bytes(location, vec![0, 255]);
+        assert_eq!(deserialize_doc(&bytes, Some(&[location])), expected);
+        assert!(deserialize_doc(&bytes, Some(&[])).is_empty());
+        assert!(deserialize_doc(&[], None).is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    util::{read_vint, write_vint},
+    DocId,
+};
+
+use super::{compress, decompress, StoreWriter};
+
+// The size of the serialized docs of a block, before compression, past which
+// the block is closed.
+const BLOCK_SIZE: usize = 16 * 1024;
+
+// The stored field values of the docs of an immutable segment, grouped into
+// compressed blocks of consecutive docs.
+//
+// A block holds the length of each of its docs followed by its bytes. Only
+// the first doc of each block is indexed, so that reading a doc takes a
+// binary search over the blocks and the decompression of one of them.
+pub struct DocStore {
+    // The first doc of each block and the start of the block in `data`.
+    checkpoints: Vec<(DocId, usize)>,
+    data: Vec<u8>,
+}
+
+impl DocStore {
+    pub fn build(store_writer: &StoreWriter, max_doc: DocId) -> Self {
+        let mut checkpoints = Vec::new();
+        let mut data = Vec::new();
+        let mut block = Vec::new();
+        let mut first_doc = 0;
+        for doc in 0..max_doc {
+            let bytes = store_writer.doc_bytes(doc).unwrap();
+            write_vint(bytes.len() as u64, &mut block);
+            block.extend_from_slice(bytes);
+            if block.len() >= BLOCK_SIZE || doc + 1 == max_doc {
+                checkpoints.push((first_doc, data.len()));
+                compress(&block, &mut data);
+                block.clear();
+                first_doc = doc + 1;
+            }
+        }
+        Self { checkpoints, data }
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    // The serialized field values of the doc, which must exist.
+    pub fn doc_bytes(&self, doc: DocId) -> Vec<u8> {
+        let block_ord = self
+            .checkpoints
+            .partition_point(|&(first_doc, _)| first_doc <= doc)
+            .checked_sub(1)
+            .unwrap();
+        let (first_doc, start) = self.checkpoints[block_ord];
+        let end = self
+            .checkpoints
+            .get(block_ord + 1)
+            .map_or(self.data.len(), |&(_, start)| start);
+        let mut block = Vec::new();
+        decompress(&self.data[start..end], &mut block);
+
+        let mut pos = 0;
+        for current_doc in first_doc.. {
+            let mut bytes = block[pos..].iter().copied();
+            let len = read_vint(&mut bytes).unwrap() as usize;
+            pos = block.len() - bytes.len();
+            if current_doc == doc {
+                return block[pos..pos + len].to_vec();
+            }
+            pos += len;
+            assert!(pos < block.len(), "Doc {} does not exist", doc);
+        }
+        unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        document::Document,
+        schema::Field,
+        store::{deserialize_doc, StoreWriter},
+    };
+
+    use super::DocStore;
+
+    #[test]
+    fn test_simple() {
+        let body = Field::from_field_id(0);
+        let id = Field::from_field_id(1);
+        let store_writer = StoreWriter::new();
+        let docs: Vec<Document> = (0..5000u64)
+            .map(|i| {
+                let mut doc = Document::new();
+                if !i.is_multiple_of(7) {
+                    doc.add_text(
+                        body,
+                        format!("doc {} says {}", i, "hello ".repeat(i as usize % 13)),
+                    );
+                }
+                doc.add_u64(id, i);
+                doc
+            })
+            .collect();
+        for doc in &docs {
+            store_writer.add_doc(doc.field_values());
+        }
+        assert_eq!(store_writer.len(), 5000);
+
+        // Docs above `max_doc` are left out.
+        let doc_store = DocStore::build(&store_writer, 4000);
+        assert!(doc_store.block_count() > 1);
+        assert!(doc_store.data.len() < doc_store.checkpoints.len() * super::BLOCK_SIZE / 2);
+        for (doc, expected) in docs[..4000].iter().enumerate() {
+            let bytes = doc_store.doc_bytes(doc as u32);
+            assert_eq!(bytes, store_writer.doc_bytes(doc as u32).unwrap());
+            assert_eq!(&deserialize_doc(&bytes, None), expected);
+        }
+        assert_eq!(DocStore::build(&store_writer, 0).block_count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_missing_doc() {
+        let store_writer = StoreWriter::new();
+        store_writer.add_doc(&[]);
+        DocStore::build(&store_writer, 1).doc_bytes(1);
+    }
+}
@@ -0,0 +1,164 @@
+// A byte oriented LZ77 codec, in the spirit of LZ4: fast to decompress and
+// good enough on the repetitive content of stored documents.
+//
+// The compressed data is a list of sequences, each made of a token, literals
+// copied as is, then a match copied from the output already decompressed:
+// - the token holds the number of literals in its high 4 bits and the length
+//   of the match minus `MIN_MATCH` in its low 4 bits. A value of 15 goes on
+//   with extra bytes, added up until one of them is below 255;
+// - the match is the distance back to its start as a 2 byte little endian
+//   integer, followed by the extra bytes of its length.
+// The last sequence has only literals, possibly none.
+
+const MIN_MATCH: usize = 4;
+const MAX_OFFSET: usize = u16::MAX as usize;
+const HASH_BITS: u32 = 12;
+
+fn hash(bytes: &[u8]) -> usize {
+    let sequence = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    (sequence.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+}
+
+fn write_length(mut length: usize, output: &mut Vec<u8>) {
+    while length >= 255 {
+        output.push(255);
+        length -= 255;
+    }
+    output.push(length as u8);
+}
+
+fn read_length(input: &[u8], pos: &mut usize, nibble: u8) -> usize {
+    let mut length = nibble as usize;
+    if nibble == 15 {
+        loop {
+            let byte = input[*pos];
+            *pos += 1;
+            length += byte as usize;
+            if byte < 255 {
+                break;
+            }
+        }
+    }
+    length
+}
+
+fn write_sequence(literals: &[u8], matched: Option<(usize, usize)>, output: &mut Vec<u8>) {
+    let match_length = matched.map_or(0, |(_, length)| length - MIN_MATCH);
+    output.push(((literals.len().min(15) as u8) << 4) | match_length.min(15) as u8);
+    if literals.len() >= 15 {
+        write_length(literals.len() - 15, output);
+    }
+    output.extend_from_slice(literals);
+    if let Some((offset, _)) = matched {
+        output.extend_from_slice(&(offset as u16).to_le_bytes());
+        if match_length >= 15 {
+            write_length(match_length - 15, output);
+        }
+    }
+}
+
+// Appends the compressed input to `output`.
+pub fn compress(input: &[u8], output: &mut Vec<u8>) {
+    // The position plus one of the last sequence of each hash, or zero.
+    let mut table = vec![0usize; 1 << HASH_BITS];
+    let mut anchor = 0;
+    let mut pos = 0;
+    while pos + MIN_MATCH <= input.len() {
+        let slot = &mut table[hash(&input[pos..])];
+        let candidate = *slot;
+        *slot = pos + 1;
+        if candidate > 0 {
+            let start = candidate - 1;
+            if pos - start <= MAX_OFFSET
+                && input[start..start + MIN_MATCH] == input[pos..pos + MIN_MATCH]
+            {
+                let mut length = MIN_MATCH;
+                while pos + length < input.len() && input[start + length] == input[pos + length] {
+                    length += 1;
+                }
+                write_sequence(&input[anchor..pos], Some((pos - start, length)), output);
+                pos += length;
+                anchor = pos;
+                continue;
+            }
+        }
+        pos += 1;
+    }
+    write_sequence(&input[anchor..], None, output);
+}
+
+// Appends the decompressed input to `output`. Panics if the input was not
+// produced by `compress`.
+pub fn decompress(input: &[u8], output: &mut Vec<u8>) {
+    let base = output.len();
+    let mut pos = 0;
+    while pos < input.len() {
+        let token = input[pos];
+        pos += 1;
+        let literals = read_length(input, &mut pos, token >> 4);
+        output.extend_from_slice(&input[pos..pos + literals]);
+        pos += literals;
+        if pos == input.len() {
+            break;
+        }
+        let offset = u16::from_le_bytes([input[pos], input[pos + 1]]) as usize;
+        pos += 2;
+        let length = read_length(input, &mut pos, token & 0xf) + MIN_MATCH;
+        let start = output.len() - offset;
+        assert!(offset > 0 && start >= base, "Corrupted input");
+        // Byte by byte, as the match may overlap the bytes it produces.
+        for i in start..start + length {
+            output.push(output[i]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::Random;
+
+    use super::{compress, decompress};
+
+    fn roundtrip(input: &[u8]) -> usize {
+        let mut compressed = Vec::new();
+        compress(input, &mut compressed);
+        let mut output = vec![42];
+        decompress(&compressed, &mut output);
+        assert_eq!(output[0], 42);
+        assert_eq!(&output[1..], input);
+        compressed.len()
+    }
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(roundtrip(b""), 1);
+        roundtrip(b"a");
+        roundtrip(b"abcd");
+        let text = b"the quick brown fox jumps over the lazy dog, the quick brown fox";
+        assert!(roundtrip(text) < text.len());
+    }
+
+    #[test]
+    fn test_long_lengths() {
+        // Runs overlap their own matches, and need extra length bytes.
+        let input = vec![7u8; 10_000];
+        assert!(roundtrip(&input) < 100);
+        let mut input: Vec<u8> = (0..300).map(|i| (i * 7) as u8).collect();
+        input.extend_from_within(..);
+        input.extend(vec![1u8; 1000]);
+        roundtrip(&input);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut random = Random::new(42);
+        for len in [10, 1000, 100_000] {
+            // Few distinct bytes, so that there are matches of all lengths,
+            // some of them further than the max offset.
+            let input: Vec<u8> = (0..len).map(|_| random.below(4) as u8).collect();
+            roundtrip(&input);
+            let input: Vec<u8> = (0..len).map(|_| random.below(256) as u8).collect();
+            roundtrip(&input);
+        }
+    }
+}
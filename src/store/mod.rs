@@ -0,0 +1,9 @@
+mod doc_serializer;
+mod doc_store;
+mod lz;
+mod store_writer;
+
+pub use doc_serializer::{deserialize_doc, serialize_field_values};
+pub use doc_store::DocStore;
+pub use lz::{compress, decompress};
+pub use store_writer::StoreWriter;
@@ -0,0 +1,46 @@
+use crate::{document::FieldValue, util::ChunkedVector, DocId};
+
+use super::serialize_field_values;
+
+const DOCS_CHUNK_EXPONENT: usize = 10;
+const DOCS_TREE_EXPONENT: usize = 4;
+
+// The stored field values of each doc of a realtime segment, serialized.
+pub struct StoreWriter {
+    docs: ChunkedVector<Box<[u8]>>,
+}
+
+impl Default for StoreWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StoreWriter {
+    pub fn new() -> Self {
+        Self {
+            docs: ChunkedVector::new(DOCS_CHUNK_EXPONENT, DOCS_TREE_EXPONENT),
+        }
+    }
+
+    // Must only be called from the single writer thread, once for each doc in
+    // order.
+    pub fn add_doc<'a, I: IntoIterator<Item = &'a FieldValue>>(&self, field_values: I) {
+        let mut bytes = Vec::new();
+        serialize_field_values(field_values, &mut bytes);
+        self.docs.push(bytes.into_boxed_slice());
+    }
+
+    // The serialized field values of the doc.
+    pub fn doc_bytes(&self, doc: DocId) -> Option<&[u8]> {
+        self.docs.get(doc as usize).map(|bytes| &bytes[..])
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+}
@@ -10,7 +10,7 @@ fn quot_and_rem(index: usize) -> (usize, usize) {
 
 impl Bitset {
     pub fn with_capacity(capacity: usize) -> Self {
-        let len = (capacity + 63) / 64;
+        let len = capacity.div_ceil(64);
         let vec: Vec<_> = (0..len).map(|_| AtomicU64::new(0)).collect();
         let data = vec.into_boxed_slice();
         Self { data }
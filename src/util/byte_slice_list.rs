@@ -0,0 +1,209 @@
+use std::ptr::{self, NonNull};
+
+use super::{
+    AcqRelAtomicPtr, CapacityPolicy, ExponentialCapacityPolicy, FixedCapacityVec, RelaxedAtomicPtr,
+    RelaxedUsize,
+};
+
+// An append-only byte buffer made of chained slices.
+//
+// A single writer appends bytes while any number of readers walk the chain.
+// The list itself doesn't publish a length: readers must be given an end
+// offset that was published by the owner after the bytes were written.
+pub struct ByteSliceList<C: CapacityPolicy = ExponentialCapacityPolicy> {
+    head: NonNull<ByteSlice>,
+    tail: RelaxedAtomicPtr<ByteSlice>,
+    len: RelaxedUsize,
+    capacity_policy: C,
+}
+
+unsafe impl<C: CapacityPolicy + Send> Send for ByteSliceList<C> {}
+unsafe impl<C: CapacityPolicy + Sync> Sync for ByteSliceList<C> {}
+
+struct ByteSlice {
+    data: FixedCapacityVec<u8>,
+    next: AcqRelAtomicPtr<ByteSlice>,
+}
+
+impl ByteSlice {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: FixedCapacityVec::with_capacity(capacity),
+            next: AcqRelAtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.data.len() == self.data.capacity()
+    }
+}
+
+impl<C: CapacityPolicy> ByteSliceList<C> {
+    pub fn with_initial_capacity(initial_capacity: usize, capacity_policy: C) -> Self {
+        let head = Box::into_raw(Box::new(ByteSlice::with_capacity(initial_capacity)));
+        Self {
+            head: unsafe { NonNull::new_unchecked(head) },
+            tail: RelaxedAtomicPtr::new(head),
+            len: RelaxedUsize::new(0),
+            capacity_policy,
+        }
+    }
+
+    // Must only be called from the single writer thread.
+    pub fn push(&self, byte: u8) {
+        let mut tail = unsafe { &*self.tail.load() };
+        if tail.is_full() {
+            let capacity = self.capacity_policy.next_capacity(tail.data.capacity());
+            let slice = Box::into_raw(Box::new(ByteSlice::with_capacity(capacity)));
+            tail.next.store(slice);
+            self.tail.store(slice);
+            tail = unsafe { &*slice };
+        }
+        tail.data.push(byte);
+        self.len.store(self.len.load() + 1);
+    }
+
+    // Must only be called from the single writer thread.
+    pub fn extend_from_slice(&self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.push(byte);
+        }
+    }
+
+    // The number of bytes written so far, only meaningful to the writer.
+    pub fn len(&self) -> usize {
+        self.len.load()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Reads the first `len` bytes, which must have been published to the
+    // calling thread.
+    pub fn reader(&self, len: usize) -> ByteSliceReader<'_> {
+        self.reader_from(0, len)
+    }
+
+    // Reads the bytes from `start` up to `end`, which must have been
+    // published to the calling thread.
+    pub fn reader_from(&self, start: usize, end: usize) -> ByteSliceReader<'_> {
+        let mut slice = unsafe { self.head.as_ref() };
+        let mut offset = start;
+        // A slice full up to `offset` is left to the reader to move past, as
+        // the next one may not exist yet.
+        while offset > slice.data.capacity() {
+            offset -= slice.data.capacity();
+            slice = unsafe { &*slice.next.load() };
+        }
+        ByteSliceReader {
+            slice,
+            offset,
+            remaining: end - start,
+        }
+    }
+}
+
+impl<C: CapacityPolicy> Drop for ByteSliceList<C> {
+    fn drop(&mut self) {
+        let mut slice = self.head.as_ptr();
+        while !slice.is_null() {
+            let boxed = unsafe { Box::from_raw(slice) };
+            slice = boxed.next.load();
+        }
+    }
+}
+
+pub struct ByteSliceReader<'a> {
+    slice: &'a ByteSlice,
+    offset: usize,
+    remaining: usize,
+}
+
+impl<'a> ByteSliceReader<'a> {
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<'a> Iterator for ByteSliceReader<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.remaining == 0 {
+            return None;
+        }
+        if self.offset == self.slice.data.capacity() {
+            self.slice = unsafe { &*self.slice.next.load() };
+            self.offset = 0;
+        }
+        let byte = self.slice.data[self.offset];
+        self.offset += 1;
+        self.remaining -= 1;
+        Some(byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use crate::util::{AcqRelUsize, ExponentialCapacityPolicy, FixedCapacityPolicy};
+
+    use super::ByteSliceList;
+
+    #[test]
+    fn test_simple() {
+        let list = ByteSliceList::with_initial_capacity(4, FixedCapacityPolicy);
+        assert!(list.is_empty());
+        assert!(list.reader(0).next().is_none());
+
+        let count = 1000;
+        for i in 0..count {
+            list.push(i as u8);
+        }
+        list.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(list.len(), count + 3);
+
+        let bytes: Vec<_> = list.reader(list.len()).collect();
+        let mut expect: Vec<_> = (0..count).map(|i| i as u8).collect();
+        expect.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(bytes, expect);
+
+        let reader = list.reader(10);
+        assert_eq!(reader.remaining(), 10);
+        assert_eq!(reader.collect::<Vec<_>>(), expect[..10]);
+        // Starting at, inside and right past the end of slices.
+        for start in [0, 3, 4, 5, 8, 999, count + 3] {
+            let reader = list.reader_from(start, count + 3);
+            assert_eq!(reader.remaining(), count + 3 - start);
+            assert_eq!(reader.collect::<Vec<_>>(), expect[start..]);
+        }
+    }
+
+    #[test]
+    fn test_multithreads() {
+        let list = ByteSliceList::with_initial_capacity(2, ExponentialCapacityPolicy);
+        let published = AcqRelUsize::new(0);
+        let count = 4096;
+        thread::scope(|scope| {
+            let t = scope.spawn(|| loop {
+                let len = published.load();
+                for (i, byte) in list.reader(len).enumerate() {
+                    assert_eq!(byte, i as u8);
+                }
+                if len == count {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(1));
+            });
+
+            for i in 0..count {
+                list.push(i as u8);
+                published.store(i + 1);
+            }
+
+            t.join().unwrap();
+        });
+    }
+}
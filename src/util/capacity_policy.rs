@@ -10,3 +10,12 @@ impl CapacityPolicy for FixedCapacityPolicy {
         current
     }
 }
+
+#[derive(Default)]
+pub struct ExponentialCapacityPolicy;
+
+impl CapacityPolicy for ExponentialCapacityPolicy {
+    fn next_capacity(&self, current: usize) -> usize {
+        current * 2
+    }
+}
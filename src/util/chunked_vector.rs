@@ -17,7 +17,7 @@ impl<T> ChunkedVector<T> {
 
     pub fn push(&self, value: T) {
         let len = self.len();
-        if len % (1 << self.chunk_exponent) == 0 {
+        if len.is_multiple_of(1 << self.chunk_exponent) {
             self.chunk_tree
                 .insert(FixedCapacityVec::with_capacity(1 << self.chunk_exponent));
         }
@@ -46,6 +46,10 @@ impl<T> ChunkedVector<T> {
         self.len.load()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn set_len(&self, len: usize) {
         self.len.store(len);
     }
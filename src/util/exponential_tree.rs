@@ -197,13 +197,10 @@ impl<T> ExponentialTreeNode<T> {
 
 impl<T> Drop for ExponentialTreeNode<T> {
     fn drop(&mut self) {
-        match &self.data {
-            ExponentialTreeNodeData::InternalNode(v) => {
-                for c in v.iter() {
-                    let _ = unsafe { Box::from_raw(c.as_ptr()) };
-                }
+        if let ExponentialTreeNodeData::InternalNode(v) = &self.data {
+            for c in v.iter() {
+                let _ = unsafe { Box::from_raw(c.as_ptr()) };
             }
-            _ => {}
         }
     }
 }
@@ -66,6 +66,10 @@ impl<T> FixedCapacityVec<T> {
         self.len.load()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     fn set_len(&self, len: usize) {
         self.len.store(len);
     }
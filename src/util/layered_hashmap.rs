@@ -1,7 +1,7 @@
 use std::{
     borrow::Borrow,
     collections::hash_map::RandomState,
-    hash::{BuildHasher, Hash, Hasher},
+    hash::{BuildHasher, Hash},
     ptr::NonNull,
     sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
 };
@@ -40,10 +40,8 @@ struct HashBucket<K, V> {
     elems: Box<[Raw<Entry<K, V>>]>,
 }
 
-fn hash<Q: Hash, H: BuildHasher>(key: &Q, hasher_builder: &H) -> u64 {
-    let mut hasher = hasher_builder.build_hasher();
-    key.hash(&mut hasher);
-    hasher.finish()
+fn hash<Q: Hash + ?Sized, H: BuildHasher>(key: &Q, hasher_builder: &H) -> u64 {
+    hasher_builder.hash_one(key)
 }
 
 impl<K, V, H: BuildHasher, C: CapacityPolicy> LayeredHashMap<K, V, H, C> {
@@ -71,7 +69,7 @@ impl<K, V, H: BuildHasher, C: CapacityPolicy> LayeredHashMap<K, V, H, C> {
     pub fn get<Q>(&self, key: &Q) -> Option<&V>
     where
         K: Borrow<Q>,
-        Q: Eq + Hash,
+        Q: Eq + Hash + ?Sized,
     {
         let mut head_ptr = self.head();
         loop {
@@ -87,6 +85,13 @@ impl<K, V, H: BuildHasher, C: CapacityPolicy> LayeredHashMap<K, V, H, C> {
         None
     }
 
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            bucket: Some(unsafe { self.head().as_ref() }),
+            index: 0,
+        }
+    }
+
     fn set_head(&self, head: NonNull<HashBucket<K, V>>) {
         self.head.store(head.as_ptr(), Ordering::Release);
     }
@@ -130,6 +135,31 @@ impl<K, V, H: BuildHasher, C: CapacityPolicy> Drop for LayeredHashMap<K, V, H, C
     }
 }
 
+pub struct Iter<'a, K, V> {
+    bucket: Option<&'a HashBucket<K, V>>,
+    index: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(bucket) = self.bucket {
+            while self.index < bucket.capacity() {
+                let index = self.index;
+                self.index += 1;
+                if bucket.bitset.contains(index) {
+                    let entry = bucket.entry(index);
+                    return Some((&entry.key, &entry.value));
+                }
+            }
+            self.bucket = bucket.next.map(|next| unsafe { next.as_ref() });
+            self.index = 0;
+        }
+        None
+    }
+}
+
 impl<K, V> HashBucket<K, V> {
     fn with_capacity(capacity: usize) -> Self {
         let elems: Vec<_> = (0..capacity).map(|_| Raw::new()).collect();
@@ -166,7 +196,7 @@ impl<K, V> HashBucket<K, V> {
     fn get<Q, H: BuildHasher>(&self, key: &Q, hasher_builder: &H) -> Option<&V>
     where
         K: Borrow<Q>,
-        Q: Eq + Hash,
+        Q: Eq + Hash + ?Sized,
     {
         let hash = hash(key, hasher_builder) as usize;
         let mut index = hash % self.capacity();
@@ -211,6 +241,18 @@ impl<K, V> HashBucket<K, V> {
     }
 }
 
+impl<K, V> Drop for HashBucket<K, V> {
+    fn drop(&mut self) {
+        for index in 0..self.capacity() {
+            if self.bitset.contains(index) {
+                unsafe {
+                    self.elems[index].drop();
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::hash_map::RandomState, thread, time::Duration};
@@ -269,6 +311,22 @@ mod tests {
         assert!(map.get(&0).is_none());
     }
 
+    #[test]
+    fn test_hashmap_iter() {
+        let hasher_builder = RandomState::new();
+        let capacity_policy = FixedCapacityPolicy;
+        let map = LayeredHashMap::with_initial_capacity(4, hasher_builder, capacity_policy);
+        assert!(map.iter().next().is_none());
+
+        for i in 0..10 {
+            map.insert(i, i * 10);
+        }
+        let mut entries: Vec<_> = map.iter().map(|(&k, &v)| (k, v)).collect();
+        entries.sort();
+        let expect: Vec<_> = (0..10).map(|i| (i, i * 10)).collect();
+        assert_eq!(entries, expect);
+    }
+
     #[test]
     fn test_hashmap_multithreads() {
         let hasher_builder = RandomState::new();
@@ -1,19 +1,31 @@
 mod atomic;
 mod bitset;
+mod byte_slice_list;
 mod capacity_policy;
 mod chunked_vector;
 mod exponential_tree;
 mod fixed_capacity_vec;
 mod layered_hashmap;
+mod numeric;
+#[cfg(test)]
+mod random;
 mod raw;
+mod small_float;
+mod vint;
 
 pub use atomic::{
     AcqRelAtomicPtr, AcqRelU64, AcqRelUsize, RelaxedAtomicPtr, RelaxedU64, RelaxedUsize,
 };
 pub use bitset::Bitset;
-pub use capacity_policy::{CapacityPolicy, FixedCapacityPolicy};
+pub use byte_slice_list::{ByteSliceList, ByteSliceReader};
+pub use capacity_policy::{CapacityPolicy, ExponentialCapacityPolicy, FixedCapacityPolicy};
 pub use chunked_vector::ChunkedVector;
 pub use exponential_tree::ExponentialTree;
 pub use fixed_capacity_vec::FixedCapacityVec;
-pub use layered_hashmap::LayeredHashMap;
+pub use layered_hashmap::{Iter as LayeredHashMapIter, LayeredHashMap};
+pub use numeric::{f64_to_u64, i64_to_u64, u64_to_f64, u64_to_i64};
+#[cfg(test)]
+pub(crate) use random::Random;
 pub use raw::Raw;
+pub use small_float::{byte4_to_int, int_to_byte4};
+pub use vint::{read_vint, write_vint};
@@ -0,0 +1,63 @@
+// Maps numbers to u64s of the same order, so that numbers of every type are
+// compared and indexed as u64s.
+pub fn i64_to_u64(value: i64) -> u64 {
+    (value as u64) ^ (1 << 63)
+}
+
+pub fn u64_to_i64(value: u64) -> i64 {
+    (value ^ (1 << 63)) as i64
+}
+
+// Negative floats have their bits flipped so that larger magnitudes sort
+// first, and positive floats have their sign bit set to sort after them.
+pub fn f64_to_u64(value: f64) -> u64 {
+    let bits = value.to_bits();
+    if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits ^ (1 << 63)
+    }
+}
+
+pub fn u64_to_f64(value: u64) -> f64 {
+    if value >> 63 == 1 {
+        f64::from_bits(value ^ (1 << 63))
+    } else {
+        f64::from_bits(!value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{f64_to_u64, i64_to_u64, u64_to_f64, u64_to_i64};
+
+    #[test]
+    fn test_simple() {
+        let values = [i64::MIN, -100, -1, 0, 1, 100, i64::MAX];
+        for pair in values.windows(2) {
+            assert!(i64_to_u64(pair[0]) < i64_to_u64(pair[1]));
+        }
+        for value in values {
+            assert_eq!(u64_to_i64(i64_to_u64(value)), value);
+        }
+
+        let values = [
+            f64::NEG_INFINITY,
+            f64::MIN,
+            -1.5,
+            -f64::MIN_POSITIVE,
+            -0.0,
+            0.0,
+            f64::MIN_POSITIVE,
+            1.5,
+            f64::MAX,
+            f64::INFINITY,
+        ];
+        for pair in values.windows(2) {
+            assert!(f64_to_u64(pair[0]) < f64_to_u64(pair[1]));
+        }
+        for value in values {
+            assert_eq!(u64_to_f64(f64_to_u64(value)).to_bits(), value.to_bits());
+        }
+    }
+}
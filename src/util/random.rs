@@ -0,0 +1,18 @@
+// A deterministic sequence of pseudo-random numbers for tests, from a linear
+// congruential generator.
+pub(crate) struct Random(u64);
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    // A number below `modulo`.
+    pub fn below(&mut self, modulo: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 33) % modulo
+    }
+}
@@ -11,6 +11,7 @@ use std::{cell, mem, ptr};
 //  Here be dragons...
 pub struct Raw<T>(cell::UnsafeCell<mem::MaybeUninit<T>>);
 
+#[allow(clippy::missing_safety_doc)]
 impl<T> Raw<T> {
     //  Creates a new instance.
     pub fn new() -> Self {
@@ -130,6 +131,7 @@ impl<T> Raw<T> {
     }
 }
 
+#[allow(clippy::missing_safety_doc)]
 impl<T: Copy> Raw<T> {
     pub unsafe fn value(&self) -> T {
         unsafe { *self.as_ptr() }
@@ -0,0 +1,77 @@
+// Encodes a non-negative int into 4 bits: 3 bits of mantissa with an implicit
+// leading one, and the shift in the remaining bits. Small values are exact.
+fn int_to_int4(value: u32) -> u32 {
+    let num_bits = u32::BITS - value.leading_zeros();
+    if num_bits < 4 {
+        value
+    } else {
+        let shift = num_bits - 4;
+        let encoded = (value >> shift) & 0x07;
+        encoded | ((shift + 1) << 3)
+    }
+}
+
+fn int4_to_int(value: u32) -> u32 {
+    let bits = value & 0x07;
+    let shift = value >> 3;
+    if shift == 0 {
+        bits
+    } else {
+        (bits | 0x08) << (shift - 1)
+    }
+}
+
+const MAX_INT4: u32 = 231;
+// Bytes below this are used as is, so that the lengths of short fields, which
+// matter most to scores, are encoded exactly.
+const NUM_FREE_VALUES: u32 = 255 - MAX_INT4;
+
+// Encodes an int into a byte with a lossy encoding that keeps the order, and
+// the relative error below 1/8. Values above `i32::MAX` are clamped.
+pub fn int_to_byte4(value: u32) -> u8 {
+    let value = value.min(i32::MAX as u32);
+    if value < NUM_FREE_VALUES {
+        value as u8
+    } else {
+        (NUM_FREE_VALUES + int_to_int4(value - NUM_FREE_VALUES)) as u8
+    }
+}
+
+// Decodes the smallest int that encodes into the byte.
+pub fn byte4_to_int(byte: u8) -> u32 {
+    let value = byte as u32;
+    if value < NUM_FREE_VALUES {
+        value
+    } else {
+        NUM_FREE_VALUES + int4_to_int(value - NUM_FREE_VALUES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{byte4_to_int, int_to_byte4, int_to_int4, MAX_INT4};
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(int_to_int4(i32::MAX as u32), MAX_INT4);
+        for value in 0..24 {
+            assert_eq!(byte4_to_int(int_to_byte4(value)), value);
+        }
+        assert_eq!(int_to_byte4(i32::MAX as u32), 255);
+        assert_eq!(int_to_byte4(u32::MAX), 255);
+        assert_eq!(byte4_to_int(255), 2_013_265_944);
+
+        let mut last = 0;
+        for value in (0..100_000).chain([1 << 20, 1 << 30, i32::MAX as u32]) {
+            let byte = int_to_byte4(value);
+            assert!(byte >= last);
+            last = byte;
+            let decoded = byte4_to_int(byte);
+            assert!(decoded <= value);
+            assert!(((value - decoded) as f64) < value as f64 / 8.0 || value == decoded);
+        }
+        for byte in 0..=255 {
+            assert_eq!(int_to_byte4(byte4_to_int(byte)), byte);
+        }
+    }
+}
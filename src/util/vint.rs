@@ -0,0 +1,41 @@
+pub fn write_vint(mut value: u64, output: &mut Vec<u8>) {
+    while value >= 0x80 {
+        output.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+pub fn read_vint<I: Iterator<Item = u8>>(input: &mut I) -> Option<u64> {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = input.next()?;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+        shift += 7;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_vint, write_vint};
+
+    #[test]
+    fn test_simple() {
+        let values = [0, 1, 127, 128, 255, 16383, 16384, u32::MAX as u64, u64::MAX];
+        let mut output = Vec::new();
+        for &value in &values {
+            write_vint(value, &mut output);
+        }
+        assert_eq!(output[..4], [0, 1, 127, 0x80]);
+
+        let mut input = output.into_iter();
+        for &value in &values {
+            assert_eq!(read_vint(&mut input), Some(value));
+        }
+        assert!(read_vint(&mut input).is_none());
+    }
+}